@@ -5,11 +5,20 @@
 mod subscription;
 
 use actix_web::dev::{Payload, PayloadStream};
-use actix_web::{http, web, Error, FromRequest, HttpRequest};
-use async_graphql::http::StreamBody;
-use async_graphql::{IntoQueryBuilder, IntoQueryBuilderOpts, ParseRequestError, QueryBuilder};
+use actix_web::error::PayloadError;
+use actix_web::{http, web, Error, FromRequest, HttpRequest, HttpResponse, Responder};
+use async_graphql::http::{
+    incremental_http_headers, multipart_mixed_stream, HeaderMap, StreamBody,
+    MULTIPART_MIXED_CONTENT_TYPE,
+};
+use async_graphql::{
+    BatchRequest, BatchResponse, IncrementalResponse, IntoBatchRequest, IntoQueryBuilder,
+    IntoQueryBuilderOpts, ParseRequestError, QueryBuilder, QueryResponse,
+};
+use bytes::Bytes;
 use futures::channel::mpsc;
-use futures::{Future, SinkExt, StreamExt, TryFutureExt};
+use futures::future::{ready, Ready};
+use futures::{Future, SinkExt, Stream, StreamExt, TryFutureExt};
 use std::pin::Pin;
 
 pub use subscription::WSSubscription;
@@ -18,6 +27,7 @@ pub use subscription::WSSubscription;
 ///
 /// It's a wrapper of `QueryBuilder`, you can use `GQLRequest::into_inner` unwrap it to `QueryBuilder`.
 /// `async_graphql::IntoQueryBuilderOpts` allows to configure extraction process.
+/// The headers of the request are available to the resolvers with `Context::http_headers`.
 pub struct GQLRequest(QueryBuilder);
 
 impl GQLRequest {
@@ -34,35 +44,176 @@ impl FromRequest for GQLRequest {
 
     fn from_request(req: &HttpRequest, payload: &mut Payload<PayloadStream>) -> Self::Future {
         let config = req.app_data::<Self::Config>().cloned().unwrap_or_default();
-        let content_type = req
-            .headers()
-            .get(http::header::CONTENT_TYPE)
-            .and_then(|value| value.to_str().ok())
-            .map(|value| value.to_string());
-
-        let (mut tx, rx) = mpsc::channel(16);
-
-        // Because Payload is !Send, so forward it to mpsc::Sender
-        let mut payload = web::Payload(payload.take());
-        actix_rt::spawn(async move {
-            while let Some(item) = payload.next().await {
-                if tx.send(item).await.is_err() {
-                    return;
-                }
-            }
-        });
+        let http_headers = http_headers(req);
+        let (content_type, body) = forward_payload(req, payload);
 
         Box::pin(async move {
-            (content_type, StreamBody::new(rx))
+            (content_type, body)
                 .into_query_builder_opts(&config)
-                .map_ok(GQLRequest)
-                .map_err(|err| match err {
-                    ParseRequestError::TooManyFiles | ParseRequestError::TooLarge => {
-                        actix_web::error::ErrorPayloadTooLarge(err)
-                    }
-                    _ => actix_web::error::ErrorBadRequest(err),
+                .map_ok(|builder| GQLRequest(builder.http_headers(http_headers)))
+                .map_err(bad_request)
+                .await
+        })
+    }
+}
+
+/// Extractor for GraphQL batch request
+///
+/// It's a wrapper of `BatchRequest`, you can use `GQLBatchRequest::into_inner` unwrap it to `BatchRequest`.
+/// `async_graphql::IntoQueryBuilderOpts` allows to configure extraction process.
+/// The headers of the request are available to the resolvers of all the queries.
+pub struct GQLBatchRequest(BatchRequest);
+
+impl GQLBatchRequest {
+    /// Unwrap it to `BatchRequest`.
+    pub fn into_inner(self) -> BatchRequest {
+        self.0
+    }
+}
+
+impl FromRequest for GQLBatchRequest {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<GQLBatchRequest, Error>>>>;
+    type Config = IntoQueryBuilderOpts;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload<PayloadStream>) -> Self::Future {
+        let config = req.app_data::<Self::Config>().cloned().unwrap_or_default();
+        let http_headers = http_headers(req);
+        let (content_type, body) = forward_payload(req, payload);
+
+        Box::pin(async move {
+            (content_type, body)
+                .into_batch_request_opts(&config)
+                .map_ok(|batch| {
+                    GQLBatchRequest(batch.map(|builder| builder.http_headers(http_headers.clone())))
                 })
+                .map_err(bad_request)
                 .await
         })
     }
 }
+
+/// Responder for GraphQL response
+///
+/// It sends the response as JSON, with the HTTP headers set by the resolvers.
+pub struct GQLResponse(pub async_graphql::Result<QueryResponse>);
+
+impl From<async_graphql::Result<QueryResponse>> for GQLResponse {
+    fn from(resp: async_graphql::Result<QueryResponse>) -> Self {
+        GQLResponse(resp)
+    }
+}
+
+impl Responder for GQLResponse {
+    type Error = Error;
+    type Future = Ready<Result<HttpResponse, Error>>;
+
+    fn respond_to(self, _req: &HttpRequest) -> Self::Future {
+        let http_headers = match &self.0 {
+            Ok(resp) => resp.http_headers.clone(),
+            Err(_) => HeaderMap::new(),
+        };
+        ready(json_response(
+            serde_json::to_string(&async_graphql::http::GQLResponse(self.0)),
+            &http_headers,
+        ))
+    }
+}
+
+/// Responder for GraphQL batch response
+///
+/// It sends the responses as JSON, with the HTTP headers set by the resolvers of all the queries.
+pub struct GQLBatchResponse(pub BatchResponse);
+
+impl From<BatchResponse> for GQLBatchResponse {
+    fn from(resp: BatchResponse) -> Self {
+        GQLBatchResponse(resp)
+    }
+}
+
+impl Responder for GQLBatchResponse {
+    type Error = Error;
+    type Future = Ready<Result<HttpResponse, Error>>;
+
+    fn respond_to(self, _req: &HttpRequest) -> Self::Future {
+        let http_headers = self.0.http_headers();
+        ready(json_response(
+            serde_json::to_string(&async_graphql::http::GQLBatchResponse(self.0)),
+            &http_headers,
+        ))
+    }
+}
+
+fn json_response(
+    body: serde_json::Result<String>,
+    http_headers: &HeaderMap,
+) -> Result<HttpResponse, Error> {
+    let body = body.map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut builder = HttpResponse::Ok();
+    builder.content_type("application/json");
+    for (name, value) in http_headers {
+        builder.header(name, value.clone());
+    }
+    Ok(builder.body(body))
+}
+
+/// Response for a query executed with incremental delivery
+///
+/// It sends the responses of `QueryBuilder::execute_stream` as a `multipart/mixed` body, with the HTTP headers set by the
+/// resolvers of the initial response.
+pub async fn graphql_incremental_response<S>(stream: S) -> HttpResponse
+where
+    S: Stream<Item = IncrementalResponse> + 'static,
+{
+    let (http_headers, stream) = incremental_http_headers(stream).await;
+    let mut builder = HttpResponse::Ok();
+    builder.content_type(MULTIPART_MIXED_CONTENT_TYPE);
+    for (name, value) in &http_headers {
+        builder.header(name, value.clone());
+    }
+    builder.streaming(Box::pin(multipart_mixed_stream(stream).map(Ok::<_, Error>)))
+}
+
+fn http_headers(req: &HttpRequest) -> HeaderMap {
+    req.headers()
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+fn forward_payload(
+    req: &HttpRequest,
+    payload: &mut Payload<PayloadStream>,
+) -> (
+    Option<String>,
+    StreamBody<mpsc::Receiver<Result<Bytes, PayloadError>>>,
+) {
+    let content_type = req
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    let (mut tx, rx) = mpsc::channel(16);
+
+    // Because Payload is !Send, so forward it to mpsc::Sender
+    let mut payload = web::Payload(payload.take());
+    actix_rt::spawn(async move {
+        while let Some(item) = payload.next().await {
+            if tx.send(item).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    (content_type, StreamBody::new(rx))
+}
+
+fn bad_request(err: ParseRequestError) -> Error {
+    match err {
+        ParseRequestError::TooManyFiles | ParseRequestError::TooLarge => {
+            actix_web::error::ErrorPayloadTooLarge(err)
+        }
+        _ => actix_web::error::ErrorBadRequest(err),
+    }
+}
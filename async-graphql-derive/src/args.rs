@@ -1,4 +1,4 @@
-use crate::utils::{parse_guards, parse_validator, parse_value};
+use crate::utils::{get_crate_name, parse_guard_list, parse_guards, parse_validator, parse_value};
 use graphql_parser::query::Value;
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -67,6 +67,8 @@ pub struct Object {
     pub desc: Option<String>,
     pub cache_control: CacheControl,
     pub extends: bool,
+    pub guard: Option<TokenStream>,
+    pub visible: Option<syn::Path>,
 }
 
 impl Object {
@@ -76,6 +78,8 @@ impl Object {
         let mut desc = None;
         let mut cache_control = CacheControl::default();
         let mut extends = false;
+        let mut guard_list = None;
+        let mut visible = None;
 
         for arg in args {
             match arg {
@@ -104,23 +108,41 @@ impl Object {
                                 "Attribute 'desc' should be a string.",
                             ));
                         }
+                    } else if nv.path.is_ident("visible") {
+                        if let syn::Lit::Str(lit) = nv.lit {
+                            visible = Some(lit.parse()?);
+                        } else {
+                            return Err(Error::new_spanned(
+                                &nv.lit,
+                                "Attribute 'visible' should be a string.",
+                            ));
+                        }
                     }
                 }
                 NestedMeta::Meta(Meta::List(ls)) => {
                     if ls.path.is_ident("cache_control") {
                         cache_control = CacheControl::parse(&ls)?;
+                    } else if ls.path.is_ident("guard") {
+                        guard_list = Some(ls);
                     }
                 }
                 _ => {}
             }
         }
 
+        let guard = match &guard_list {
+            Some(ls) => parse_guard_list(&get_crate_name(internal), ls)?,
+            None => None,
+        };
+
         Ok(Self {
             internal,
             name,
             desc,
             cache_control,
             extends,
+            guard,
+            visible,
         })
     }
 }
@@ -216,6 +238,14 @@ pub struct Field {
     pub requires: Option<String>,
     pub is_ref: bool,
     pub guard: Option<TokenStream>,
+    pub complexity: Option<Complexity>,
+    pub visible: Option<syn::Path>,
+}
+
+#[derive(Debug)]
+pub enum Complexity {
+    Const(usize),
+    Expr(Box<syn::Expr>),
 }
 
 impl Field {
@@ -229,6 +259,8 @@ impl Field {
         let mut requires = None;
         let mut is_ref = false;
         let mut guard = None;
+        let mut complexity = None;
+        let mut visible = None;
 
         for attr in attrs {
             match attr.parse_meta()? {
@@ -291,6 +323,32 @@ impl Field {
                                             "Attribute 'requires' should be a string.",
                                         ));
                                     }
+                                } else if nv.path.is_ident("complexity") {
+                                    match &nv.lit {
+                                        syn::Lit::Int(lit) => {
+                                            complexity =
+                                                Some(Complexity::Const(lit.base10_parse()?));
+                                        }
+                                        syn::Lit::Str(lit) => {
+                                            complexity =
+                                                Some(Complexity::Expr(Box::new(lit.parse()?)));
+                                        }
+                                        _ => {
+                                            return Err(Error::new_spanned(
+                                                &nv.lit,
+                                                "Attribute 'complexity' should be an integer or a string.",
+                                            ));
+                                        }
+                                    }
+                                } else if nv.path.is_ident("visible") {
+                                    if let syn::Lit::Str(lit) = &nv.lit {
+                                        visible = Some(lit.parse()?);
+                                    } else {
+                                        return Err(Error::new_spanned(
+                                            &nv.lit,
+                                            "Attribute 'visible' should be a string.",
+                                        ));
+                                    }
                                 }
                             }
                             NestedMeta::Meta(Meta::List(ls)) => {
@@ -316,6 +374,8 @@ impl Field {
             requires,
             is_ref,
             guard,
+            complexity,
+            visible,
         }))
     }
 }
@@ -524,6 +584,7 @@ pub struct InputObject {
     pub internal: bool,
     pub name: Option<String>,
     pub desc: Option<String>,
+    pub validator: Option<syn::Path>,
 }
 
 impl InputObject {
@@ -531,6 +592,7 @@ impl InputObject {
         let mut internal = false;
         let mut name = None;
         let mut desc = None;
+        let mut validator = None;
 
         for arg in args {
             match arg {
@@ -556,6 +618,15 @@ impl InputObject {
                                 "Attribute 'desc' should be a string.",
                             ));
                         }
+                    } else if nv.path.is_ident("validator") {
+                        if let syn::Lit::Str(lit) = nv.lit {
+                            validator = Some(lit.parse()?);
+                        } else {
+                            return Err(Error::new_spanned(
+                                &nv.lit,
+                                "Attribute 'validator' should be a string.",
+                            ));
+                        }
                     }
                 }
                 _ => {}
@@ -566,6 +637,7 @@ impl InputObject {
             internal,
             name,
             desc,
+            validator,
         })
     }
 }
@@ -672,6 +744,8 @@ pub struct InterfaceField {
     pub external: bool,
     pub provides: Option<String>,
     pub requires: Option<String>,
+    pub complexity: Option<Complexity>,
+    pub visible: Option<syn::Path>,
 }
 
 impl InterfaceField {
@@ -684,6 +758,8 @@ impl InterfaceField {
         let mut external = false;
         let mut provides = None;
         let mut requires = None;
+        let mut complexity = None;
+        let mut visible = None;
 
         for meta in &ls.nested {
             match meta {
@@ -749,6 +825,30 @@ impl InterfaceField {
                                 "Attribute 'requires' should be a string.",
                             ));
                         }
+                    } else if nv.path.is_ident("complexity") {
+                        match &nv.lit {
+                            syn::Lit::Int(lit) => {
+                                complexity = Some(Complexity::Const(lit.base10_parse()?));
+                            }
+                            syn::Lit::Str(lit) => {
+                                complexity = Some(Complexity::Expr(Box::new(lit.parse()?)));
+                            }
+                            _ => {
+                                return Err(Error::new_spanned(
+                                    &nv.lit,
+                                    "Attribute 'complexity' should be an integer or a string.",
+                                ));
+                            }
+                        }
+                    } else if nv.path.is_ident("visible") {
+                        if let syn::Lit::Str(lit) = &nv.lit {
+                            visible = Some(lit.parse()?);
+                        } else {
+                            return Err(Error::new_spanned(
+                                &nv.lit,
+                                "Attribute 'visible' should be a string.",
+                            ));
+                        }
                     }
                 }
                 NestedMeta::Meta(Meta::List(ls)) if ls.path.is_ident("arg") => {
@@ -775,6 +875,8 @@ impl InterfaceField {
             external,
             requires,
             provides,
+            complexity,
+            visible,
         })
     }
 }
@@ -786,6 +888,8 @@ pub struct Interface {
     pub desc: Option<String>,
     pub fields: Vec<InterfaceField>,
     pub extends: bool,
+    pub guard: Option<TokenStream>,
+    pub visible: Option<syn::Path>,
 }
 
 impl Interface {
@@ -795,6 +899,8 @@ impl Interface {
         let mut desc = None;
         let mut fields = Vec::new();
         let mut extends = false;
+        let mut guard_list = None;
+        let mut visible = None;
 
         for arg in args {
             match arg {
@@ -823,21 +929,40 @@ impl Interface {
                                 "Attribute 'desc' should be a string.",
                             ));
                         }
+                    } else if nv.path.is_ident("visible") {
+                        if let syn::Lit::Str(lit) = nv.lit {
+                            visible = Some(lit.parse()?);
+                        } else {
+                            return Err(Error::new_spanned(
+                                &nv.lit,
+                                "Attribute 'visible' should be a string.",
+                            ));
+                        }
                     }
                 }
                 NestedMeta::Meta(Meta::List(ls)) if ls.path.is_ident("field") => {
                     fields.push(InterfaceField::parse(&ls)?);
                 }
+                NestedMeta::Meta(Meta::List(ls)) if ls.path.is_ident("guard") => {
+                    guard_list = Some(ls);
+                }
                 _ => {}
             }
         }
 
+        let guard = match &guard_list {
+            Some(ls) => parse_guard_list(&get_crate_name(internal), ls)?,
+            None => None,
+        };
+
         Ok(Self {
             internal,
             name,
             desc,
             fields,
             extends,
+            guard,
+            visible,
         })
     }
 }
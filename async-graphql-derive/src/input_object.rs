@@ -50,6 +50,9 @@ pub fn generate(object_args: &args::InputObject, input: &DeriveInput) -> Result<
         .unwrap_or_else(|| quote! {None});
 
     let mut get_fields = Vec::new();
+    let mut field_errors = Vec::new();
+    let mut validate_fields = Vec::new();
+    let mut validate_field_errors = Vec::new();
     let mut fields = Vec::new();
     let mut schema_fields = Vec::new();
 
@@ -74,6 +77,14 @@ pub fn generate(object_args: &args::InputObject, input: &DeriveInput) -> Result<
                 quote! {Some(#s)}
             })
             .unwrap_or_else(|| quote! {None});
+        let parsed_default = field_args
+            .default
+            .as_ref()
+            .map(|v| {
+                let repr = build_value_repr(&crate_name, v);
+                quote! {Some(#repr)}
+            })
+            .unwrap_or_else(|| quote! {None});
 
         if let Some(default) = &field_args.default {
             let default_repr = build_value_repr(&crate_name, default);
@@ -94,6 +105,32 @@ pub fn generate(object_args: &args::InputObject, input: &DeriveInput) -> Result<
             });
         }
 
+        field_errors.push(quote! {
+            if let Some(value) = obj.get(#name) {
+                if <#ty as #crate_name::InputValueType>::parse(value).is_none() {
+                    if let Some(reason) = <#ty as #crate_name::InputValueType>::parse_error(value) {
+                        return Some(format!("field \"{}\": {}", #name, reason));
+                    }
+                }
+            }
+        });
+
+        // The validators also run when the value is parsed, the validation of the query does not check the variables.
+        let validate_field = |on_invalid| {
+            quote! {
+                if let Some(value) = obj.get(#name) {
+                    let validator: Option<std::sync::Arc<dyn #crate_name::validators::InputValueValidator>> = #validator;
+                    if let Some(reason) = validator.and_then(|validator| validator.is_valid(value)) {
+                        #on_invalid
+                    }
+                }
+            }
+        };
+        validate_fields.push(validate_field(quote! { return None; }));
+        validate_field_errors.push(validate_field(quote! {
+            return Some(format!("field \"{}\": {}", #name, reason));
+        }));
+
         fields.push(ident);
         schema_fields.push(quote! {
             fields.insert(#name.to_string(), #crate_name::registry::InputValue {
@@ -101,11 +138,34 @@ pub fn generate(object_args: &args::InputObject, input: &DeriveInput) -> Result<
                 description: #desc,
                 ty: <#ty as #crate_name::Type>::create_type_info(registry),
                 default_value: #default,
+                parsed_default_value: #parsed_default,
                 validator: #validator,
             });
         })
     }
 
+    let (validate, validate_error) = match &object_args.validator {
+        Some(validator) => (
+            quote! {
+                if #validator(&res).is_err() {
+                    return None;
+                }
+            },
+            quote! {
+                fn parse_fields(
+                    obj: &std::collections::BTreeMap<String, #crate_name::Value>,
+                ) -> Option<#ident> {
+                    #(#get_fields)*
+                    Some(#ident { #(#fields),* })
+                }
+                if let Some(Err(reason)) = parse_fields(obj).as_ref().map(#validator) {
+                    return Some(reason);
+                }
+            },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
     let expanded = quote! {
         #new_struct
 
@@ -133,11 +193,23 @@ pub fn generate(object_args: &args::InputObject, input: &DeriveInput) -> Result<
 
                 if let #crate_name::Value::Object(obj) = value {
                     #(#get_fields)*
-                    Some(Self { #(#fields),* })
+                    #(#validate_fields)*
+                    let res = Self { #(#fields),* };
+                    #validate
+                    Some(res)
                 } else {
                     None
                 }
             }
+
+            fn parse_error(value: &#crate_name::Value) -> Option<String> {
+                if let #crate_name::Value::Object(obj) = value {
+                    #(#field_errors)*
+                    #(#validate_field_errors)*
+                    #validate_error
+                }
+                None
+            }
         }
 
         impl #crate_name::InputObjectType for #ident {}
@@ -1,7 +1,10 @@
 use crate::args;
 use crate::args::{InterfaceField, InterfaceFieldArgument};
 use crate::output_type::OutputType;
-use crate::utils::{build_value_repr, check_reserved_name, get_crate_name};
+use crate::utils::{
+    build_value_repr, check_reserved_name, generate_complexity, generate_visible, get_crate_name,
+    guard_uses_args,
+};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
@@ -93,6 +96,8 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
         external,
         provides,
         requires,
+        complexity,
+        visible,
     } in &interface_args.fields
     {
         let method_name = Ident::new(name, Span::call_site());
@@ -101,7 +106,9 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
         let mut use_params = Vec::new();
         let mut decl_params = Vec::new();
         let mut get_params = Vec::new();
+        let mut arg_idents = Vec::new();
         let mut schema_args = Vec::new();
+        let mut complexity_params = Vec::new();
         let requires = match &requires {
             Some(requires) => quote! { Some(#requires) },
             None => quote! { None },
@@ -125,6 +132,7 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
             let name = name.to_camel_case();
             decl_params.push(quote! { #ident: #ty });
             use_params.push(quote! { #ident });
+            arg_idents.push(ident.clone());
 
             let param_default = match &default {
                 Some(default) => {
@@ -136,6 +144,9 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
             get_params.push(quote! {
                 let #ident: #ty = ctx.param_value(#name, ctx.position, #param_default)?;
             });
+            complexity_params.push(quote! {
+                let #ident: #ty = #crate_name::registry::complexity_arg(complexity_args, #name, #param_default)?;
+            });
 
             let desc = desc
                 .as_ref()
@@ -148,12 +159,20 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
                     quote! {Some(#s)}
                 })
                 .unwrap_or_else(|| quote! {None});
+            let parsed_default = default
+                .as_ref()
+                .map(|v| {
+                    let repr = build_value_repr(&crate_name, v);
+                    quote! {Some(#repr)}
+                })
+                .unwrap_or_else(|| quote! {None});
             schema_args.push(quote! {
                 args.insert(#name, #crate_name::registry::InputValue {
                     name: #name,
                     description: #desc,
                     ty: <#ty as #crate_name::Type>::create_type_info(registry),
                     default_value: #schema_default,
+                    parsed_default_value: #parsed_default,
                     validator: None,
                 });
             });
@@ -190,6 +209,8 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
             }
         });
 
+        let compute_complexity = generate_complexity(&crate_name, complexity, &complexity_params);
+        let visible = generate_visible(visible);
         schema_fields.push(quote! {
             fields.insert(#name.to_string(), #crate_name::registry::Field {
                 name: #name.to_string(),
@@ -205,6 +226,8 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
                 external: #external,
                 provides: #provides,
                 requires: #requires,
+                compute_complexity: #compute_complexity,
+                visible: #visible,
             });
         });
 
@@ -213,9 +236,26 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
                 map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?
         };
 
+        let defer_guard = interface_args
+            .guard
+            .as_ref()
+            .map(|guard| guard_uses_args(guard, &arg_idents))
+            .unwrap_or_default();
+        let guard = interface_args.guard.as_ref().map(|guard| quote! {
+            #guard.check(ctx).await
+                .map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?;
+        });
+        let (guard, deferred_guard) = if defer_guard {
+            (None, guard)
+        } else {
+            (guard, None)
+        };
+
         resolvers.push(quote! {
             if ctx.name.as_str() == #name {
+                #guard
                 #(#get_params)*
+                #deferred_guard
                 let ctx_obj = ctx.with_selection_set(&ctx.selection_set);
                 return #crate_name::OutputValueType::resolve(&#resolve_obj, &ctx_obj, ctx.position).await;
             }
@@ -232,6 +272,7 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
         }
     };
 
+    let visible = generate_visible(&interface_args.visible);
     let expanded = quote! {
         #(#attrs)*
         #vis enum #ident #generics { #(#enum_items),* }
@@ -270,6 +311,7 @@ pub fn generate(interface_args: &args::Interface, input: &DeriveInput) -> Result
                         },
                         extends: #extends,
                         keys: None,
+                        visible: #visible,
                     }
                 })
             }
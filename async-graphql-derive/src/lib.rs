@@ -166,6 +166,10 @@ pub fn Scalar(args: TokenStream, input: TokenStream) -> TokenStream {
             fn parse(value: &#crate_name::Value) -> Option<Self> {
                 <#self_ty as #crate_name::ScalarType>::parse(value)
             }
+
+            fn parse_error(value: &#crate_name::Value) -> Option<String> {
+                <#self_ty as #crate_name::ScalarType>::parse_error(value)
+            }
         }
 
         #[allow(clippy::ptr_arg)]
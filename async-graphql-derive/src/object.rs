@@ -1,6 +1,9 @@
 use crate::args;
 use crate::output_type::OutputType;
-use crate::utils::{build_value_repr, check_reserved_name, get_crate_name};
+use crate::utils::{
+    build_value_repr, check_reserved_name, generate_complexity, generate_visible, get_crate_name,
+    guard_uses_args, merge_guards,
+};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -281,6 +284,8 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                 let mut schema_args = Vec::new();
                 let mut use_params = Vec::new();
                 let mut get_params = Vec::new();
+                let mut arg_idents = Vec::new();
+                let mut complexity_params = Vec::new();
 
                 for (
                     ident,
@@ -307,6 +312,13 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                             quote! {Some(#s)}
                         })
                         .unwrap_or_else(|| quote! {None});
+                    let parsed_default = default
+                        .as_ref()
+                        .map(|v| {
+                            let repr = build_value_repr(&crate_name, v);
+                            quote! {Some(#repr)}
+                        })
+                        .unwrap_or_else(|| quote! {None});
 
                     schema_args.push(quote! {
                         args.insert(#name, #crate_name::registry::InputValue {
@@ -314,11 +326,13 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                             description: #desc,
                             ty: <#ty as #crate_name::Type>::create_type_info(registry),
                             default_value: #schema_default,
+                            parsed_default_value: #parsed_default,
                             validator: #validator,
                         });
                     });
 
                     use_params.push(quote! { #ident });
+                    arg_idents.push(ident.ident.clone());
 
                     let default = match &default {
                         Some(default) => {
@@ -331,8 +345,15 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     get_params.push(quote! {
                         let #ident: #ty = ctx.param_value(#name, ctx.position, #default)?;
                     });
+                    complexity_params.push(quote! {
+                        let #ident: #ty = #crate_name::registry::complexity_arg(complexity_args, #name, #default)?;
+                    });
                 }
 
+                let compute_complexity =
+                    generate_complexity(&crate_name, &field.complexity, &complexity_params);
+                let visible = generate_visible(&field.visible);
+
                 let schema_ty = ty.value_type();
 
                 schema_fields.push(quote! {
@@ -350,6 +371,8 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                         external: #external,
                         provides: #provides,
                         requires: #requires,
+                        compute_complexity: #compute_complexity,
+                        visible: #visible,
                     });
                 });
 
@@ -375,18 +398,27 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     }
                 };
 
-                let guard = field
-                    .guard
-                    .map(|guard| quote! {
-                        #guard.check(ctx).await
-                            .map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?;
-                    });
+                let guard = merge_guards(&crate_name, &object_args.guard, field.guard);
+                let defer_guard = guard
+                    .as_ref()
+                    .map(|guard| guard_uses_args(guard, &arg_idents))
+                    .unwrap_or_default();
+                let guard = guard.map(|guard| quote! {
+                    #guard.check(ctx).await
+                        .map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?;
+                });
+                let (guard, deferred_guard) = if defer_guard {
+                    (None, guard)
+                } else {
+                    (guard, None)
+                };
 
                 resolvers.push(quote! {
                     if ctx.name.as_str() == #field_name {
                         use #crate_name::OutputValueType;
                         #guard
                         #(#get_params)*
+                        #deferred_guard
                         let ctx_obj = ctx.with_selection_set(&ctx.selection_set);
                         return OutputValueType::resolve(&#resolve_obj, &ctx_obj, ctx.position).await;
                     }
@@ -411,6 +443,7 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
         }
     }
 
+    let visible = generate_visible(&object_args.visible);
     let cache_control = {
         let public = object_args.cache_control.public;
         let max_age = object_args.cache_control.max_age;
@@ -445,6 +478,7 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     cache_control: #cache_control,
                     extends: #extends,
                     keys: None,
+                    visible: #visible,
                 });
                 #(#create_entity_types)*
                 #(#add_keys)*
@@ -1,5 +1,7 @@
 use crate::args;
-use crate::utils::{check_reserved_name, get_crate_name};
+use crate::utils::{
+    check_reserved_name, generate_complexity, generate_visible, get_crate_name, merge_guards,
+};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -62,6 +64,8 @@ pub fn generate(object_args: &args::Object, input: &mut DeriveInput) -> Result<T
                     Some(provides) => quote! { Some(#provides) },
                     None => quote! { None },
                 };
+                let compute_complexity = generate_complexity(&crate_name, &field.complexity, &[]);
+                let visible = generate_visible(&field.visible);
                 let vis = &item.vis;
                 let ty = &item.ty;
 
@@ -87,12 +91,13 @@ pub fn generate(object_args: &args::Object, input: &mut DeriveInput) -> Result<T
                         external: #external,
                         provides: #provides,
                         requires: #requires,
+                        compute_complexity: #compute_complexity,
+                        visible: #visible,
                     });
                 });
 
                 let ident = &item.ident;
-                let guard = field
-                    .guard
+                let guard = merge_guards(&crate_name, &object_args.guard, field.guard)
                     .map(|guard| quote! { #guard.check(ctx).await.map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?; });
 
                 if field.is_ref {
@@ -132,6 +137,7 @@ pub fn generate(object_args: &args::Object, input: &mut DeriveInput) -> Result<T
         }
     }
 
+    let visible = generate_visible(&object_args.visible);
     let cache_control = {
         let public = object_args.cache_control.public;
         let max_age = object_args.cache_control.max_age;
@@ -167,6 +173,7 @@ pub fn generate(object_args: &args::Object, input: &mut DeriveInput) -> Result<T
                     cache_control: #cache_control,
                     extends: #extends,
                     keys: None,
+                    visible: #visible,
                 })
             }
         }
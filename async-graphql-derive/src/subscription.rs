@@ -1,6 +1,9 @@
 use crate::args;
 use crate::output_type::OutputType;
-use crate::utils::{build_value_repr, check_reserved_name, get_crate_name};
+use crate::utils::{
+    build_value_repr, check_reserved_name, generate_complexity, generate_visible, get_crate_name,
+    guard_uses_args, merge_guards,
+};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -53,6 +56,7 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     .as_ref()
                     .map(|s| quote! {Some(#s)})
                     .unwrap_or_else(|| quote! {None});
+                let visible = generate_visible(&field.visible);
                 let field_deprecation = field
                     .deprecation
                     .as_ref()
@@ -133,6 +137,8 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                 let mut schema_args = Vec::new();
                 let mut use_params = Vec::new();
                 let mut get_params = Vec::new();
+                let mut arg_idents = Vec::new();
+                let mut complexity_params = Vec::new();
 
                 for (
                     ident,
@@ -159,6 +165,13 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                             quote! {Some(#s)}
                         })
                         .unwrap_or_else(|| quote! {None});
+                    let parsed_default = default
+                        .as_ref()
+                        .map(|v| {
+                            let repr = build_value_repr(&crate_name, v);
+                            quote! {Some(#repr)}
+                        })
+                        .unwrap_or_else(|| quote! {None});
 
                     schema_args.push(quote! {
                         args.insert(#name, #crate_name::registry::InputValue {
@@ -166,11 +179,13 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                             description: #desc,
                             ty: <#ty as #crate_name::Type>::create_type_info(registry),
                             default_value: #schema_default,
+                            parsed_default_value: #parsed_default,
                             validator: #validator,
                         });
                     });
 
                     use_params.push(quote! { #ident });
+                    arg_idents.push(ident.ident.clone());
 
                     let default = match &default {
                         Some(default) => {
@@ -183,8 +198,13 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     get_params.push(quote! {
                         let #ident: #ty = ctx.param_value(#name, ctx.position, #default)?;
                     });
+                    complexity_params.push(quote! {
+                        let #ident: #ty = #crate_name::registry::complexity_arg(complexity_args, #name, #default)?;
+                    });
                 }
 
+                let compute_complexity =
+                    generate_complexity(&crate_name, &field.complexity, &complexity_params);
                 let res_ty = ty.value_type();
                 let stream_ty = if let Type::ImplTrait(TypeImplTrait { bounds, .. }) = &res_ty {
                     quote! { #bounds }
@@ -221,7 +241,9 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                         cache_control: Default::default(),
                         external: false,
                         requires: None,
+                        compute_complexity: #compute_complexity,
                         provides: None,
+                        visible: #visible,
                     });
                 });
 
@@ -230,9 +252,19 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                         map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?)
                 };
 
-                let guard = field.guard.map(|guard| quote! {
+                let guard = merge_guards(&crate_name, &object_args.guard, field.guard);
+                let defer_guard = guard
+                    .as_ref()
+                    .map(|guard| guard_uses_args(guard, &arg_idents))
+                    .unwrap_or_default();
+                let guard = guard.map(|guard| quote! {
                     #guard.check(ctx).await.map_err(|err| err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json()))?;
                 });
+                let (guard, deferred_guard) = if defer_guard {
+                    (None, guard)
+                } else {
+                    (guard, None)
+                };
 
                 create_stream.push(quote! {
                     if ctx.name.as_str() == #field_name {
@@ -242,6 +274,7 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
 
                         let field_name = std::sync::Arc::new(ctx.result_name().to_string());
                         #(#get_params)*
+                        #deferred_guard
                         let field_selection_set = std::sync::Arc::new(ctx.selection_set.clone());
                         let schema = schema.clone();
                         let pos = ctx.position;
@@ -294,6 +327,7 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
         }
     }
 
+    let visible = generate_visible(&object_args.visible);
     let expanded = quote! {
         #item_impl
 
@@ -315,6 +349,7 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     cache_control: Default::default(),
                     extends: false,
                     keys: None,
+                    visible: #visible,
                 })
             }
         }
@@ -1,8 +1,10 @@
+use crate::args::Complexity;
 use graphql_parser::parse_query;
 use graphql_parser::query::{Definition, OperationDefinition, ParseError, Query, Value};
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::quote;
-use syn::{Error, Expr, Ident, Lit, Meta, MetaList, NestedMeta, Result};
+use std::convert::TryFrom;
+use syn::{BinOp, Error, Expr, Ident, Lit, Meta, MetaList, NestedMeta, Result};
 
 pub fn get_crate_name(internal: bool) -> TokenStream {
     if internal {
@@ -33,7 +35,14 @@ pub fn build_value_repr(crate_name: &TokenStream, value: &Value) -> TokenStream
         Value::Variable(_) => unreachable!(),
         Value::Int(n) => {
             let n = n.as_i64().unwrap();
-            quote! { #crate_name::Value::Int((#n as i32).into()) }
+            match i32::try_from(n) {
+                Ok(n) => quote! { #crate_name::Value::Int(#n.into()) },
+                // Like the variables, the integers outside of the range of `i32` are passed as strings.
+                Err(_) => {
+                    let s = n.to_string();
+                    quote! { #crate_name::Value::String(#s.to_string()) }
+                }
+            }
         }
         Value::Float(n) => {
             quote! { #crate_name::Value::Float(#n) }
@@ -182,49 +191,169 @@ pub fn parse_validator(crate_name: &TokenStream, args: &MetaList) -> Result<Toke
     Ok(quote! {None})
 }
 
+fn parse_guard(crate_name: &TokenStream, item: &NestedMeta) -> Result<TokenStream> {
+    match item {
+        NestedMeta::Meta(Meta::List(ls)) if ls.path.is_ident("and") || ls.path.is_ident("or") => {
+            let combinator = if ls.path.is_ident("and") {
+                quote! { and }
+            } else {
+                quote! { or }
+            };
+            let mut guards = None;
+            for item in &ls.nested {
+                let guard = parse_guard(crate_name, item)?;
+                guards = Some(match guards {
+                    Some(guards) => {
+                        quote! { #crate_name::guard::GuardExt::#combinator(#guards, #guard) }
+                    }
+                    None => guard,
+                });
+            }
+            guards.ok_or_else(|| Error::new_spanned(ls, "At least one guard is required"))
+        }
+        NestedMeta::Meta(Meta::List(ls)) if ls.path.is_ident("not") => {
+            if ls.nested.len() != 1 {
+                return Err(Error::new_spanned(ls, "Exactly one guard is required"));
+            }
+            let guard = parse_guard(crate_name, &ls.nested[0])?;
+            Ok(quote! { #crate_name::guard::GuardExt::not(#guard) })
+        }
+        NestedMeta::Meta(Meta::List(ls)) => {
+            let ty = &ls.path;
+            let mut params = Vec::new();
+            for attr in &ls.nested {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = attr {
+                    let name = &nv.path;
+                    if let Lit::Str(value) = &nv.lit {
+                        let expr = syn::parse_str::<Expr>(&value.value())?;
+                        params.push(quote! { #name: #expr.into() });
+                    } else {
+                        return Err(Error::new_spanned(&nv.lit, "Value must be string literal"));
+                    }
+                } else {
+                    return Err(Error::new_spanned(attr, "Invalid property for guard"));
+                }
+            }
+            Ok(quote! { #ty { #(#params),* } })
+        }
+        NestedMeta::Meta(Meta::Path(path)) => Ok(quote! { #path }),
+        _ => Err(Error::new_spanned(item, "Invalid guard")),
+    }
+}
+
+pub fn parse_guard_list(crate_name: &TokenStream, ls: &MetaList) -> Result<Option<TokenStream>> {
+    let mut guards = None;
+
+    for item in &ls.nested {
+        let guard = parse_guard(crate_name, item)?;
+        if guards.is_none() {
+            guards = Some(guard);
+        } else {
+            guards = Some(quote! { #crate_name::guard::GuardExt::and(#guard, #guards) });
+        }
+    }
+
+    Ok(guards)
+}
+
 pub fn parse_guards(crate_name: &TokenStream, args: &MetaList) -> Result<Option<TokenStream>> {
     for arg in &args.nested {
         if let NestedMeta::Meta(Meta::List(ls)) = arg {
             if ls.path.is_ident("guard") {
-                let mut guards = None;
+                return parse_guard_list(crate_name, ls);
+            }
+        }
+    }
 
-                for item in &ls.nested {
-                    if let NestedMeta::Meta(Meta::List(ls)) = item {
-                        let ty = &ls.path;
-                        let mut params = Vec::new();
-                        for attr in &ls.nested {
-                            if let NestedMeta::Meta(Meta::NameValue(nv)) = attr {
-                                let name = &nv.path;
-                                if let Lit::Str(value) = &nv.lit {
-                                    let expr = syn::parse_str::<Expr>(&value.value())?;
-                                    params.push(quote! { #name: #expr.into() });
-                                } else {
-                                    return Err(Error::new_spanned(
-                                        &nv.lit,
-                                        "Value must be string literal",
-                                    ));
-                                }
-                            } else {
-                                return Err(Error::new_spanned(attr, "Invalid property for guard"));
-                            }
-                        }
+    Ok(None)
+}
 
-                        let guard = quote! { #ty { #(#params),* } };
-                        if guards.is_none() {
-                            guards = Some(guard);
-                        } else {
-                            guards =
-                                Some(quote! { #crate_name::guard::GuardExt::and(#guard, #guards) });
-                        }
-                    } else {
-                        return Err(Error::new_spanned(item, "Invalid guard"));
+/// Combine the guard of the type with the guard of a field, the guard of the type is checked first.
+pub fn merge_guards(
+    crate_name: &TokenStream,
+    type_guard: &Option<TokenStream>,
+    field_guard: Option<TokenStream>,
+) -> Option<TokenStream> {
+    match (type_guard, field_guard) {
+        (Some(type_guard), Some(field_guard)) => {
+            Some(quote! { #crate_name::guard::GuardExt::and(#type_guard, #field_guard) })
+        }
+        (Some(type_guard), None) => Some(type_guard.clone()),
+        (None, field_guard) => field_guard,
+    }
+}
+
+/// Returns `true` if the guard refers to one of the arguments, it must then be checked after they are parsed.
+pub fn guard_uses_args(guard: &TokenStream, args: &[Ident]) -> bool {
+    guard.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => args.contains(&ident),
+        TokenTree::Group(group) => guard_uses_args(&group.stream(), args),
+        _ => false,
+    })
+}
+
+pub fn generate_visible(visible: &Option<syn::Path>) -> TokenStream {
+    match visible {
+        Some(visible) => quote! { Some(#visible) },
+        None => quote! { None },
+    }
+}
+
+pub fn generate_complexity(
+    crate_name: &TokenStream,
+    complexity: &Option<Complexity>,
+    parse_args: &[TokenStream],
+) -> TokenStream {
+    match complexity {
+        Some(Complexity::Const(n)) => {
+            quote! { Some(#crate_name::registry::ComplexityType::Const(#n)) }
+        }
+        Some(Complexity::Expr(expr)) => {
+            let expr = checked_expr(crate_name, expr);
+            quote! {
+                {
+                    #[allow(unused_variables)]
+                    fn compute_complexity(
+                        complexity_args: &[(String, #crate_name::Value)],
+                        child_complexity: usize,
+                    ) -> #crate_name::FieldResult<usize> {
+                        #(#parse_args)*
+                        let complexity: usize = #expr;
+                        Ok(complexity)
                     }
+                    Some(#crate_name::registry::ComplexityType::Fn(compute_complexity))
                 }
-
-                return Ok(guards);
             }
         }
+        None => quote! { None },
     }
+}
 
-    Ok(None)
+/// Rewrite the arithmetic operators of a complexity expression, so that an overflow is reported as an error.
+fn checked_expr(crate_name: &TokenStream, expr: &Expr) -> TokenStream {
+    match expr {
+        Expr::Binary(binary) => {
+            let op = match binary.op {
+                BinOp::Add(_) => quote! { checked_add },
+                BinOp::Sub(_) => quote! { checked_sub },
+                BinOp::Mul(_) => quote! { checked_mul },
+                BinOp::Div(_) => quote! { checked_div },
+                BinOp::Rem(_) => quote! { checked_rem },
+                _ => return quote! { #expr },
+            };
+            let left = checked_expr(crate_name, &binary.left);
+            let right = checked_expr(crate_name, &binary.right);
+            quote! { #crate_name::registry::ComplexityArith::#op(#left, #right)? }
+        }
+        Expr::Paren(paren) => {
+            let expr = checked_expr(crate_name, &paren.expr);
+            quote! { (#expr) }
+        }
+        Expr::Cast(cast) => {
+            let expr = checked_expr(crate_name, &cast.expr);
+            let ty = &cast.ty;
+            quote! { (#expr) as #ty }
+        }
+        _ => quote! { #expr },
+    }
 }
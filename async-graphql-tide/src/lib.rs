@@ -4,14 +4,24 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::needless_doctest_main)]
 
-use async_graphql::http::GQLResponse;
+use async_graphql::futures::task::{Context, Poll};
+use async_graphql::futures::{Stream, StreamExt, TryStreamExt};
+use async_graphql::http::{
+    incremental_http_headers, multipart_mixed_stream, GQLBatchResponse, GQLResponse, HeaderMap,
+    HeaderName, HeaderValue, MULTIPART_MIXED_CONTENT_TYPE,
+};
 use async_graphql::{
-    IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType, QueryBuilder, Schema, SubscriptionType,
+    IncrementalResponse, IntoBatchRequest, IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType,
+    QueryBuilder, Schema, SubscriptionType,
 };
+use std::pin::Pin;
+use std::sync::Mutex;
 use tide::{http::headers, Request, Response, Status, StatusCode};
 
 /// GraphQL request handler
 ///
+/// The headers of the request are available to the resolvers with `Context::http_headers`,
+/// and the headers set by the resolvers are copied into the response.
 ///
 /// # Examples
 /// *[Full Example](<https://github.com/async-graphql/examples/blob/master/tide/starwars/src/main.rs>)*
@@ -75,19 +85,167 @@ where
     let content_type = req
         .header(&headers::CONTENT_TYPE)
         .and_then(|values| values.first().map(|value| value.to_string()));
+    let http_headers = http_headers(&req);
 
     let mut query_builder = (content_type, req)
         .into_query_builder_opts(&opts)
         .await
         .status(StatusCode::BadRequest)?;
 
-    query_builder = query_builder_configuration(query_builder);
+    query_builder = query_builder_configuration(query_builder.http_headers(http_headers));
 
     let query_response = query_builder.execute(&schema).await;
 
+    let response_http_headers = match &query_response {
+        Ok(resp) => resp.http_headers.clone(),
+        Err(_) => HeaderMap::new(),
+    };
+
     let gql_response = GQLResponse(query_response);
 
     let resp = Response::new(StatusCode::Ok).body_json(&gql_response)?;
 
-    Ok(resp)
+    Ok(add_http_headers(resp, &response_http_headers))
+}
+
+/// GraphQL batch request handler
+///
+/// Similar to graphql, but it also accepts an array of queries, and executes them one after another.
+/// `query_builder_configuration` is applied to each query.
+pub async fn graphql_batch<Query, Mutation, Subscription, TideState, F>(
+    req: Request<TideState>,
+    schema: Schema<Query, Mutation, Subscription>,
+    query_builder_configuration: F,
+) -> tide::Result<Response>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    TideState: Send + Sync + 'static,
+    F: Fn(QueryBuilder) -> QueryBuilder,
+{
+    graphql_batch_opts(req, schema, query_builder_configuration, Default::default()).await
+}
+
+/// Similar to graphql_batch, but you can set the options `IntoQueryBuilderOpts`.
+pub async fn graphql_batch_opts<Query, Mutation, Subscription, TideState, F>(
+    req: Request<TideState>,
+    schema: Schema<Query, Mutation, Subscription>,
+    query_builder_configuration: F,
+    opts: IntoQueryBuilderOpts,
+) -> tide::Result<Response>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    TideState: Send + Sync + 'static,
+    F: Fn(QueryBuilder) -> QueryBuilder,
+{
+    let content_type = req
+        .header(&headers::CONTENT_TYPE)
+        .and_then(|values| values.first().map(|value| value.to_string()));
+    let http_headers = http_headers(&req);
+
+    let batch = (content_type, req)
+        .into_batch_request_opts(&opts)
+        .await
+        .status(StatusCode::BadRequest)?
+        .map(|query_builder| {
+            query_builder_configuration(query_builder.http_headers(http_headers.clone()))
+        });
+
+    let batch_response = batch.execute(&schema).await;
+
+    let response_http_headers = batch_response.http_headers();
+
+    let gql_response = GQLBatchResponse(batch_response);
+
+    let resp = Response::new(StatusCode::Ok).body_json(&gql_response)?;
+
+    Ok(add_http_headers(resp, &response_http_headers))
+}
+
+fn http_headers<TideState>(req: &Request<TideState>) -> HeaderMap {
+    let mut http_headers = HeaderMap::new();
+    for (name, values) in req.iter() {
+        if let Ok(name) = HeaderName::from_bytes(name.as_str().as_bytes()) {
+            for value in values {
+                if let Ok(value) = HeaderValue::from_str(value.as_str()) {
+                    http_headers.append(&name, value);
+                }
+            }
+        }
+    }
+    http_headers
+}
+
+fn add_http_headers(mut resp: Response, http_headers: &HeaderMap) -> Response {
+    for (name, value) in http_headers {
+        if let (Ok(name), Ok(value)) =
+            (name.as_str().parse::<headers::HeaderName>(), value.to_str())
+        {
+            resp = resp.append_header(name, value);
+        }
+    }
+    resp
+}
+
+/// Response for a query executed with incremental delivery
+///
+/// It sends the responses of `QueryBuilder::execute_stream` as a `multipart/mixed` body, with the HTTP headers set by the
+/// resolvers of the initial response.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_graphql::*;
+/// use async_std::task;
+/// use tide::Request;
+///
+/// struct QueryRoot;
+/// #[Object]
+/// impl QueryRoot {
+///     #[field]
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// fn main() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+///     task::block_on(async {
+///         let mut app = tide::new();
+///         app.at("/").post(|req: Request<()>| async move {
+///             let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish();
+///             let builder = QueryBuilder::new("{ ... @defer { value } }");
+///             Ok(async_graphql_tide::graphql_incremental_response(builder.execute_stream(&schema)).await)
+///         });
+///         app.listen("0.0.0.0:8000").await?;
+///
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn graphql_incremental_response<S>(stream: S) -> Response
+where
+    S: Stream<Item = IncrementalResponse> + Send + 'static,
+{
+    let (http_headers, stream) = incremental_http_headers(stream).await;
+    let body = SyncStream(Mutex::new(Box::pin(
+        multipart_mixed_stream(stream).map(Ok::<_, std::io::Error>),
+    )));
+    let resp = Response::new(StatusCode::Ok)
+        .body(body.into_async_read())
+        .set_header(headers::CONTENT_TYPE, MULTIPART_MIXED_CONTENT_TYPE);
+    add_http_headers(resp, &http_headers)
+}
+
+// The body of a tide response must be `Sync`.
+struct SyncStream<S>(Mutex<Pin<Box<S>>>);
+
+impl<S: Stream> Stream for SyncStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.lock().unwrap().as_mut().poll_next(cx)
+    }
 }
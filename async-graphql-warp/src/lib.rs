@@ -4,14 +4,18 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::needless_doctest_main)]
 
-use async_graphql::http::StreamBody;
+use async_graphql::http::{
+    incremental_http_headers, multipart_mixed_stream, HeaderMap, StreamBody,
+    MULTIPART_MIXED_CONTENT_TYPE,
+};
 use async_graphql::{
-    Data, FieldResult, IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType, QueryBuilder, Schema,
+    BatchRequest, BatchResponse, Data, FieldResult, IncrementalResponse, IntoBatchRequest,
+    IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType, QueryBuilder, QueryResponse, Schema,
     SubscriptionType, WebSocketTransport,
 };
 use bytes::Bytes;
 use futures::select;
-use futures::{SinkExt, StreamExt};
+use futures::{SinkExt, Stream, StreamExt};
 use std::sync::Arc;
 use warp::filters::ws::Message;
 use warp::filters::BoxedFilter;
@@ -33,7 +37,8 @@ impl Reject for BadRequest {}
 
 /// GraphQL request filter
 ///
-/// It outputs a tuple containing the `Schema` and `QuertBuilder`.
+/// It outputs a tuple containing the `Schema` and `QuertBuilder`, the headers of the request are available to the resolvers
+/// with `Context::http_headers`.
 ///
 /// # Examples
 /// *[Full Example](<https://github.com/async-graphql/examples/blob/master/warp/starwars/src/main.rs>)*
@@ -41,9 +46,9 @@ impl Reject for BadRequest {}
 /// ```no_run
 ///
 /// use async_graphql::*;
-/// use warp::{Filter, Reply};
+/// use async_graphql_warp::GQLResponse;
+/// use warp::Filter;
 /// use std::convert::Infallible;
-/// use async_graphql::http::GQLResponse;
 ///
 /// struct QueryRoot;
 ///
@@ -59,8 +64,7 @@ impl Reject for BadRequest {}
 /// async fn main() {
 ///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
 ///     let filter = async_graphql_warp::graphql(schema).and_then(|(schema, builder): (_, QueryBuilder)| async move {
-///         let resp = builder.execute(&schema).await;
-///         Ok::<_, Infallible>(warp::reply::json(&GQLResponse(resp)).into_response())
+///         Ok::<_, Infallible>(GQLResponse::from(builder.execute(&schema).await))
 ///     });
 ///     warp::serve(filter).run(([0, 0, 0, 0], 8000)).await;
 /// }
@@ -76,13 +80,15 @@ where
     warp::any()
         .and(warp::post())
         .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::headers_cloned())
         .and(warp::body::stream())
         .and(warp::any().map(move || schema.clone()))
-        .and_then(|content_type, body, schema| async move {
+        .and_then(|content_type, http_headers, body, schema| async move {
             let builder = (content_type, StreamBody::new(body))
                 .into_query_builder()
                 .await
-                .map_err(|err| warp::reject::custom(BadRequest(err)))?;
+                .map_err(|err| warp::reject::custom(BadRequest(err)))?
+                .http_headers(http_headers);
             Ok::<_, Rejection>((schema, builder))
         })
         .boxed()
@@ -102,21 +108,203 @@ where
     warp::any()
         .and(warp::post())
         .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::headers_cloned())
         .and(warp::body::stream())
         .and(warp::any().map(move || opts.clone()))
         .and(warp::any().map(move || schema.clone()))
         .and_then(
-            |content_type, body, opts: Arc<IntoQueryBuilderOpts>, schema| async move {
+            |content_type,
+             http_headers: HeaderMap,
+             body,
+             opts: Arc<IntoQueryBuilderOpts>,
+             schema| async move {
                 let builder = (content_type, StreamBody::new(body))
                     .into_query_builder_opts(&opts)
                     .await
-                    .map_err(|err| warp::reject::custom(BadRequest(err)))?;
+                    .map_err(|err| warp::reject::custom(BadRequest(err)))?
+                    .http_headers(http_headers);
                 Ok::<_, Rejection>((schema, builder))
             },
         )
         .boxed()
 }
 
+/// GraphQL batch request filter
+///
+/// It outputs a tuple containing the `Schema` and `BatchRequest`.
+///
+/// # Examples
+///
+/// ```no_run
+///
+/// use async_graphql::*;
+/// use async_graphql_warp::GQLBatchResponse;
+/// use warp::Filter;
+/// use std::convert::Infallible;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     #[field]
+///     async fn value(&self, ctx: &Context<'_>) -> i32 {
+///         unimplemented!()
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     let filter = async_graphql_warp::graphql_batch(schema).and_then(|(schema, batch): (_, BatchRequest)| async move {
+///         Ok::<_, Infallible>(GQLBatchResponse::from(batch.execute(&schema).await))
+///     });
+///     warp::serve(filter).run(([0, 0, 0, 0], 8000)).await;
+/// }
+/// ```
+pub fn graphql_batch<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+) -> BoxedFilter<((Schema<Query, Mutation, Subscription>, BatchRequest),)>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    graphql_batch_opts(schema, Default::default())
+}
+
+/// Similar to graphql_batch, but you can set the options `IntoQueryBuilderOpts`.
+pub fn graphql_batch_opts<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+    opts: IntoQueryBuilderOpts,
+) -> BoxedFilter<((Schema<Query, Mutation, Subscription>, BatchRequest),)>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    let opts = Arc::new(opts);
+    warp::any()
+        .and(warp::post())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::headers_cloned())
+        .and(warp::body::stream())
+        .and(warp::any().map(move || opts.clone()))
+        .and(warp::any().map(move || schema.clone()))
+        .and_then(
+            |content_type,
+             http_headers: HeaderMap,
+             body,
+             opts: Arc<IntoQueryBuilderOpts>,
+             schema| async move {
+                let batch = (content_type, StreamBody::new(body))
+                    .into_batch_request_opts(&opts)
+                    .await
+                    .map_err(|err| warp::reject::custom(BadRequest(err)))?
+                    .map(|builder| builder.http_headers(http_headers.clone()));
+                Ok::<_, Rejection>((schema, batch))
+            },
+        )
+        .boxed()
+}
+
+/// Reply for a GraphQL response
+///
+/// It sends the response as JSON, with the HTTP headers set by the resolvers.
+pub struct GQLResponse(pub async_graphql::Result<QueryResponse>);
+
+impl From<async_graphql::Result<QueryResponse>> for GQLResponse {
+    fn from(resp: async_graphql::Result<QueryResponse>) -> Self {
+        GQLResponse(resp)
+    }
+}
+
+impl Reply for GQLResponse {
+    fn into_response(self) -> warp::reply::Response {
+        let http_headers = match &self.0 {
+            Ok(resp) => resp.http_headers.clone(),
+            Err(_) => HeaderMap::new(),
+        };
+        let reply = warp::reply::json(&async_graphql::http::GQLResponse(self.0));
+        add_http_headers(reply.into_response(), http_headers)
+    }
+}
+
+/// Reply for a GraphQL batch response
+///
+/// It sends the responses as JSON, with the HTTP headers set by the resolvers of all the queries.
+pub struct GQLBatchResponse(pub BatchResponse);
+
+impl From<BatchResponse> for GQLBatchResponse {
+    fn from(resp: BatchResponse) -> Self {
+        GQLBatchResponse(resp)
+    }
+}
+
+impl Reply for GQLBatchResponse {
+    fn into_response(self) -> warp::reply::Response {
+        let http_headers = self.0.http_headers();
+        let reply = warp::reply::json(&async_graphql::http::GQLBatchResponse(self.0));
+        add_http_headers(reply.into_response(), http_headers)
+    }
+}
+
+fn add_http_headers(
+    mut resp: warp::reply::Response,
+    http_headers: HeaderMap,
+) -> warp::reply::Response {
+    for (name, value) in &http_headers {
+        resp.headers_mut().append(name, value.clone());
+    }
+    resp
+}
+
+/// Reply for a query executed with incremental delivery
+///
+/// It sends the responses of `QueryBuilder::execute_stream` as a `multipart/mixed` body, with the HTTP headers set by the
+/// resolvers of the initial response.
+///
+/// # Examples
+///
+/// ```no_run
+///
+/// use async_graphql::*;
+/// use warp::Filter;
+/// use std::convert::Infallible;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     #[field]
+///     async fn value(&self, ctx: &Context<'_>) -> i32 {
+///         unimplemented!()
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     let filter = async_graphql_warp::graphql(schema).and_then(|(schema, builder): (_, QueryBuilder)| async move {
+///         Ok::<_, Infallible>(async_graphql_warp::graphql_incremental_reply(builder.execute_stream(&schema)).await)
+///     });
+///     warp::serve(filter).run(([0, 0, 0, 0], 8000)).await;
+/// }
+/// ```
+pub async fn graphql_incremental_reply<S>(stream: S) -> impl Reply
+where
+    S: Stream<Item = IncrementalResponse> + Send + 'static,
+{
+    let (http_headers, stream) = incremental_http_headers(stream).await;
+    let reply = warp::reply::with_header(
+        warp::http::Response::new(warp::hyper::Body::wrap_stream(
+            multipart_mixed_stream(stream).map(Ok::<_, std::convert::Infallible>),
+        )),
+        "content-type",
+        MULTIPART_MIXED_CONTENT_TYPE,
+    );
+    add_http_headers(reply.into_response(), http_headers)
+}
+
 /// GraphQL subscription filter
 ///
 /// # Examples
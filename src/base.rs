@@ -51,6 +51,33 @@ pub trait Type {
 pub trait InputValueType: Type + Sized {
     /// Parse from `Value`
     fn parse(value: &Value) -> Option<Self>;
+
+    /// The reason why a value of the expected kind can't be parsed, such as a number out of range.
+    ///
+    /// It is only called when `parse` fails, `None` reports a generic type mismatch.
+    fn parse_error(_value: &Value) -> Option<String> {
+        None
+    }
+}
+
+/// Parse an input value, the error contains the reason returned by `InputValueType::parse_error` if any.
+pub(crate) fn parse_input_value<T: InputValueType>(value: Value, pos: Pos) -> Result<T> {
+    match T::parse(&value) {
+        Some(res) => Ok(res),
+        None => match T::parse_error(&value) {
+            Some(reason) => Err(QueryError::InvalidValue {
+                expect: T::qualified_type_name(),
+                actual: value,
+                reason,
+            }
+            .into_error(pos)),
+            None => Err(QueryError::ExpectedType {
+                expect: T::qualified_type_name(),
+                actual: value,
+            }
+            .into_error(pos)),
+        },
+    }
 }
 
 /// Represents a GraphQL output value
@@ -60,9 +87,9 @@ pub trait OutputValueType: Type {
     async fn resolve(&self, ctx: &ContextSelectionSet<'_>, pos: Pos) -> Result<serde_json::Value>;
 }
 
-#[allow(missing_docs)]
+/// Future of a field resolved in a selection set, outputs `None` if the field is left out of the response
 pub type BoxFieldFuture<'a> =
-    Pin<Box<dyn Future<Output = Result<(String, serde_json::Value)>> + 'a + Send>>;
+    Pin<Box<dyn Future<Output = Result<Option<(String, serde_json::Value)>>> + 'a + Send>>;
 
 /// Represents a GraphQL object
 #[async_trait::async_trait]
@@ -73,6 +100,12 @@ pub trait ObjectType: OutputValueType {
         false
     }
 
+    /// Name of the type in the registry that the fields are resolved from, it is `Type::type_name` for the types defined in Rust.
+    #[doc(hidden)]
+    fn registry_type_name(&self) -> Cow<'static, str> {
+        Self::type_name()
+    }
+
     /// Resolves a field value and outputs it as a json value `serde_json::Value`.
     async fn resolve_field(&self, ctx: &Context<'_>) -> Result<serde_json::Value>;
 
@@ -87,11 +120,12 @@ pub trait ObjectType: OutputValueType {
     where
         Self: Send + Sync + Sized,
     {
-        if name == Self::type_name().as_ref()
+        let type_name = self.registry_type_name();
+        if name == type_name.as_ref()
             || ctx
                 .registry
                 .implements
-                .get(Self::type_name().as_ref())
+                .get(type_name.as_ref())
                 .map(|ty| ty.contains(name))
                 .unwrap_or_default()
         {
@@ -157,6 +191,11 @@ pub trait ScalarType: Sized + Send {
     /// Parse a scalar value, return `Some(Self)` if successful, otherwise return `None`.
     fn parse(value: &Value) -> Option<Self>;
 
+    /// The reason why a value can't be parsed, returned in the error of the query.
+    fn parse_error(_value: &Value) -> Option<String> {
+        None
+    }
+
     /// Checks for a valid scalar value.
     ///
     /// The default implementation is to try to parse it, and in some cases you can implement this on your own to improve performance.
@@ -1,15 +1,29 @@
+use crate::base::parse_input_value;
+use crate::directive::{Directive as CustomDirective, DirectiveArgs};
 use crate::extensions::BoxExtension;
-use crate::registry::Registry;
-use crate::{InputValueType, Pos, QueryError, Result, Schema, Type};
+use crate::look_ahead::Lookahead;
+use crate::query::Deadline;
+use crate::registry::{self, Registry};
+use crate::{
+    Error, FieldError, FieldResult, InputValueType, Pos, QueryError, QueryPatch, Result, Schema,
+    Type,
+};
 use fnv::FnvHashMap;
+use futures::channel::mpsc::UnboundedSender;
+use futures::channel::oneshot;
+use futures::future::Shared;
 use graphql_parser::query::{
-    Directive, Field, FragmentDefinition, SelectionSet, Value, VariableDefinition,
+    Directive, Field, FragmentDefinition, Selection, SelectionSet, Value, VariableDefinition,
 };
+use http::header::IntoHeaderName;
+use http::{HeaderMap, HeaderValue};
+use parking_lot::Mutex;
 use std::any::{Any, TypeId};
 use std::collections::{BTreeMap, HashMap};
+use std::convert::{TryFrom, TryInto};
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 use std::sync::Arc;
 
 /// Variables of query
@@ -116,7 +130,11 @@ fn json_value_to_gql_value(value: serde_json::Value) -> Value {
         serde_json::Value::Null => Value::Null,
         serde_json::Value::Bool(n) => Value::Boolean(n),
         serde_json::Value::Number(n) if n.is_f64() => Value::Float(n.as_f64().unwrap()),
-        serde_json::Value::Number(n) => Value::Int((n.as_i64().unwrap() as i32).into()),
+        // `Number` can only be created from an `i32`, the larger integers are passed as strings.
+        serde_json::Value::Number(n) => match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(n) => Value::Int(n.into()),
+            None => Value::String(n.to_string()),
+        },
         serde_json::Value::String(s) => Value::String(s),
         serde_json::Value::Array(ls) => {
             Value::List(ls.into_iter().map(json_value_to_gql_value).collect())
@@ -138,6 +156,13 @@ impl Data {
     pub fn insert<D: Any + Send + Sync>(&mut self, data: D) {
         self.0.insert(TypeId::of::<D>(), Box::new(data));
     }
+
+    /// Gets the data of the specified type, returns `None` if it does not exist.
+    pub fn get<D: Any + Send + Sync>(&self) -> Option<&D> {
+        self.0
+            .get(&TypeId::of::<D>())
+            .and_then(|d| d.downcast_ref::<D>())
+    }
 }
 
 /// Context for `SelectionSet`
@@ -251,6 +276,67 @@ impl std::fmt::Display for ResolveId {
     }
 }
 
+/// State of a query executed with incremental delivery (`@defer` and `@stream`)
+///
+/// It is only set at the positions whose value is passed to `initial`, the other positions resolve the deferred
+/// fragments and the streamed lists inline.
+#[derive(Clone, Copy)]
+pub(crate) struct Incremental<'a> {
+    /// Set when a deferred fragment or a streamed list item is left out of the initial response
+    pub(crate) has_next: &'a AtomicBool,
+
+    /// Receives the deferred fragments and the streamed list items
+    pub(crate) patches: &'a UnboundedSender<QueryPatch>,
+
+    /// Completed once the initial response is sent, the incremental parts are only resolved after it
+    pub(crate) initial_sent: &'a Shared<oneshot::Receiver<()>>,
+
+    /// Receives the value of the current position without its incremental parts, as soon as it is known
+    pub(crate) initial: &'a (dyn Fn(serde_json::Value) + Send + Sync),
+
+    /// Values of the fields of the object resolved at the current position
+    pub(crate) fields: Option<&'a InitialValues<Option<(String, serde_json::Value)>>>,
+
+    /// `@stream` directive of the current list field
+    pub(crate) stream: Option<StreamDirective<'a>>,
+}
+
+/// Values of the fields of an object or of the items of a list, without their incremental parts
+pub(crate) struct InitialValues<T>(Mutex<BTreeMap<usize, T>>);
+
+impl<T> Default for InitialValues<T> {
+    fn default() -> Self {
+        Self(Mutex::new(BTreeMap::new()))
+    }
+}
+
+impl<T: Clone> InitialValues<T> {
+    /// Stores the value of the field or item `idx`, unless it is already known.
+    pub(crate) fn insert(&self, idx: usize, value: T) {
+        self.0.lock().entry(idx).or_insert(value);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.lock().len()
+    }
+
+    /// Returns the values ordered by index.
+    pub(crate) fn values(&self) -> Vec<T> {
+        self.0.lock().values().cloned().collect()
+    }
+
+    /// Removes the values, ordered by index.
+    pub(crate) fn take_values(&self) -> Vec<T> {
+        std::mem::take(&mut *self.0.lock()).into_values().collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct StreamDirective<'a> {
+    pub(crate) label: Option<&'a str>,
+    pub(crate) initial_count: usize,
+}
+
 /// Query context
 #[derive(Clone)]
 pub struct ContextBase<'a, T> {
@@ -266,6 +352,13 @@ pub struct ContextBase<'a, T> {
     pub(crate) data: &'a Data,
     pub(crate) ctx_data: Option<&'a Data>,
     pub(crate) fragments: &'a HashMap<String, FragmentDefinition>,
+    pub(crate) errors: Option<&'a Mutex<Vec<Error>>>,
+    pub(crate) http_headers: &'a HeaderMap,
+    pub(crate) response_http_headers: Option<&'a Mutex<HeaderMap>>,
+    pub(crate) incremental: Option<Incremental<'a>>,
+    pub(crate) directives: &'a HashMap<String, Box<dyn CustomDirective>>,
+    pub(crate) parent_type: Option<&'a str>,
+    pub(crate) deadline: Option<&'a Deadline>,
 }
 
 impl<'a, T> Deref for ContextBase<'a, T> {
@@ -282,6 +375,7 @@ pub struct Environment {
     pub variable_definitions: Vec<VariableDefinition>,
     pub fragments: HashMap<String, FragmentDefinition>,
     pub ctx_data: Arc<Data>,
+    pub http_headers: HeaderMap,
 }
 
 impl Environment {
@@ -305,6 +399,13 @@ impl Environment {
             data: &schema.0.data,
             ctx_data: Some(&self.ctx_data),
             fragments: &self.fragments,
+            errors: None,
+            http_headers: &self.http_headers,
+            response_http_headers: None,
+            incremental: None,
+            directives: &schema.0.directives,
+            parent_type: None,
+            deadline: None,
         }
     }
 }
@@ -343,6 +444,13 @@ impl<'a, T> ContextBase<'a, T> {
             data: self.data,
             ctx_data: self.ctx_data,
             fragments: self.fragments,
+            errors: self.errors,
+            http_headers: self.http_headers,
+            response_http_headers: self.response_http_headers,
+            incremental: None,
+            directives: self.directives,
+            parent_type: None,
+            deadline: self.deadline,
         }
     }
 
@@ -363,6 +471,13 @@ impl<'a, T> ContextBase<'a, T> {
             data: self.data,
             ctx_data: self.ctx_data,
             fragments: self.fragments,
+            errors: self.errors,
+            http_headers: self.http_headers,
+            response_http_headers: self.response_http_headers,
+            incremental: self.incremental,
+            directives: self.directives,
+            parent_type: self.parent_type,
+            deadline: self.deadline,
         }
     }
 
@@ -380,42 +495,131 @@ impl<'a, T> ContextBase<'a, T> {
             .and_then(|d| d.downcast_ref::<D>())
     }
 
-    fn var_value(&self, name: &str, pos: Pos) -> Result<Value> {
-        let def = self
+    /// Gets the HTTP headers of the request, they are set with `QueryBuilder::http_headers`.
+    pub fn http_headers(&self) -> &HeaderMap {
+        self.http_headers
+    }
+
+    /// Set a HTTP header of the response, it replaces the previous values of this header.
+    ///
+    /// The headers are returned in `QueryResponse::http_headers`, the integrations copy them into the HTTP response.
+    /// They are ignored in subscriptions.
+    pub fn insert_http_header<N, V>(&self, name: N, value: V) -> FieldResult<()>
+    where
+        N: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        V::Error: std::fmt::Display + Send + Sync + 'static,
+    {
+        let value = value.try_into()?;
+        if let Some(headers) = self.response_http_headers {
+            headers.lock().insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// Add a value to a HTTP header of the response, like `insert_http_header` but keeping the previous values.
+    ///
+    /// It allows to send several `Set-Cookie` headers.
+    pub fn append_http_header<N, V>(&self, name: N, value: V) -> FieldResult<()>
+    where
+        N: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        V::Error: std::fmt::Display + Send + Sync + 'static,
+    {
+        let value = value.try_into()?;
+        if let Some(headers) = self.response_http_headers {
+            headers.lock().append(name, value);
+        }
+        Ok(())
+    }
+
+    /// Returns true if the type is visible to the data of the request.
+    pub(crate) fn is_visible_type(&self, type_name: &str) -> bool {
+        let empty = Data::default();
+        self.registry
+            .is_visible_type(type_name, self.ctx_data.unwrap_or(&empty))
+    }
+
+    /// Returns true if the field is visible to the data of the request.
+    pub(crate) fn is_visible_field(
+        &self,
+        parent_type: &registry::Type,
+        field: &registry::Field,
+    ) -> bool {
+        let empty = Data::default();
+        self.registry
+            .is_visible_field(parent_type, field, self.ctx_data.unwrap_or(&empty))
+    }
+
+    /// Handles an error raised while resolving the value at the current position.
+    ///
+    /// If the position is nullable, the error is recorded in the response and `null` is returned in its place,
+    /// otherwise the error is propagated to the parent field, as described in the GraphQL specification.
+    pub(crate) fn resolve_error(&self, err: Error, non_null: bool) -> Result<serde_json::Value> {
+        match self.errors {
+            Some(errors) if !non_null => {
+                errors.lock().push(err);
+                Ok(serde_json::Value::Null)
+            }
+            _ => Err(err),
+        }
+    }
+
+    /// Returns the value of a variable, `None` if it is not provided and has no default value.
+    pub(crate) fn var_value(&self, name: &str, pos: Pos) -> Result<Option<Value>> {
+        match self
             .variable_definitions
             .iter()
-            .find(|def| def.name == name);
-        if let Some(def) = def {
-            if let Some(var_value) = self.variables.get(&def.name) {
-                return Ok(var_value.clone());
-            } else if let Some(default) = &def.default_value {
-                return Ok(default.clone());
+            .find(|def| def.name == name)
+        {
+            Some(def) => Ok(self
+                .variables
+                .get(&def.name)
+                .or(def.default_value.as_ref())
+                .cloned()),
+            None => Err(QueryError::VarNotDefined {
+                var_name: name.to_string(),
             }
+            .into_error(pos)),
         }
-        Err(QueryError::VarNotDefined {
-            var_name: name.to_string(),
-        }
-        .into_error(pos))
     }
 
-    fn resolve_input_value(&self, mut value: Value, pos: Pos) -> Result<Value> {
+    /// Replaces the variables in an input value by their values, at any depth.
+    ///
+    /// A field of an input object whose variable is not provided is removed, so that the default value of
+    /// the field applies, and such an item of a list is `null`.
+    pub(crate) fn resolve_input_value(&self, value: Value, pos: Pos) -> Result<Value> {
         match value {
-            Value::Variable(var_name) => self.var_value(&var_name, pos),
-            Value::List(ref mut ls) => {
+            Value::Variable(var_name) => self
+                .var_value(&var_name, pos)?
+                .ok_or_else(|| QueryError::VarNotDefined { var_name }.into_error(pos)),
+            Value::List(ls) => {
+                let mut values = Vec::with_capacity(ls.len());
                 for value in ls {
-                    if let Value::Variable(var_name) = value {
-                        *value = self.var_value(&var_name, pos)?;
-                    }
+                    values.push(match value {
+                        Value::Variable(var_name) => {
+                            self.var_value(&var_name, pos)?.unwrap_or(Value::Null)
+                        }
+                        value => self.resolve_input_value(value, pos)?,
+                    });
                 }
-                Ok(value)
+                Ok(Value::List(values))
             }
-            Value::Object(ref mut obj) => {
-                for value in obj.values_mut() {
-                    if let Value::Variable(var_name) = value {
-                        *value = self.var_value(&var_name, pos)?;
+            Value::Object(obj) => {
+                let mut fields = BTreeMap::new();
+                for (name, value) in obj {
+                    match value {
+                        Value::Variable(var_name) => {
+                            if let Some(value) = self.var_value(&var_name, pos)? {
+                                fields.insert(name, value);
+                            }
+                        }
+                        value => {
+                            fields.insert(name, self.resolve_input_value(value, pos)?);
+                        }
                     }
                 }
-                Ok(value)
+                Ok(Value::Object(fields))
             }
             _ => Ok(value),
         }
@@ -476,7 +680,10 @@ impl<'a, T> ContextBase<'a, T> {
                     }
                     .into_error(directive.position));
                 }
-            } else {
+            } else if directive.name != "defer"
+                && directive.name != "stream"
+                && !self.directives.contains_key(&directive.name)
+            {
                 return Err(QueryError::UnknownDirective {
                     name: directive.name.clone(),
                 }
@@ -486,6 +693,148 @@ impl<'a, T> ContextBase<'a, T> {
 
         Ok(false)
     }
+
+    fn directive_if(&self, directive: &Directive) -> Result<bool> {
+        match directive
+            .arguments
+            .iter()
+            .find(|(name, _)| name == "if")
+            .map(|(_, value)| value)
+        {
+            Some(value) => {
+                let value = self.resolve_input_value(value.clone(), directive.position)?;
+                InputValueType::parse(&value).ok_or_else(|| {
+                    QueryError::ExpectedType {
+                        expect: bool::qualified_type_name(),
+                        actual: value,
+                    }
+                    .into_error(directive.position)
+                })
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Returns the label of the `@defer` directive if the fragment is deferred.
+    pub(crate) fn defer_directive(
+        &self,
+        directives: &'a [Directive],
+    ) -> Result<Option<Option<&'a str>>> {
+        if !self.collects_incremental_fields() {
+            return Ok(None);
+        }
+        for directive in directives {
+            if directive.name == "defer" && self.directive_if(directive)? {
+                return Ok(Some(directive_label(directive)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the `@stream` directive of the field.
+    pub(crate) fn stream_directive(
+        &self,
+        directives: &'a [Directive],
+    ) -> Result<Option<StreamDirective<'a>>> {
+        if !self.collects_incremental_fields() {
+            return Ok(None);
+        }
+        for directive in directives {
+            if directive.name == "stream" && self.directive_if(directive)? {
+                let initial_count = match directive
+                    .arguments
+                    .iter()
+                    .find(|(name, _)| name == "initialCount")
+                    .map(|(_, value)| value)
+                {
+                    Some(value) => {
+                        let value = self.resolve_input_value(value.clone(), directive.position)?;
+                        match <i32 as InputValueType>::parse(&value) {
+                            Some(n) if n >= 0 => n as usize,
+                            _ => {
+                                return Err(QueryError::ExpectedType {
+                                    expect: i32::qualified_type_name(),
+                                    actual: value,
+                                }
+                                .into_error(directive.position))
+                            }
+                        }
+                    }
+                    None => 0,
+                };
+                return Ok(Some(StreamDirective {
+                    label: directive_label(directive),
+                    initial_count,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns true if the fields of the object at the current position are collected with their incremental parts.
+    fn collects_incremental_fields(&self) -> bool {
+        matches!(
+            self.incremental,
+            Some(Incremental {
+                fields: Some(_),
+                ..
+            })
+        )
+    }
+
+    /// Sends a deferred fragment or a streamed list item, its errors are passed to the extensions like the field
+    /// errors of the initial response.
+    pub(crate) fn send_patch(&self, patch: QueryPatch) {
+        if let Some(incremental) = self.incremental {
+            let errors = match &patch {
+                QueryPatch::Defer { errors, .. } | QueryPatch::Stream { errors, .. } => errors,
+            };
+            for err in errors {
+                self.extensions.iter().for_each(|e| e.field_error(err));
+            }
+            incremental.patches.unbounded_send(patch).ok();
+        }
+    }
+
+    /// Returns true if the selection set contains a deferred fragment or a streamed field.
+    ///
+    /// The directives whose `if` argument is false are ignored.
+    pub(crate) fn has_incremental_parts(&self, selection_set: &SelectionSet) -> bool {
+        let is_enabled = |directives: &[Directive], name: &str| {
+            directives
+                .iter()
+                .any(|d| d.name == name && self.directive_if(d).unwrap_or(true))
+        };
+        selection_set.items.iter().any(|selection| match selection {
+            Selection::Field(field) => {
+                is_enabled(&field.directives, "stream")
+                    || self.has_incremental_parts(&field.selection_set)
+            }
+            Selection::FragmentSpread(fragment_spread) => {
+                is_enabled(&fragment_spread.directives, "defer")
+                    || self
+                        .fragments
+                        .get(fragment_spread.fragment_name.as_str())
+                        .map(|fragment| self.has_incremental_parts(&fragment.selection_set))
+                        .unwrap_or_default()
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                is_enabled(&inline_fragment.directives, "defer")
+                    || self.has_incremental_parts(&inline_fragment.selection_set)
+            }
+        })
+    }
+}
+
+fn directive_label(directive: &Directive) -> Option<&str> {
+    directive
+        .arguments
+        .iter()
+        .find(|(name, _)| name == "label")
+        .and_then(|(_, value)| match value {
+            Value::String(label) => Some(label.as_str()),
+            _ => None,
+        })
 }
 
 impl<'a> ContextBase<'a, &'a SelectionSet> {
@@ -506,11 +855,35 @@ impl<'a> ContextBase<'a, &'a SelectionSet> {
             data: self.data,
             ctx_data: self.ctx_data,
             fragments: self.fragments,
+            errors: self.errors,
+            http_headers: self.http_headers,
+            response_http_headers: self.response_http_headers,
+            incremental: None,
+            directives: self.directives,
+            parent_type: self.parent_type,
+            deadline: self.deadline,
         }
     }
 }
 
 impl<'a> ContextBase<'a, &'a Field> {
+    /// Sets the type of the object that the field belongs to.
+    pub(crate) fn with_parent_type(self, type_name: &str) -> Self {
+        ContextBase {
+            parent_type: self
+                .registry
+                .types
+                .get_key_value(type_name)
+                .map(|(name, _)| name.as_str()),
+            ..self
+        }
+    }
+
+    /// Look ahead at the selection of the current field, to know which subfields are queried.
+    pub fn look_ahead(&'a self) -> Lookahead<'a> {
+        Lookahead::new(self)
+    }
+
     #[doc(hidden)]
     pub fn param_value<T: InputValueType, F: FnOnce() -> Value>(
         &self,
@@ -525,29 +898,64 @@ impl<'a> ContextBase<'a, &'a Field> {
             .map(|(_, v)| v)
             .cloned()
         {
+            // The default value of the argument applies if its variable is not provided.
+            Some(Value::Variable(var_name)) => match self.var_value(&var_name, pos)? {
+                Some(value) => parse_input_value(value, pos),
+                None => parse_input_value(default(), pos),
+            },
             Some(value) => {
                 let value = self.resolve_input_value(value, pos)?;
-                let res = InputValueType::parse(&value).ok_or_else(|| {
-                    QueryError::ExpectedType {
-                        expect: T::qualified_type_name(),
-                        actual: value,
-                    }
-                    .into_error(pos)
-                })?;
-                Ok(res)
+                parse_input_value(value, pos)
             }
-            None => {
-                let value = default();
-                let res = InputValueType::parse(&value).ok_or_else(|| {
-                    QueryError::ExpectedType {
-                        expect: T::qualified_type_name(),
-                        actual: value.clone(),
-                    }
-                    .into_error(pos)
-                })?;
-                Ok(res)
+            None => parse_input_value(default(), pos),
+        }
+    }
+
+    /// Calls `Directive::include` for the custom directives of the field.
+    pub(crate) async fn include_by_directives(&self) -> Result<bool> {
+        for directive in &self.item.directives {
+            if let Some(custom_directive) = self.directives.get(&directive.name) {
+                let args = DirectiveArgs {
+                    ctx: self,
+                    directive,
+                };
+                if !custom_directive
+                    .include(self, &args)
+                    .await
+                    .map_err(|err| self.directive_error(err, directive))?
+                {
+                    return Ok(false);
+                }
             }
         }
+        Ok(true)
+    }
+
+    /// Calls `Directive::resolve` for the custom directives of the field, in the order they are specified.
+    pub(crate) async fn resolve_by_directives(
+        &self,
+        mut value: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        for directive in &self.item.directives {
+            if let Some(custom_directive) = self.directives.get(&directive.name) {
+                let args = DirectiveArgs {
+                    ctx: self,
+                    directive,
+                };
+                value = custom_directive
+                    .resolve(self, &args, value)
+                    .await
+                    .map_err(|err| self.directive_error(err, directive))?;
+            }
+        }
+        Ok(value)
+    }
+
+    fn directive_error(&self, err: FieldError, directive: &Directive) -> Error {
+        match &self.path_node {
+            Some(path_node) => err.into_error_with_path(directive.position, path_node.to_json()),
+            None => err.into_error(directive.position),
+        }
     }
 
     #[doc(hidden)]
@@ -0,0 +1,205 @@
+//! Batch loading
+//!
+//! A `DataLoader` coalesces all the `load` calls issued while resolving the fields of one selection set into a
+//! single call of `Loader::load`, and caches the loaded values for as long as the `DataLoader` lives. The keys that are
+//! being loaded by a batch are not loaded again, the later calls wait for that batch.
+//!
+//! Insert a new `DataLoader` for each request with `QueryBuilder::data`, and get it with `Context::data` in the resolvers.
+//!
+//! ```rust
+//! use async_graphql::*;
+//! use async_graphql::dataloader::{DataLoader, Loader};
+//! use std::collections::HashMap;
+//!
+//! struct UserNameLoader;
+//!
+//! #[async_trait::async_trait]
+//! impl Loader for UserNameLoader {
+//!     type Key = i32;
+//!     type Value = String;
+//!
+//!     async fn load(&self, keys: &[i32]) -> HashMap<i32, String> {
+//!         // Load all the users with one query...
+//!         keys.iter().map(|id| (*id, format!("user{}", id))).collect()
+//!     }
+//! }
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn user_name(&self, ctx: &Context<'_>, id: i32) -> Option<String> {
+//!         ctx.data::<DataLoader<UserNameLoader>>().load(id).await
+//!     }
+//! }
+//!
+//! #[async_std::main]
+//! async fn main() {
+//!     let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+//!     let res = QueryBuilder::new("{ a: userName(id: 1) b: userName(id: 2) }")
+//!         .data(DataLoader::new(UserNameLoader))
+//!         .execute(&schema)
+//!         .await
+//!         .unwrap();
+//!     assert_eq!(res.data, serde_json::json!({ "a": "user1", "b": "user2" }));
+//! }
+//! ```
+
+use futures::channel::oneshot;
+use futures::future::Shared;
+use futures::FutureExt;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Batch loader
+///
+/// Loads the values of a batch of keys at once. Keys without a value in the returned map are resolved to `None`, which is
+/// cached like the other values.
+#[async_trait::async_trait]
+pub trait Loader: Send + Sync + 'static {
+    /// Type of the keys.
+    type Key: Hash + Eq + Clone + Send + Sync + 'static;
+
+    /// Type of the values.
+    type Value: Clone + Send + Sync + 'static;
+
+    /// Load the values of `keys`.
+    async fn load(&self, keys: &[Self::Key]) -> HashMap<Self::Key, Self::Value>;
+}
+
+type Batch<L> = Shared<oneshot::Receiver<Arc<HashMap<<L as Loader>::Key, <L as Loader>::Value>>>>;
+
+struct State<L: Loader> {
+    cache: HashMap<L::Key, Option<L::Value>>,
+    pending: HashSet<L::Key>,
+    batch: Option<(usize, Batch<L>)>,
+    in_flight: HashMap<L::Key, (usize, Batch<L>)>,
+    next_batch_id: usize,
+}
+
+/// Batching and caching loader
+///
+/// All the calls of `DataLoader::load` made before the resolvers yield are dispatched as one call of `Loader::load`.
+pub struct DataLoader<L: Loader> {
+    loader: L,
+    state: Mutex<State<L>>,
+}
+
+impl<L: Loader> DataLoader<L> {
+    /// Create a `DataLoader` with the specified loader.
+    pub fn new(loader: L) -> Self {
+        Self {
+            loader,
+            state: Mutex::new(State {
+                cache: Default::default(),
+                pending: Default::default(),
+                batch: None,
+                in_flight: Default::default(),
+                next_batch_id: 0,
+            }),
+        }
+    }
+
+    /// Get the loader.
+    pub fn loader(&self) -> &L {
+        &self.loader
+    }
+
+    /// Load the value of `key`, from the cache if it has been loaded before, or from the batch that is loading it.
+    pub async fn load(&self, key: L::Key) -> Option<L::Value> {
+        loop {
+            let (batch_id, batch, sender) = {
+                let mut state = self.state.lock();
+                if let Some(value) = state.cache.get(&key) {
+                    return value.clone();
+                }
+                if let Some((batch_id, batch)) = state.in_flight.get(&key) {
+                    (*batch_id, batch.clone(), None)
+                } else {
+                    state.pending.insert(key.clone());
+                    match &state.batch {
+                        Some((batch_id, batch)) => (*batch_id, batch.clone(), None),
+                        None => {
+                            let (tx, rx) = oneshot::channel();
+                            let batch_id = state.next_batch_id;
+                            let batch = rx.shared();
+                            state.next_batch_id += 1;
+                            state.batch = Some((batch_id, batch.clone()));
+                            (batch_id, batch, Some(tx))
+                        }
+                    }
+                }
+            };
+
+            if let Some(tx) = sender {
+                // The first caller dispatches the batch, after giving the other pending resolvers a chance to
+                // add their keys to it.
+                YieldNow(false).await;
+                let values = Arc::new(self.dispatch(batch_id, batch).await);
+                let value = values.get(&key).cloned();
+                tx.send(values).ok();
+                return value;
+            }
+
+            match batch.await {
+                Ok(values) => return values.get(&key).cloned(),
+                Err(_) => {
+                    // The dispatching resolver has been dropped, start a new batch.
+                    let mut state = self.state.lock();
+                    if matches!(&state.batch, Some((id, _)) if *id == batch_id) {
+                        state.batch = None;
+                    }
+                    state.in_flight.retain(|_, (id, _)| *id != batch_id);
+                }
+            }
+        }
+    }
+
+    /// Remove all the loaded values from the cache.
+    pub fn clear(&self) {
+        self.state.lock().cache.clear();
+    }
+
+    async fn dispatch(&self, batch_id: usize, batch: Batch<L>) -> HashMap<L::Key, L::Value> {
+        let keys = {
+            let mut state = self.state.lock();
+            state.batch = None;
+            let keys = state.pending.drain().collect::<Vec<_>>();
+            for key in &keys {
+                state
+                    .in_flight
+                    .insert(key.clone(), (batch_id, batch.clone()));
+            }
+            keys
+        };
+        let values = self.loader.load(&keys).await;
+        let mut state = self.state.lock();
+        for key in keys {
+            state.in_flight.remove(&key);
+            let value = values.get(&key).cloned();
+            state.cache.insert(key, value);
+        }
+        values
+    }
+}
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
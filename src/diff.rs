@@ -0,0 +1,933 @@
+//! Schema diffing
+//!
+//! Compares two versions of a schema and reports the changes, categorized by their impact on the
+//! existing clients. It is typically used in CI to detect the breaking changes before deploying.
+//!
+//! ```rust
+//! use async_graphql::diff::{diff_sdl, Criticality};
+//!
+//! let old = r#"
+//!     type Query {
+//!         user(id: ID): User
+//!     }
+//!
+//!     type User {
+//!         name: String!
+//!         email: String
+//!     }
+//! "#;
+//!
+//! let new = r#"
+//!     type Query {
+//!         user(id: ID!): User
+//!     }
+//!
+//!     type User {
+//!         name: String!
+//!     }
+//! "#;
+//!
+//! let changes = diff_sdl(old, new).unwrap();
+//! assert!(changes.iter().all(|change| change.criticality == Criticality::Breaking));
+//! assert_eq!(
+//!     changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+//!     vec![
+//!         "Argument \"Query.user(id:)\" changed type from \"ID\" to \"ID!\".",
+//!         "Field \"User.email\" was removed.",
+//!     ]
+//! );
+//! ```
+
+use crate::registry::{self, is_builtin_directive, is_builtin_scalar, Registry, TypeName};
+use graphql_parser::schema::{self, parse_schema, Definition, ParseError, TypeDefinition};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+
+/// Impact of a change on the existing clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Criticality {
+    /// The change breaks the existing queries.
+    Breaking,
+
+    /// The existing queries still work, but their results may change in a way the clients do not expect.
+    Dangerous,
+
+    /// The change does not affect the existing clients.
+    Safe,
+}
+
+/// Kind of a schema change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum ChangeKind {
+    RootTypeChanged,
+    TypeAdded,
+    TypeRemoved,
+    TypeKindChanged,
+    FieldAdded,
+    FieldRemoved,
+    FieldTypeChanged,
+    FieldDeprecationChanged,
+    ArgumentAdded,
+    ArgumentRemoved,
+    ArgumentTypeChanged,
+    ArgumentDefaultValueChanged,
+    InputFieldAdded,
+    InputFieldRemoved,
+    InputFieldTypeChanged,
+    InputFieldDefaultValueChanged,
+    EnumValueAdded,
+    EnumValueRemoved,
+    EnumValueDeprecationChanged,
+    UnionMemberAdded,
+    UnionMemberRemoved,
+    InterfaceImplementationAdded,
+    InterfaceImplementationRemoved,
+    DirectiveAdded,
+    DirectiveRemoved,
+    DirectiveLocationAdded,
+    DirectiveLocationRemoved,
+}
+
+/// A change between two versions of a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    /// Impact of the change.
+    pub criticality: Criticality,
+
+    /// Kind of the change.
+    pub kind: ChangeKind,
+
+    /// Path of the changed element, such as `User.friends(first:)` or `@include`.
+    pub path: String,
+
+    /// Description of the change.
+    pub message: String,
+}
+
+impl SchemaChange {
+    /// Returns true if the change breaks the existing queries.
+    pub fn is_breaking(&self) -> bool {
+        self.criticality == Criticality::Breaking
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Compares two registries, the changes are grouped by directive and type in alphabetical order.
+pub fn diff_registries(old: &Registry, new: &Registry) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    diff_schemas(
+        &SchemaModel::from_registry(old),
+        &SchemaModel::from_registry(new),
+        &mut changes,
+    );
+    changes
+}
+
+/// Compares two schemas in SDL, the changes are grouped by directive and type in alphabetical order.
+pub fn diff_sdl(old: &str, new: &str) -> Result<Vec<SchemaChange>, ParseError> {
+    let mut changes = Vec::new();
+    diff_schemas(
+        &SchemaModel::from_sdl(old)?,
+        &SchemaModel::from_sdl(new)?,
+        &mut changes,
+    );
+    Ok(changes)
+}
+
+#[derive(Default)]
+struct SchemaModel {
+    roots: [Option<String>; 3],
+    types: BTreeMap<String, TypeModel>,
+    directives: BTreeMap<String, DirectiveModel>,
+}
+
+#[derive(Default)]
+struct TypeModel {
+    kind: &'static str,
+    fields: BTreeMap<String, FieldModel>,
+    input_fields: BTreeMap<String, InputValueModel>,
+    enum_values: BTreeMap<String, bool>,
+    possible_types: BTreeSet<String>,
+    interfaces: BTreeSet<String>,
+}
+
+#[derive(Default)]
+struct FieldModel {
+    ty: String,
+    args: BTreeMap<String, InputValueModel>,
+    deprecated: bool,
+}
+
+struct InputValueModel {
+    ty: String,
+    default_value: Option<String>,
+}
+
+#[derive(Default)]
+struct DirectiveModel {
+    locations: BTreeSet<String>,
+    args: BTreeMap<String, InputValueModel>,
+}
+
+const ROOT_NAMES: [&str; 3] = ["query", "mutation", "subscription"];
+
+impl SchemaModel {
+    fn from_registry(registry: &Registry) -> Self {
+        let mut model = SchemaModel {
+            roots: [
+                Some(registry.query_type.clone()),
+                registry.mutation_type.clone(),
+                registry.subscription_type.clone(),
+            ],
+            ..Default::default()
+        };
+
+        let input_values =
+            |args: &std::collections::HashMap<&'static str, registry::InputValue>| {
+                args.values()
+                    .map(|arg| {
+                        (
+                            arg.name.to_string(),
+                            InputValueModel {
+                                ty: arg.ty.clone(),
+                                default_value: arg.default_value.map(ToString::to_string),
+                            },
+                        )
+                    })
+                    .collect()
+            };
+
+        for ty in registry.types.values() {
+            if ty.name().starts_with("__") || is_builtin_scalar(ty.name()) {
+                continue;
+            }
+            let mut type_model = TypeModel::default();
+            match ty {
+                registry::Type::Scalar { .. } => type_model.kind = "scalar",
+                registry::Type::Object { name, fields, .. }
+                | registry::Type::Interface { name, fields, .. } => {
+                    type_model.kind = if let registry::Type::Object { .. } = ty {
+                        "object"
+                    } else {
+                        "interface"
+                    };
+                    type_model.fields = fields
+                        .values()
+                        .filter(|field| !field.name.starts_with("__"))
+                        .map(|field| {
+                            (
+                                field.name.clone(),
+                                FieldModel {
+                                    ty: field.ty.clone(),
+                                    args: input_values(&field.args),
+                                    deprecated: field.deprecation.is_some(),
+                                },
+                            )
+                        })
+                        .collect();
+                    if let registry::Type::Object { .. } = ty {
+                        type_model.interfaces = registry
+                            .implements
+                            .get(name)
+                            .into_iter()
+                            .flatten()
+                            .cloned()
+                            .collect();
+                    }
+                }
+                registry::Type::Union { possible_types, .. } => {
+                    type_model.kind = "union";
+                    type_model.possible_types = possible_types.iter().cloned().collect();
+                }
+                registry::Type::Enum { enum_values, .. } => {
+                    type_model.kind = "enum";
+                    type_model.enum_values = enum_values
+                        .values()
+                        .map(|value| (value.name.to_string(), value.deprecation.is_some()))
+                        .collect();
+                }
+                registry::Type::InputObject { input_fields, .. } => {
+                    type_model.kind = "input object";
+                    type_model.input_fields = input_fields
+                        .values()
+                        .map(|field| {
+                            (
+                                field.name.to_string(),
+                                InputValueModel {
+                                    ty: field.ty.clone(),
+                                    default_value: field.default_value.map(ToString::to_string),
+                                },
+                            )
+                        })
+                        .collect();
+                }
+            }
+            model.types.insert(ty.name().to_string(), type_model);
+        }
+
+        for directive in registry.directives.values() {
+            if is_builtin_directive(directive.name) {
+                continue;
+            }
+            model.directives.insert(
+                directive.name.to_string(),
+                DirectiveModel {
+                    locations: directive
+                        .locations
+                        .iter()
+                        .map(|location| format!("{:?}", location))
+                        .collect(),
+                    args: input_values(&directive.args),
+                },
+            );
+        }
+
+        model
+    }
+
+    fn from_sdl(sdl: &str) -> Result<Self, ParseError> {
+        let document = parse_schema(sdl)?;
+        let mut model = SchemaModel::default();
+        let mut has_schema_definition = false;
+
+        let input_values = |args: &[schema::InputValue]| {
+            args.iter()
+                .map(|arg| {
+                    (
+                        arg.name.clone(),
+                        InputValueModel {
+                            ty: arg.value_type.to_string(),
+                            default_value: arg.default_value.as_ref().map(ToString::to_string),
+                        },
+                    )
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let fields = |fields: &[schema::Field]| {
+            fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.clone(),
+                        FieldModel {
+                            ty: field.field_type.to_string(),
+                            args: input_values(&field.arguments),
+                            deprecated: is_deprecated(&field.directives),
+                        },
+                    )
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let enum_values = |values: &[schema::EnumValue]| {
+            values
+                .iter()
+                .map(|value| (value.name.clone(), is_deprecated(&value.directives)))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        for definition in document.definitions {
+            match definition {
+                Definition::SchemaDefinition(schema_definition) => {
+                    has_schema_definition = true;
+                    model.roots = [
+                        schema_definition.query,
+                        schema_definition.mutation,
+                        schema_definition.subscription,
+                    ];
+                }
+                Definition::TypeDefinition(type_definition) => {
+                    let (name, type_model) = match type_definition {
+                        TypeDefinition::Scalar(scalar) => (
+                            scalar.name,
+                            TypeModel {
+                                kind: "scalar",
+                                ..Default::default()
+                            },
+                        ),
+                        TypeDefinition::Object(object) => (
+                            object.name,
+                            TypeModel {
+                                kind: "object",
+                                fields: fields(&object.fields),
+                                interfaces: object.implements_interfaces.into_iter().collect(),
+                                ..Default::default()
+                            },
+                        ),
+                        TypeDefinition::Interface(interface) => (
+                            interface.name,
+                            TypeModel {
+                                kind: "interface",
+                                fields: fields(&interface.fields),
+                                ..Default::default()
+                            },
+                        ),
+                        TypeDefinition::Union(union) => (
+                            union.name,
+                            TypeModel {
+                                kind: "union",
+                                possible_types: union.types.into_iter().collect(),
+                                ..Default::default()
+                            },
+                        ),
+                        TypeDefinition::Enum(enum_type) => (
+                            enum_type.name,
+                            TypeModel {
+                                kind: "enum",
+                                enum_values: enum_values(&enum_type.values),
+                                ..Default::default()
+                            },
+                        ),
+                        TypeDefinition::InputObject(input_object) => (
+                            input_object.name,
+                            TypeModel {
+                                kind: "input object",
+                                input_fields: input_values(&input_object.fields),
+                                ..Default::default()
+                            },
+                        ),
+                    };
+                    if !is_builtin_scalar(&name) {
+                        model.types.insert(name, type_model);
+                    }
+                }
+                Definition::TypeExtension(type_extension) => {
+                    // The extensions are merged into the types they extend.
+                    let (name, extension) = match type_extension {
+                        schema::TypeExtension::Scalar(scalar) => {
+                            (scalar.name, TypeModel::default())
+                        }
+                        schema::TypeExtension::Object(object) => (
+                            object.name,
+                            TypeModel {
+                                fields: fields(&object.fields),
+                                interfaces: object.implements_interfaces.into_iter().collect(),
+                                ..Default::default()
+                            },
+                        ),
+                        schema::TypeExtension::Interface(interface) => (
+                            interface.name,
+                            TypeModel {
+                                fields: fields(&interface.fields),
+                                ..Default::default()
+                            },
+                        ),
+                        schema::TypeExtension::Union(union) => (
+                            union.name,
+                            TypeModel {
+                                possible_types: union.types.into_iter().collect(),
+                                ..Default::default()
+                            },
+                        ),
+                        schema::TypeExtension::Enum(enum_type) => (
+                            enum_type.name,
+                            TypeModel {
+                                enum_values: enum_values(&enum_type.values),
+                                ..Default::default()
+                            },
+                        ),
+                        schema::TypeExtension::InputObject(input_object) => (
+                            input_object.name,
+                            TypeModel {
+                                input_fields: input_values(&input_object.fields),
+                                ..Default::default()
+                            },
+                        ),
+                    };
+                    let type_model = model.types.entry(name).or_default();
+                    type_model.fields.extend(extension.fields);
+                    type_model.input_fields.extend(extension.input_fields);
+                    type_model.enum_values.extend(extension.enum_values);
+                    type_model.possible_types.extend(extension.possible_types);
+                    type_model.interfaces.extend(extension.interfaces);
+                }
+                Definition::DirectiveDefinition(directive) => {
+                    if !is_builtin_directive(&directive.name) {
+                        model.directives.insert(
+                            directive.name,
+                            DirectiveModel {
+                                locations: directive
+                                    .locations
+                                    .iter()
+                                    .map(|location| location.as_str().to_string())
+                                    .collect(),
+                                args: input_values(&directive.arguments),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        if !has_schema_definition {
+            // Without a schema definition, the root types have the default names.
+            for (idx, name) in ["Query", "Mutation", "Subscription"].iter().enumerate() {
+                if model.types.contains_key(*name) {
+                    model.roots[idx] = Some(name.to_string());
+                }
+            }
+        }
+
+        Ok(model)
+    }
+}
+
+fn is_deprecated(directives: &[schema::Directive]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.name == "deprecated")
+}
+
+fn push_change(
+    changes: &mut Vec<SchemaChange>,
+    criticality: Criticality,
+    kind: ChangeKind,
+    path: String,
+    message: String,
+) {
+    changes.push(SchemaChange {
+        criticality,
+        kind,
+        path,
+        message,
+    });
+}
+
+fn diff_schemas(old: &SchemaModel, new: &SchemaModel, changes: &mut Vec<SchemaChange>) {
+    for (idx, name) in ROOT_NAMES.iter().enumerate() {
+        if old.roots[idx] != new.roots[idx] {
+            let root_name =
+                |root: &Option<String>| root.clone().unwrap_or_else(|| "none".to_string());
+            push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::RootTypeChanged,
+                name.to_string(),
+                format!(
+                    "Root {} type changed from \"{}\" to \"{}\".",
+                    name,
+                    root_name(&old.roots[idx]),
+                    root_name(&new.roots[idx])
+                ),
+            );
+        }
+    }
+
+    for (name, old_directive) in &old.directives {
+        let path = format!("@{}", name);
+        match new.directives.get(name) {
+            Some(new_directive) => {
+                for location in old_directive.locations.difference(&new_directive.locations) {
+                    push_change(
+                        changes,
+                        Criticality::Breaking,
+                        ChangeKind::DirectiveLocationRemoved,
+                        path.clone(),
+                        format!(
+                            "Location \"{}\" was removed from directive \"{}\".",
+                            location, path
+                        ),
+                    );
+                }
+                for location in new_directive.locations.difference(&old_directive.locations) {
+                    push_change(
+                        changes,
+                        Criticality::Safe,
+                        ChangeKind::DirectiveLocationAdded,
+                        path.clone(),
+                        format!(
+                            "Location \"{}\" was added to directive \"{}\".",
+                            location, path
+                        ),
+                    );
+                }
+                diff_args(
+                    &path,
+                    &old_directive.args,
+                    &new_directive.args,
+                    false,
+                    changes,
+                );
+            }
+            None => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::DirectiveRemoved,
+                path.clone(),
+                format!("Directive \"{}\" was removed.", path),
+            ),
+        }
+    }
+    for name in new.directives.keys() {
+        if !old.directives.contains_key(name) {
+            let path = format!("@{}", name);
+            push_change(
+                changes,
+                Criticality::Safe,
+                ChangeKind::DirectiveAdded,
+                path.clone(),
+                format!("Directive \"{}\" was added.", path),
+            );
+        }
+    }
+
+    let names = old
+        .types
+        .keys()
+        .chain(new.types.keys())
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        match (old.types.get(name), new.types.get(name)) {
+            (Some(old_type), Some(new_type)) if old_type.kind != new_type.kind => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::TypeKindChanged,
+                name.clone(),
+                format!(
+                    "Type \"{}\" changed from {} to {}.",
+                    name, old_type.kind, new_type.kind
+                ),
+            ),
+            (Some(old_type), Some(new_type)) => diff_type(name, old_type, new_type, changes),
+            (Some(_), None) => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::TypeRemoved,
+                name.clone(),
+                format!("Type \"{}\" was removed.", name),
+            ),
+            (None, Some(_)) => push_change(
+                changes,
+                Criticality::Safe,
+                ChangeKind::TypeAdded,
+                name.clone(),
+                format!("Type \"{}\" was added.", name),
+            ),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+fn diff_type(name: &str, old: &TypeModel, new: &TypeModel, changes: &mut Vec<SchemaChange>) {
+    for interface in old.interfaces.difference(&new.interfaces) {
+        push_change(
+            changes,
+            Criticality::Breaking,
+            ChangeKind::InterfaceImplementationRemoved,
+            name.to_string(),
+            format!(
+                "Type \"{}\" no longer implements interface \"{}\".",
+                name, interface
+            ),
+        );
+    }
+    for interface in new.interfaces.difference(&old.interfaces) {
+        push_change(
+            changes,
+            Criticality::Dangerous,
+            ChangeKind::InterfaceImplementationAdded,
+            name.to_string(),
+            format!(
+                "Type \"{}\" now implements interface \"{}\".",
+                name, interface
+            ),
+        );
+    }
+
+    for member in old.possible_types.difference(&new.possible_types) {
+        push_change(
+            changes,
+            Criticality::Breaking,
+            ChangeKind::UnionMemberRemoved,
+            name.to_string(),
+            format!("Member \"{}\" was removed from union \"{}\".", member, name),
+        );
+    }
+    for member in new.possible_types.difference(&old.possible_types) {
+        push_change(
+            changes,
+            Criticality::Dangerous,
+            ChangeKind::UnionMemberAdded,
+            name.to_string(),
+            format!("Member \"{}\" was added to union \"{}\".", member, name),
+        );
+    }
+
+    for (value, old_deprecated) in &old.enum_values {
+        let path = format!("{}.{}", name, value);
+        match new.enum_values.get(value) {
+            Some(new_deprecated) if new_deprecated != old_deprecated => push_change(
+                changes,
+                Criticality::Safe,
+                ChangeKind::EnumValueDeprecationChanged,
+                path.clone(),
+                if *new_deprecated {
+                    format!("Enum value \"{}\" was deprecated.", path)
+                } else {
+                    format!("Enum value \"{}\" is no longer deprecated.", path)
+                },
+            ),
+            Some(_) => {}
+            None => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::EnumValueRemoved,
+                path.clone(),
+                format!("Enum value \"{}\" was removed.", path),
+            ),
+        }
+    }
+    for value in new.enum_values.keys() {
+        if !old.enum_values.contains_key(value) {
+            let path = format!("{}.{}", name, value);
+            push_change(
+                changes,
+                Criticality::Dangerous,
+                ChangeKind::EnumValueAdded,
+                path.clone(),
+                format!("Enum value \"{}\" was added.", path),
+            );
+        }
+    }
+
+    for (field_name, old_field) in &old.fields {
+        let path = format!("{}.{}", name, field_name);
+        match new.fields.get(field_name) {
+            Some(new_field) => {
+                if old_field.ty != new_field.ty {
+                    push_change(
+                        changes,
+                        if is_safe_output_change(&old_field.ty, &new_field.ty) {
+                            Criticality::Safe
+                        } else {
+                            Criticality::Breaking
+                        },
+                        ChangeKind::FieldTypeChanged,
+                        path.clone(),
+                        format!(
+                            "Field \"{}\" changed type from \"{}\" to \"{}\".",
+                            path, old_field.ty, new_field.ty
+                        ),
+                    );
+                }
+                if old_field.deprecated != new_field.deprecated {
+                    push_change(
+                        changes,
+                        Criticality::Safe,
+                        ChangeKind::FieldDeprecationChanged,
+                        path.clone(),
+                        if new_field.deprecated {
+                            format!("Field \"{}\" was deprecated.", path)
+                        } else {
+                            format!("Field \"{}\" is no longer deprecated.", path)
+                        },
+                    );
+                }
+                diff_args(&path, &old_field.args, &new_field.args, true, changes);
+            }
+            None => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::FieldRemoved,
+                path.clone(),
+                format!("Field \"{}\" was removed.", path),
+            ),
+        }
+    }
+    for field_name in new.fields.keys() {
+        if !old.fields.contains_key(field_name) {
+            let path = format!("{}.{}", name, field_name);
+            push_change(
+                changes,
+                Criticality::Safe,
+                ChangeKind::FieldAdded,
+                path.clone(),
+                format!("Field \"{}\" was added.", path),
+            );
+        }
+    }
+
+    for (field_name, old_field) in &old.input_fields {
+        let path = format!("{}.{}", name, field_name);
+        match new.input_fields.get(field_name) {
+            Some(new_field) => diff_input_value(
+                "Input field",
+                &path,
+                old_field,
+                new_field,
+                (
+                    ChangeKind::InputFieldTypeChanged,
+                    ChangeKind::InputFieldDefaultValueChanged,
+                ),
+                changes,
+            ),
+            None => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::InputFieldRemoved,
+                path.clone(),
+                format!("Input field \"{}\" was removed.", path),
+            ),
+        }
+    }
+    for (field_name, new_field) in &new.input_fields {
+        if !old.input_fields.contains_key(field_name) {
+            let path = format!("{}.{}", name, field_name);
+            let (criticality, message) = if is_required(new_field) {
+                (
+                    Criticality::Breaking,
+                    format!("Required input field \"{}\" was added.", path),
+                )
+            } else {
+                (
+                    Criticality::Dangerous,
+                    format!("Optional input field \"{}\" was added.", path),
+                )
+            };
+            push_change(
+                changes,
+                criticality,
+                ChangeKind::InputFieldAdded,
+                path,
+                message,
+            );
+        }
+    }
+}
+
+/// Compares the arguments of a field or a directive, an optional argument added to a field is dangerous.
+fn diff_args(
+    parent: &str,
+    old: &BTreeMap<String, InputValueModel>,
+    new: &BTreeMap<String, InputValueModel>,
+    is_field: bool,
+    changes: &mut Vec<SchemaChange>,
+) {
+    for (name, old_arg) in old {
+        let path = format!("{}({}:)", parent, name);
+        match new.get(name) {
+            Some(new_arg) => diff_input_value(
+                "Argument",
+                &path,
+                old_arg,
+                new_arg,
+                (
+                    ChangeKind::ArgumentTypeChanged,
+                    ChangeKind::ArgumentDefaultValueChanged,
+                ),
+                changes,
+            ),
+            None => push_change(
+                changes,
+                Criticality::Breaking,
+                ChangeKind::ArgumentRemoved,
+                path.clone(),
+                format!("Argument \"{}\" was removed.", path),
+            ),
+        }
+    }
+    for (name, new_arg) in new {
+        if !old.contains_key(name) {
+            let path = format!("{}({}:)", parent, name);
+            let (criticality, message) = if is_required(new_arg) {
+                (
+                    Criticality::Breaking,
+                    format!("Required argument \"{}\" was added.", path),
+                )
+            } else if is_field {
+                (
+                    Criticality::Dangerous,
+                    format!("Optional argument \"{}\" was added.", path),
+                )
+            } else {
+                (
+                    Criticality::Safe,
+                    format!("Optional argument \"{}\" was added.", path),
+                )
+            };
+            push_change(
+                changes,
+                criticality,
+                ChangeKind::ArgumentAdded,
+                path,
+                message,
+            );
+        }
+    }
+}
+
+fn diff_input_value(
+    label: &str,
+    path: &str,
+    old: &InputValueModel,
+    new: &InputValueModel,
+    (type_changed, default_value_changed): (ChangeKind, ChangeKind),
+    changes: &mut Vec<SchemaChange>,
+) {
+    if old.ty != new.ty {
+        push_change(
+            changes,
+            if is_safe_input_change(&old.ty, &new.ty) {
+                Criticality::Safe
+            } else {
+                Criticality::Breaking
+            },
+            type_changed,
+            path.to_string(),
+            format!(
+                "{} \"{}\" changed type from \"{}\" to \"{}\".",
+                label, path, old.ty, new.ty
+            ),
+        );
+    }
+    if old.default_value != new.default_value {
+        let default_value =
+            |value: &Option<String>| value.clone().unwrap_or_else(|| "none".to_string());
+        push_change(
+            changes,
+            Criticality::Dangerous,
+            default_value_changed,
+            path.to_string(),
+            format!(
+                "{} \"{}\" changed default value from {} to {}.",
+                label,
+                path,
+                default_value(&old.default_value),
+                default_value(&new.default_value)
+            ),
+        );
+    }
+}
+
+fn is_required(input_value: &InputValueModel) -> bool {
+    TypeName::create(&input_value.ty).is_non_null() && input_value.default_value.is_none()
+}
+
+/// Returns true if the queries selecting a field of type `old` still work with the type `new`.
+fn is_safe_output_change(old: &str, new: &str) -> bool {
+    match (TypeName::create(old), TypeName::create(new)) {
+        (TypeName::NonNull(old), TypeName::NonNull(new)) => is_safe_output_change(old, new),
+        (TypeName::NonNull(_), _) => false,
+        (_, TypeName::NonNull(new)) => is_safe_output_change(old, new),
+        (TypeName::List(old), TypeName::List(new)) => is_safe_output_change(old, new),
+        (TypeName::Named(old), TypeName::Named(new)) => old == new,
+        _ => false,
+    }
+}
+
+/// Returns true if the queries passing a value of type `old` still work with the type `new`.
+fn is_safe_input_change(old: &str, new: &str) -> bool {
+    match (TypeName::create(old), TypeName::create(new)) {
+        (TypeName::NonNull(old), TypeName::NonNull(new)) => is_safe_input_change(old, new),
+        (TypeName::NonNull(old), _) => is_safe_input_change(old, new),
+        (_, TypeName::NonNull(_)) => false,
+        (TypeName::List(old), TypeName::List(new)) => is_safe_input_change(old, new),
+        (TypeName::Named(old), TypeName::Named(new)) => old == new,
+        _ => false,
+    }
+}
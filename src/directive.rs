@@ -0,0 +1,157 @@
+//! Custom executable directives
+//!
+//! A custom directive is used on the fields of a query, it can leave the field out of the response,
+//! or transform the resolved value. Register it with `SchemaBuilder::directive`.
+//!
+//! ```rust
+//! use async_graphql::*;
+//! use async_graphql::directive::{Directive, DirectiveArgs, DirectiveArgument};
+//!
+//! struct Uppercase;
+//!
+//! #[async_trait::async_trait]
+//! impl Directive for Uppercase {
+//!     fn name(&self) -> &'static str {
+//!         "uppercase"
+//!     }
+//!
+//!     async fn resolve(
+//!         &self,
+//!         _ctx: &Context<'_>,
+//!         _args: &DirectiveArgs<'_>,
+//!         value: serde_json::Value,
+//!     ) -> FieldResult<serde_json::Value> {
+//!         Ok(match value {
+//!             serde_json::Value::String(s) => s.to_uppercase().into(),
+//!             value => value,
+//!         })
+//!     }
+//! }
+//!
+//! struct FeatureFlag;
+//!
+//! #[async_trait::async_trait]
+//! impl Directive for FeatureFlag {
+//!     fn name(&self) -> &'static str {
+//!         "featureFlag"
+//!     }
+//!
+//!     fn args(&self) -> Vec<DirectiveArgument> {
+//!         vec![DirectiveArgument::new::<String>("name")]
+//!     }
+//!
+//!     async fn include(&self, _ctx: &Context<'_>, args: &DirectiveArgs<'_>) -> FieldResult<bool> {
+//!         Ok(args.get::<String>("name")? == "enabled")
+//!     }
+//! }
+//!
+//! struct QueryRoot;
+//!
+//! #[Object]
+//! impl QueryRoot {
+//!     async fn value(&self) -> String {
+//!         "abc".to_string()
+//!     }
+//! }
+//!
+//! async_std::task::block_on(async move {
+//!     let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+//!         .directive(Uppercase)
+//!         .directive(FeatureFlag)
+//!         .finish();
+//!     let res = schema
+//!         .execute(r#"{ a: value @uppercase b: value @featureFlag(name: "disabled") }"#)
+//!         .await
+//!         .unwrap();
+//!     assert_eq!(res.data, serde_json::json!({ "a": "ABC" }));
+//! });
+//! ```
+
+use crate::base::parse_input_value;
+use crate::{Context, FieldResult, InputValueType, Result, Type};
+use graphql_parser::query;
+
+/// Custom executable directive
+///
+/// The directive can be used on fields, the hooks are called for each field it is used on.
+#[async_trait::async_trait]
+pub trait Directive: Send + Sync + 'static {
+    /// Name of the directive, without `@`.
+    fn name(&self) -> &'static str;
+
+    /// Description of the directive, visible in introspection.
+    fn description(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Arguments of the directive.
+    fn args(&self) -> Vec<DirectiveArgument> {
+        Vec::new()
+    }
+
+    /// Called before the field is resolved, returns false to leave the field out of the response.
+    async fn include(&self, ctx: &Context<'_>, args: &DirectiveArgs<'_>) -> FieldResult<bool> {
+        let _ = (ctx, args);
+        Ok(true)
+    }
+
+    /// Transforms the resolved value of the field.
+    async fn resolve(
+        &self,
+        ctx: &Context<'_>,
+        args: &DirectiveArgs<'_>,
+        value: serde_json::Value,
+    ) -> FieldResult<serde_json::Value> {
+        let _ = (ctx, args);
+        Ok(value)
+    }
+}
+
+/// Argument of a custom directive
+pub struct DirectiveArgument {
+    pub(crate) name: &'static str,
+    pub(crate) description: Option<&'static str>,
+    pub(crate) ty: String,
+}
+
+impl DirectiveArgument {
+    /// Create an argument of type `T`.
+    pub fn new<T: Type>(name: &'static str) -> Self {
+        Self {
+            name,
+            description: None,
+            ty: T::qualified_type_name(),
+        }
+    }
+
+    /// Set the description of the argument.
+    pub fn description(self, description: &'static str) -> Self {
+        Self {
+            description: Some(description),
+            ..self
+        }
+    }
+}
+
+/// Arguments of a custom directive in the query
+pub struct DirectiveArgs<'a> {
+    pub(crate) ctx: &'a Context<'a>,
+    pub(crate) directive: &'a query::Directive,
+}
+
+impl<'a> DirectiveArgs<'a> {
+    /// Gets the value of the argument `name`, an argument that is not specified is `null`.
+    pub fn get<T: InputValueType>(&self, name: &str) -> Result<T> {
+        let value = self
+            .directive
+            .arguments
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or(query::Value::Null);
+        let value = self
+            .ctx
+            .resolve_input_value(value, self.directive.position)?;
+        parse_input_value(value, self.directive.position)
+    }
+}
@@ -0,0 +1,221 @@
+use crate::base::parse_input_value;
+use crate::dynamic::{InputObject, InputValue};
+use crate::registry::TypeName;
+use crate::{Context, FieldResult, InputValueType, Result, Value};
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Future of a field resolver
+pub type FieldFuture<'a> = Pin<Box<dyn Future<Output = FieldResult<FieldValue>> + Send + 'a>>;
+
+type BoxResolverFn = Box<dyn for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync>;
+
+/// Value returned by a field resolver
+pub enum FieldValue {
+    /// A scalar or an enum value, `null` is `FieldValue::NULL`.
+    Value(serde_json::Value),
+
+    /// A list of values.
+    List(Vec<FieldValue>),
+
+    /// Any Rust value, used as the parent value of the fields of an object.
+    OwnedAny(Box<dyn Any + Send + Sync>),
+
+    /// A value with the name of its object type, it is required when the field returns an interface or a union.
+    WithType(Box<FieldValue>, String),
+}
+
+impl FieldValue {
+    /// `null`
+    pub const NULL: FieldValue = FieldValue::Value(serde_json::Value::Null);
+
+    /// Create a scalar or an enum value.
+    pub fn value(value: impl Into<serde_json::Value>) -> Self {
+        FieldValue::Value(value.into())
+    }
+
+    /// Create a list of values.
+    pub fn list(values: impl IntoIterator<Item = FieldValue>) -> Self {
+        FieldValue::List(values.into_iter().collect())
+    }
+
+    /// Create a value from any Rust value.
+    pub fn owned_any<T: Any + Send + Sync>(value: T) -> Self {
+        FieldValue::OwnedAny(Box::new(value))
+    }
+
+    /// Set the name of the object type of the value.
+    pub fn with_type(self, type_name: impl Into<String>) -> Self {
+        FieldValue::WithType(Box::new(self), type_name.into())
+    }
+
+    /// Returns the scalar or enum value, or `None` if it is not one.
+    pub fn as_value(&self) -> Option<&serde_json::Value> {
+        match self.without_type() {
+            FieldValue::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the list of values, or `None` if it is not a list.
+    pub fn as_list(&self) -> Option<&[FieldValue]> {
+        match self.without_type() {
+            FieldValue::List(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the Rust value if it is of type `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        match self.without_type() {
+            FieldValue::OwnedAny(value) => value.downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(
+            self.without_type(),
+            FieldValue::Value(serde_json::Value::Null)
+        )
+    }
+
+    pub(crate) fn without_type(&self) -> &FieldValue {
+        match self {
+            FieldValue::WithType(value, _) => value.without_type(),
+            value => value,
+        }
+    }
+}
+
+/// Field of an object in a dynamic schema
+pub struct Field {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) args: HashMap<String, InputValue>,
+    pub(crate) ty: String,
+    pub(crate) deprecation: Option<String>,
+    pub(crate) resolver: BoxResolverFn,
+}
+
+impl Field {
+    /// Create a field of type `ty`, such as `"[Int!]!"`, resolved by the `resolver` function.
+    pub fn new<F>(name: impl Into<String>, ty: impl Into<String>, resolver: F) -> Self
+    where
+        F: for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            description: None,
+            args: Default::default(),
+            ty: ty.into(),
+            deprecation: None,
+            resolver: Box::new(resolver),
+        }
+    }
+
+    /// Set the description of the field.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add an argument to the field.
+    pub fn argument(mut self, input_value: InputValue) -> Self {
+        self.args.insert(input_value.name.clone(), input_value);
+        self
+    }
+
+    /// Mark the field as deprecated.
+    pub fn deprecation(self, reason: impl Into<String>) -> Self {
+        Self {
+            deprecation: Some(reason.into()),
+            ..self
+        }
+    }
+}
+
+/// Context of a field resolver
+pub struct ResolverContext<'a> {
+    /// Context of the field.
+    pub ctx: &'a Context<'a>,
+
+    /// Value of the object that the field belongs to, it is `FieldValue::NULL` for the fields of the root objects.
+    pub parent_value: &'a FieldValue,
+
+    pub(crate) field: &'a Field,
+    pub(crate) input_objects: &'a HashMap<String, InputObject>,
+}
+
+impl<'a> ResolverContext<'a> {
+    /// Gets the value of the argument `name`.
+    ///
+    /// The default values of the argument and of the fields of the input objects are applied,
+    /// an argument that is not specified and has no default value is `null`.
+    pub fn arg_value(&self, name: &str) -> Result<Value> {
+        let input_value = self.field.args.get(name);
+        let value = match self
+            .ctx
+            .arguments
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.clone())
+        {
+            Some(value) => self.ctx.resolve_input_value(value, self.ctx.position)?,
+            None => input_value
+                .and_then(|input_value| input_value.default_value.clone())
+                .unwrap_or(Value::Null),
+        };
+        Ok(match input_value {
+            Some(input_value) => apply_input_defaults(self.input_objects, &input_value.ty, value),
+            None => value,
+        })
+    }
+
+    /// Gets the value of the argument `name` as type `T`.
+    pub fn arg<T: InputValueType>(&self, name: &str) -> Result<T> {
+        let value = self.arg_value(name)?;
+        parse_input_value(value, self.ctx.position)
+    }
+}
+
+/// Adds the default values of the fields that are missing in the input objects of `value`.
+fn apply_input_defaults(
+    input_objects: &HashMap<String, InputObject>,
+    ty: &str,
+    value: Value,
+) -> Value {
+    match TypeName::create(ty) {
+        TypeName::NonNull(ty) => apply_input_defaults(input_objects, ty, value),
+        TypeName::List(ty) => match value {
+            Value::List(items) => Value::List(
+                items
+                    .into_iter()
+                    .map(|item| apply_input_defaults(input_objects, ty, item))
+                    .collect(),
+            ),
+            value => apply_input_defaults(input_objects, ty, value),
+        },
+        TypeName::Named(name) => match (input_objects.get(name), value) {
+            (Some(input_object), Value::Object(mut fields)) => {
+                for field in input_object.fields.values() {
+                    if let Some(value) = fields
+                        .remove(&field.name)
+                        .or_else(|| field.default_value.clone())
+                    {
+                        fields.insert(
+                            field.name.clone(),
+                            apply_input_defaults(input_objects, &field.ty, value),
+                        );
+                    }
+                }
+                Value::Object(fields)
+            }
+            (_, value) => value,
+        },
+    }
+}
@@ -0,0 +1,58 @@
+//! Dynamic schemas
+//!
+//! The types of a dynamic schema are defined at runtime instead of with the procedural macros,
+//! the fields are resolved by async functions. The queries are validated and executed the same way
+//! as for the other schemas.
+//!
+//! ```rust
+//! use async_graphql::dynamic::*;
+//! use async_graphql::{FieldError, Value};
+//!
+//! struct Book {
+//!     title: String,
+//! }
+//!
+//! let book = Object::new("Book").field(Field::new("title", "String!", |ctx| {
+//!     Box::pin(async move {
+//!         let book = ctx.parent_value.downcast_ref::<Book>().unwrap();
+//!         Ok(FieldValue::value(book.title.clone()))
+//!     })
+//! }));
+//!
+//! let query = Object::new("Query").field(
+//!     Field::new("book", "Book", |ctx| {
+//!         Box::pin(async move {
+//!             let id = ctx.arg::<i32>("id")?;
+//!             if id == 1 {
+//!                 Ok(FieldValue::owned_any(Book {
+//!                     title: "Rust".to_string(),
+//!                 }))
+//!             } else {
+//!                 Err(FieldError("Book not found".to_string(), None))
+//!             }
+//!         })
+//!     })
+//!     .argument(InputValue::new("id", "Int!").default_value(Value::Int(1.into()))),
+//! );
+//!
+//! async_std::task::block_on(async move {
+//!     let schema: Schema = SchemaBuilder::new("Query")
+//!         .register(query)
+//!         .register(book)
+//!         .build()
+//!         .unwrap()
+//!         .finish();
+//!     let res = schema.execute("{ book { title } }").await.unwrap();
+//!     assert_eq!(res.data, serde_json::json!({ "book": { "title": "Rust" } }));
+//! });
+//! ```
+
+mod field;
+mod schema;
+mod types;
+
+pub use field::{Field, FieldFuture, FieldValue, ResolverContext};
+pub use schema::{Root, Schema, SchemaBuilder, SchemaError};
+pub use types::{
+    Enum, EnumItem, InputObject, InputValue, Interface, InterfaceField, Object, Type, Union,
+};
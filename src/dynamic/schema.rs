@@ -0,0 +1,661 @@
+use crate::dynamic::{
+    Field, FieldValue, InputObject, InputValue, InterfaceField, ResolverContext, Type,
+};
+use crate::registry::{self, Registry, TypeName};
+use crate::schema::create_registry;
+use crate::types::create_introspection_type_info;
+use crate::{
+    do_resolve, Context, ContextSelectionSet, EmptySubscription, Error, ObjectType,
+    OutputValueType, Pos, QueryError, Result, Type as _, ID,
+};
+use futures::future::try_join_all;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Dynamic schema, created with `dynamic::SchemaBuilder`
+pub type Schema = crate::Schema<Root, Root, EmptySubscription>;
+
+/// Error in the definitions of a dynamic schema
+#[derive(Debug, Error, PartialEq)]
+#[error("{0}")]
+pub struct SchemaError(pub String);
+
+/// The fields and input objects of a dynamic schema, used to resolve the queries
+pub(crate) struct Types {
+    objects: HashMap<String, HashMap<String, Field>>,
+    input_objects: HashMap<String, InputObject>,
+}
+
+/// Builder of a dynamic schema
+pub struct SchemaBuilder {
+    query_type: String,
+    mutation_type: Option<String>,
+    types: Vec<Type>,
+}
+
+impl SchemaBuilder {
+    /// Create a builder of a schema whose query type is the object `query_type`.
+    pub fn new(query_type: impl Into<String>) -> Self {
+        Self {
+            query_type: query_type.into(),
+            mutation_type: None,
+            types: Default::default(),
+        }
+    }
+
+    /// Set the mutation type to the object `mutation_type`.
+    pub fn mutation(self, mutation_type: impl Into<String>) -> Self {
+        Self {
+            mutation_type: Some(mutation_type.into()),
+            ..self
+        }
+    }
+
+    /// Add a type to the schema.
+    pub fn register(mut self, ty: impl Into<Type>) -> Self {
+        self.types.push(ty.into());
+        self
+    }
+
+    /// Checks the definitions and creates the schema builder, which sets the other options of the schema.
+    ///
+    /// The names and descriptions are interned for the lifetime of the process, building the same schema again
+    /// reuses them.
+    pub fn build(
+        self,
+    ) -> std::result::Result<crate::schema::SchemaBuilder<Root, Root, EmptySubscription>, SchemaError>
+    {
+        let mut registry =
+            create_registry(self.query_type.clone(), self.mutation_type.clone(), None);
+        ID::create_type_info(&mut registry);
+
+        let mut objects = HashMap::new();
+        let mut input_objects = HashMap::new();
+        for ty in self.types {
+            if registry.types.contains_key(ty.name()) {
+                return Err(SchemaError(format!(
+                    "Type \"{}\" is defined more than once.",
+                    ty.name()
+                )));
+            }
+
+            match ty {
+                Type::Object(object) => {
+                    for interface in &object.implements {
+                        registry.add_implements(&object.name, interface);
+                    }
+                    registry.types.insert(
+                        object.name.clone(),
+                        registry::Type::Object {
+                            name: object.name.clone(),
+                            description: intern_opt(object.description),
+                            fields: object
+                                .fields
+                                .values()
+                                .map(|field| {
+                                    (
+                                        field.name.clone(),
+                                        create_field(
+                                            &field.name,
+                                            &field.description,
+                                            &field.args,
+                                            &field.ty,
+                                            &field.deprecation,
+                                        ),
+                                    )
+                                })
+                                .collect(),
+                            cache_control: Default::default(),
+                            extends: false,
+                            keys: None,
+                            visible: None,
+                        },
+                    );
+                    objects.insert(object.name, object.fields);
+                }
+                Type::Interface(interface) => {
+                    registry.types.insert(
+                        interface.name.clone(),
+                        registry::Type::Interface {
+                            name: interface.name,
+                            description: intern_opt(interface.description),
+                            fields: interface
+                                .fields
+                                .into_iter()
+                                .map(|(name, field)| {
+                                    let InterfaceField {
+                                        description,
+                                        args,
+                                        ty,
+                                        deprecation,
+                                        ..
+                                    } = field;
+                                    let field =
+                                        create_field(&name, &description, &args, &ty, &deprecation);
+                                    (name, field)
+                                })
+                                .collect(),
+                            possible_types: Default::default(),
+                            extends: false,
+                            keys: None,
+                            visible: None,
+                        },
+                    );
+                }
+                Type::Union(union) => {
+                    registry.types.insert(
+                        union.name.clone(),
+                        registry::Type::Union {
+                            name: union.name,
+                            description: intern_opt(union.description),
+                            possible_types: union.possible_types.into_iter().collect(),
+                        },
+                    );
+                }
+                Type::Enum(enum_type) => {
+                    registry.types.insert(
+                        enum_type.name.clone(),
+                        registry::Type::Enum {
+                            name: enum_type.name,
+                            description: intern_opt(enum_type.description),
+                            enum_values: enum_type
+                                .items
+                                .into_iter()
+                                .map(|item| {
+                                    let name = intern(item.name);
+                                    (
+                                        name,
+                                        registry::EnumValue {
+                                            name,
+                                            description: intern_opt(item.description),
+                                            deprecation: intern_opt(item.deprecation),
+                                        },
+                                    )
+                                })
+                                .collect(),
+                        },
+                    );
+                }
+                Type::InputObject(input_object) => {
+                    registry.types.insert(
+                        input_object.name.clone(),
+                        registry::Type::InputObject {
+                            name: input_object.name.clone(),
+                            description: intern_opt(input_object.description.clone()),
+                            input_fields: input_object
+                                .fields
+                                .values()
+                                .map(|field| (field.name.clone(), create_input_value(field)))
+                                .collect(),
+                        },
+                    );
+                    input_objects.insert(input_object.name.clone(), input_object);
+                }
+            }
+        }
+
+        let implements = registry.implements.clone();
+        for (object, interfaces) in implements {
+            for interface in interfaces {
+                if let Some(registry::Type::Interface { possible_types, .. }) =
+                    registry.types.get_mut(&interface)
+                {
+                    possible_types.insert(object.clone());
+                }
+            }
+        }
+
+        check_registry(&registry)?;
+        create_introspection_type_info(&mut registry, &self.query_type);
+
+        let types = Arc::new(Types {
+            objects,
+            input_objects,
+        });
+        Ok(crate::schema::SchemaBuilder::new(
+            Root {
+                types: types.clone(),
+                type_name: self.query_type,
+                value: FieldValue::NULL,
+            },
+            Root {
+                types,
+                type_name: self.mutation_type.unwrap_or_default(),
+                value: FieldValue::NULL,
+            },
+            EmptySubscription,
+            registry,
+        ))
+    }
+}
+
+/// The names and descriptions of the dynamic schemas, each one is allocated once.
+static STRINGS: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+fn intern(s: String) -> &'static str {
+    let mut strings = STRINGS.lock();
+    match strings.get(s.as_str()) {
+        Some(s) => s,
+        None => {
+            let s = Box::leak(s.into_boxed_str());
+            strings.insert(s);
+            s
+        }
+    }
+}
+
+fn intern_opt(s: Option<String>) -> Option<&'static str> {
+    s.map(intern)
+}
+
+fn create_input_value(input_value: &InputValue) -> registry::InputValue {
+    registry::InputValue {
+        name: intern(input_value.name.clone()),
+        description: intern_opt(input_value.description.clone()),
+        ty: input_value.ty.clone(),
+        default_value: input_value
+            .default_value
+            .as_ref()
+            .map(|value| intern(value.to_string())),
+        parsed_default_value: input_value.default_value.clone(),
+        validator: None,
+    }
+}
+
+fn create_field(
+    name: &str,
+    description: &Option<String>,
+    args: &HashMap<String, InputValue>,
+    ty: &str,
+    deprecation: &Option<String>,
+) -> registry::Field {
+    registry::Field {
+        name: name.to_string(),
+        description: intern_opt(description.clone()),
+        args: args
+            .values()
+            .map(|arg| {
+                let arg = create_input_value(arg);
+                (arg.name, arg)
+            })
+            .collect(),
+        ty: ty.to_string(),
+        deprecation: intern_opt(deprecation.clone()),
+        cache_control: Default::default(),
+        external: false,
+        requires: None,
+        compute_complexity: None,
+        visible: None,
+        provides: None,
+    }
+}
+
+/// Checks that the types referenced by the definitions exist and are used where they are allowed.
+fn check_registry(registry: &Registry) -> std::result::Result<(), SchemaError> {
+    let is_object = |name: &str| {
+        matches!(
+            registry.types.get(name),
+            Some(registry::Type::Object { .. })
+        )
+    };
+    let check_input_type =
+        |ty: &str, owner: &str| match registry.types.get(TypeName::concrete_typename(ty)) {
+            Some(registry::Type::Scalar { .. })
+            | Some(registry::Type::Enum { .. })
+            | Some(registry::Type::InputObject { .. }) => Ok(()),
+            Some(_) => Err(SchemaError(format!(
+                "Type \"{}\" of {} is not an input type.",
+                ty, owner
+            ))),
+            None => Err(SchemaError(format!(
+                "Type \"{}\" of {} is not defined.",
+                ty, owner
+            ))),
+        };
+    let check_fields = |type_name: &str, fields: &HashMap<String, registry::Field>| {
+        for field in fields.values() {
+            let owner = format!("field \"{}.{}\"", type_name, field.name);
+            match registry.types.get(TypeName::concrete_typename(&field.ty)) {
+                Some(registry::Type::InputObject { .. }) => {
+                    return Err(SchemaError(format!(
+                        "Type \"{}\" of {} is not an output type.",
+                        field.ty, owner
+                    )))
+                }
+                Some(_) => {}
+                None => {
+                    return Err(SchemaError(format!(
+                        "Type \"{}\" of {} is not defined.",
+                        field.ty, owner
+                    )))
+                }
+            }
+            for arg in field.args.values() {
+                check_input_type(&arg.ty, &format!("argument \"{}\" of {}", arg.name, owner))?;
+            }
+        }
+        Ok(())
+    };
+
+    if !is_object(&registry.query_type) {
+        return Err(SchemaError(format!(
+            "Query type \"{}\" is not an object.",
+            registry.query_type
+        )));
+    }
+    if let Some(mutation_type) = &registry.mutation_type {
+        if !is_object(mutation_type) {
+            return Err(SchemaError(format!(
+                "Mutation type \"{}\" is not an object.",
+                mutation_type
+            )));
+        }
+    }
+
+    for ty in registry.types.values() {
+        match ty {
+            registry::Type::Object { name, fields, .. } => {
+                check_fields(name, fields)?;
+                for interface in registry.implements.get(name).into_iter().flatten() {
+                    let interface_fields = match registry.types.get(interface) {
+                        Some(registry::Type::Interface { fields, .. }) => fields,
+                        _ => {
+                            return Err(SchemaError(format!(
+                                "Object \"{}\" implements \"{}\", which is not an interface.",
+                                name, interface
+                            )))
+                        }
+                    };
+                    for interface_field in interface_fields.values() {
+                        if fields.get(&interface_field.name).map(|field| &field.ty)
+                            != Some(&interface_field.ty)
+                        {
+                            return Err(SchemaError(format!(
+                                "Object \"{}\" must have the field \"{}\" of type \"{}\" of the interface \"{}\".",
+                                name, interface_field.name, interface_field.ty, interface
+                            )));
+                        }
+                    }
+                }
+            }
+            registry::Type::Interface { name, fields, .. } => check_fields(name, fields)?,
+            registry::Type::Union {
+                name,
+                possible_types,
+                ..
+            } => {
+                for possible_type in possible_types {
+                    if !is_object(possible_type) {
+                        return Err(SchemaError(format!(
+                            "Member \"{}\" of the union \"{}\" is not an object.",
+                            possible_type, name
+                        )));
+                    }
+                }
+            }
+            registry::Type::InputObject {
+                name, input_fields, ..
+            } => {
+                for field in input_fields.values() {
+                    check_input_type(&field.ty, &format!("field \"{}.{}\"", name, field.name))?;
+                }
+            }
+            registry::Type::Scalar { .. } | registry::Type::Enum { .. } => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Root object of a dynamic schema
+pub struct Root {
+    types: Arc<Types>,
+    type_name: String,
+    value: FieldValue,
+}
+
+impl crate::Type for Root {
+    fn type_name() -> Cow<'static, str> {
+        // The name of the root object is only known at runtime, see `ObjectType::registry_type_name`.
+        Cow::Borrowed("")
+    }
+
+    fn introspection_type_name(&self) -> Cow<'static, str> {
+        Cow::Owned(self.type_name.clone())
+    }
+
+    fn create_type_info(_registry: &mut Registry) -> String {
+        unreachable!("the types of a dynamic schema are registered by `SchemaBuilder::build`")
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectType for Root {
+    fn registry_type_name(&self) -> Cow<'static, str> {
+        Cow::Owned(self.type_name.clone())
+    }
+
+    async fn resolve_field(&self, ctx: &Context<'_>) -> Result<serde_json::Value> {
+        ObjectValue {
+            types: &self.types,
+            type_name: &self.type_name,
+            value: &self.value,
+        }
+        .resolve_field(ctx)
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl OutputValueType for Root {
+    async fn resolve(&self, ctx: &ContextSelectionSet<'_>, _pos: Pos) -> Result<serde_json::Value> {
+        do_resolve(ctx, self).await
+    }
+}
+
+/// A value resolved as the object `type_name`
+struct ObjectValue<'a> {
+    types: &'a Types,
+    type_name: &'a str,
+    value: &'a FieldValue,
+}
+
+impl<'a> crate::Type for ObjectValue<'a> {
+    fn type_name() -> Cow<'static, str> {
+        // The name of the object is only known at runtime, see `ObjectType::registry_type_name`.
+        Cow::Borrowed("")
+    }
+
+    fn introspection_type_name(&self) -> Cow<'static, str> {
+        Cow::Owned(self.type_name.to_string())
+    }
+
+    fn create_type_info(_registry: &mut Registry) -> String {
+        unreachable!("the types of a dynamic schema are registered by `SchemaBuilder::build`")
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> ObjectType for ObjectValue<'a> {
+    fn registry_type_name(&self) -> Cow<'static, str> {
+        Cow::Owned(self.type_name.to_string())
+    }
+
+    async fn resolve_field(&self, ctx: &Context<'_>) -> Result<serde_json::Value> {
+        let field = match self
+            .types
+            .objects
+            .get(self.type_name)
+            .and_then(|fields| fields.get(ctx.name.as_str()))
+        {
+            Some(field) => field,
+            None => {
+                return Err(QueryError::FieldNotFound {
+                    field_name: ctx.name.clone(),
+                    object: self.type_name.to_string(),
+                }
+                .into_error(ctx.position))
+            }
+        };
+
+        let value = (field.resolver)(ResolverContext {
+            ctx,
+            parent_value: self.value,
+            field,
+            input_objects: &self.types.input_objects,
+        })
+        .await
+        .map_err(|err| {
+            err.into_error_with_path(ctx.position, ctx.path_node.as_ref().unwrap().to_json())
+        })?;
+        let ctx_obj = ctx.with_selection_set(&ctx.selection_set);
+        resolve_value(self.types, &ctx_obj, &field.ty, &value, ctx.position).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> OutputValueType for ObjectValue<'a> {
+    async fn resolve(&self, ctx: &ContextSelectionSet<'_>, _pos: Pos) -> Result<serde_json::Value> {
+        do_resolve(ctx, self).await
+    }
+}
+
+/// Resolves a value returned by a field resolver as the type `ty`.
+fn resolve_value<'a>(
+    types: &'a Types,
+    ctx: &'a ContextSelectionSet<'a>,
+    ty: &'a str,
+    value: &'a FieldValue,
+    pos: Pos,
+) -> Pin<Box<dyn Future<Output = Result<serde_json::Value>> + Send + 'a>> {
+    Box::pin(async move {
+        match TypeName::create(ty) {
+            TypeName::NonNull(ty) => {
+                let res = resolve_value(types, ctx, ty, value, pos).await?;
+                if res.is_null() {
+                    return Err(value_error(
+                        ctx,
+                        pos,
+                        format!("Expected a non-null value of type \"{}!\".", ty),
+                    ));
+                }
+                Ok(res)
+            }
+            TypeName::List(item_ty) => {
+                let values;
+                let items = match value.without_type() {
+                    FieldValue::List(items) => items.as_slice(),
+                    FieldValue::Value(serde_json::Value::Null) => {
+                        return Ok(serde_json::Value::Null)
+                    }
+                    FieldValue::Value(serde_json::Value::Array(items)) => {
+                        values = items
+                            .iter()
+                            .cloned()
+                            .map(FieldValue::Value)
+                            .collect::<Vec<_>>();
+                        values.as_slice()
+                    }
+                    _ => {
+                        return Err(value_error(
+                            ctx,
+                            pos,
+                            format!("Expected a list of type \"{}\".", ty),
+                        ))
+                    }
+                };
+
+                let non_null = TypeName::create(item_ty).is_non_null();
+                let mut futures = Vec::with_capacity(items.len());
+                for (idx, item) in items.iter().enumerate() {
+                    let ctx_idx = ctx.with_index(idx);
+                    futures.push(async move {
+                        match resolve_value(types, &ctx_idx, item_ty, item, pos).await {
+                            Ok(value) => Ok(value),
+                            Err(err) => ctx_idx.resolve_error(err, non_null),
+                        }
+                    });
+                }
+                Ok(try_join_all(futures).await?.into())
+            }
+            TypeName::Named(name) => {
+                if value.is_null() {
+                    return Ok(serde_json::Value::Null);
+                }
+
+                let type_name = match ctx.registry.types.get(name) {
+                    Some(registry::Type::Scalar { .. }) => {
+                        return match value.as_value() {
+                            Some(value) => Ok(value.clone()),
+                            None => Err(value_error(
+                                ctx,
+                                pos,
+                                format!("Expected a value of the scalar \"{}\".", name),
+                            )),
+                        };
+                    }
+                    Some(registry::Type::Enum { enum_values, .. }) => {
+                        return match value.as_value() {
+                            Some(serde_json::Value::String(item))
+                                if enum_values.contains_key(item.as_str()) =>
+                            {
+                                Ok(item.clone().into())
+                            }
+                            _ => Err(value_error(
+                                ctx,
+                                pos,
+                                format!("Expected an item of the enum \"{}\".", name),
+                            )),
+                        };
+                    }
+                    Some(registry::Type::Object { .. }) => name,
+                    Some(registry::Type::Interface { possible_types, .. })
+                    | Some(registry::Type::Union { possible_types, .. }) => match value {
+                        FieldValue::WithType(_, type_name)
+                            if possible_types.contains(type_name) =>
+                        {
+                            type_name.as_str()
+                        }
+                        _ => {
+                            return Err(value_error(
+                                ctx,
+                                pos,
+                                format!(
+                                    "The value of the abstract type \"{}\" must be created with `FieldValue::with_type` and one of its possible types.",
+                                    name
+                                ),
+                            ))
+                        }
+                    },
+                    Some(registry::Type::InputObject { .. }) | None => unreachable!(),
+                };
+
+                do_resolve(
+                    ctx,
+                    &ObjectValue {
+                        types,
+                        type_name,
+                        value: value.without_type(),
+                    },
+                )
+                .await
+            }
+        }
+    })
+}
+
+fn value_error(ctx: &ContextSelectionSet<'_>, pos: Pos, message: String) -> Error {
+    Error::Query {
+        pos,
+        path: ctx.path_node.as_ref().map(|path_node| path_node.to_json()),
+        err: QueryError::FieldError {
+            err: message,
+            extended_error: None,
+        },
+    }
+}
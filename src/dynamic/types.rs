@@ -0,0 +1,351 @@
+use crate::dynamic::Field;
+use crate::Value;
+use std::collections::HashMap;
+
+/// Input value of a dynamic schema, it is an argument of a field or a field of an input object
+pub struct InputValue {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) ty: String,
+    pub(crate) default_value: Option<Value>,
+}
+
+impl InputValue {
+    /// Create an input value of type `ty`, such as `"String!"`.
+    pub fn new(name: impl Into<String>, ty: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            ty: ty.into(),
+            default_value: None,
+        }
+    }
+
+    /// Set the description of the input value.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Set the default value of the input value.
+    pub fn default_value(self, value: Value) -> Self {
+        Self {
+            default_value: Some(value),
+            ..self
+        }
+    }
+}
+
+/// Object of a dynamic schema
+pub struct Object {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) fields: HashMap<String, Field>,
+    pub(crate) implements: Vec<String>,
+}
+
+impl Object {
+    /// Create an object without fields.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            fields: Default::default(),
+            implements: Default::default(),
+        }
+    }
+
+    /// Set the description of the object.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add a field to the object.
+    pub fn field(mut self, field: Field) -> Self {
+        self.fields.insert(field.name.clone(), field);
+        self
+    }
+
+    /// Declare that the object implements the interface `name`.
+    pub fn implement(mut self, name: impl Into<String>) -> Self {
+        self.implements.push(name.into());
+        self
+    }
+}
+
+/// Field of an interface in a dynamic schema
+pub struct InterfaceField {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) args: HashMap<String, InputValue>,
+    pub(crate) ty: String,
+    pub(crate) deprecation: Option<String>,
+}
+
+impl InterfaceField {
+    /// Create a field of type `ty`, the objects implementing the interface resolve it.
+    pub fn new(name: impl Into<String>, ty: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            args: Default::default(),
+            ty: ty.into(),
+            deprecation: None,
+        }
+    }
+
+    /// Set the description of the field.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add an argument to the field.
+    pub fn argument(mut self, input_value: InputValue) -> Self {
+        self.args.insert(input_value.name.clone(), input_value);
+        self
+    }
+
+    /// Mark the field as deprecated.
+    pub fn deprecation(self, reason: impl Into<String>) -> Self {
+        Self {
+            deprecation: Some(reason.into()),
+            ..self
+        }
+    }
+}
+
+/// Interface of a dynamic schema
+pub struct Interface {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) fields: HashMap<String, InterfaceField>,
+}
+
+impl Interface {
+    /// Create an interface without fields.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            fields: Default::default(),
+        }
+    }
+
+    /// Set the description of the interface.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add a field to the interface.
+    pub fn field(mut self, field: InterfaceField) -> Self {
+        self.fields.insert(field.name.clone(), field);
+        self
+    }
+}
+
+/// Union of a dynamic schema
+pub struct Union {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) possible_types: Vec<String>,
+}
+
+impl Union {
+    /// Create a union without members.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            possible_types: Default::default(),
+        }
+    }
+
+    /// Set the description of the union.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add the object `name` to the members of the union.
+    pub fn possible_type(mut self, name: impl Into<String>) -> Self {
+        self.possible_types.push(name.into());
+        self
+    }
+}
+
+/// Item of an enum in a dynamic schema
+pub struct EnumItem {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) deprecation: Option<String>,
+}
+
+impl EnumItem {
+    /// Create an enum item.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            deprecation: None,
+        }
+    }
+
+    /// Set the description of the item.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Mark the item as deprecated.
+    pub fn deprecation(self, reason: impl Into<String>) -> Self {
+        Self {
+            deprecation: Some(reason.into()),
+            ..self
+        }
+    }
+}
+
+impl<T: Into<String>> From<T> for EnumItem {
+    fn from(name: T) -> Self {
+        EnumItem::new(name)
+    }
+}
+
+/// Enum of a dynamic schema
+pub struct Enum {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) items: Vec<EnumItem>,
+}
+
+impl Enum {
+    /// Create an enum without items.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            items: Default::default(),
+        }
+    }
+
+    /// Set the description of the enum.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add an item to the enum.
+    pub fn item(mut self, item: impl Into<EnumItem>) -> Self {
+        self.items.push(item.into());
+        self
+    }
+}
+
+/// Input object of a dynamic schema
+pub struct InputObject {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) fields: HashMap<String, InputValue>,
+}
+
+impl InputObject {
+    /// Create an input object without fields.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            fields: Default::default(),
+        }
+    }
+
+    /// Set the description of the input object.
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Add a field to the input object.
+    pub fn field(mut self, field: InputValue) -> Self {
+        self.fields.insert(field.name.clone(), field);
+        self
+    }
+}
+
+/// Type of a dynamic schema
+pub enum Type {
+    /// Object
+    Object(Object),
+
+    /// Interface
+    Interface(Interface),
+
+    /// Union
+    Union(Union),
+
+    /// Enum
+    Enum(Enum),
+
+    /// Input object
+    InputObject(InputObject),
+}
+
+impl Type {
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            Type::Object(object) => &object.name,
+            Type::Interface(interface) => &interface.name,
+            Type::Union(union) => &union.name,
+            Type::Enum(enum_type) => &enum_type.name,
+            Type::InputObject(input_object) => &input_object.name,
+        }
+    }
+}
+
+impl From<Object> for Type {
+    fn from(object: Object) -> Self {
+        Type::Object(object)
+    }
+}
+
+impl From<Interface> for Type {
+    fn from(interface: Interface) -> Self {
+        Type::Interface(interface)
+    }
+}
+
+impl From<Union> for Type {
+    fn from(union: Union) -> Self {
+        Type::Union(union)
+    }
+}
+
+impl From<Enum> for Type {
+    fn from(enum_type: Enum) -> Self {
+        Type::Enum(enum_type)
+    }
+}
+
+impl From<InputObject> for Type {
+    fn from(input_object: InputObject) -> Self {
+        Type::InputObject(input_object)
+    }
+}
@@ -138,6 +138,18 @@ pub enum QueryError {
         actual: Value,
     },
 
+    #[error("Invalid value for type \"{expect}\": {reason}.")]
+    InvalidValue {
+        /// Expect input type
+        expect: String,
+
+        /// Actual input value
+        actual: Value,
+
+        /// The reason why the value is invalid
+        reason: String,
+    },
+
     #[error("Cannot query field \"{field_name}\" on type \"{object}\".")]
     FieldNotFound {
         /// Field name
@@ -224,6 +236,15 @@ pub enum QueryError {
     #[error("Too deep")]
     TooDeep,
 
+    #[error("Query timed out")]
+    Timeout,
+
+    #[error("Rate limit exceeded, retry after {} seconds.", retry_after_secs(*.retry_after))]
+    RateLimited {
+        /// Time to wait before the query can be executed
+        retry_after: std::time::Duration,
+    },
+
     #[error("Failed to resolve field: {err}")]
     FieldError {
         err: String,
@@ -235,6 +256,18 @@ pub enum QueryError {
 
     #[error("\"__typename\" must be an existing string")]
     TypeNameNotExists,
+
+    #[error("PersistedQueryNotFound")]
+    PersistedQueryNotFound,
+
+    #[error("PersistedQueryNotSupported")]
+    PersistedQueryNotSupported,
+
+    #[error("provided sha does not match query")]
+    InvalidPersistedQueryHash,
+
+    #[error("The operation is not in the safelist.")]
+    NotSafelisted,
 }
 
 impl QueryError {
@@ -248,6 +281,11 @@ impl QueryError {
     }
 }
 
+/// Rounds up to whole seconds, as the `Retry-After` HTTP header.
+pub(crate) fn retry_after_secs(retry_after: std::time::Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
+
 #[derive(Debug, PartialEq)]
 pub struct RuleError {
     pub locations: Vec<Pos>,
@@ -308,6 +346,12 @@ pub enum ParseRequestError {
 
     #[error("The file size is too large")]
     TooLarge,
+
+    #[error("Too many operations in the batch")]
+    BatchTooLarge,
+
+    #[error("Batch requests are not supported")]
+    UnsupportedBatch,
 }
 
 #[allow(missing_docs)]
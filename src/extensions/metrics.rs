@@ -0,0 +1,343 @@
+use crate::extensions::{Extension, NextExecute, NextResolve, ResolveInfo};
+use crate::{Error, QueryResponse, Result};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+struct Histogram {
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: usize) -> Self {
+        Self {
+            counts: vec![0; buckets],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, buckets: &[f64], value: f64) {
+        if let Some(idx) = buckets.iter().position(|bound| value <= *bound) {
+            self.counts[idx] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+struct Series {
+    total: u64,
+    errors: u64,
+    duration: Histogram,
+}
+
+struct Inner {
+    buckets: Vec<f64>,
+    operations: BTreeMap<String, Series>,
+    fields: BTreeMap<(String, String), Series>,
+}
+
+impl Inner {
+    fn series<K: Ord>(
+        buckets: &[f64],
+        map: &mut BTreeMap<K, Series>,
+        key: K,
+        duration: f64,
+        is_error: bool,
+    ) {
+        let series = map.entry(key).or_insert_with(|| Series {
+            total: 0,
+            errors: 0,
+            duration: Histogram::new(buckets.len()),
+        });
+        series.total += 1;
+        if is_error {
+            series.errors += 1;
+        }
+        series.duration.observe(buckets, duration);
+    }
+}
+
+/// Metrics of the queries executed by the `Metrics` extension
+///
+/// It is shared by the extension instances of the queries, and renders the metrics in the Prometheus text
+/// exposition format, which can be served on `/metrics` by any integration:
+///
+/// - `graphql_requests_total`, `graphql_request_errors_total` and `graphql_request_duration_seconds` labeled
+///   with the name of the operation executed by the request. It is taken from the validated document, so the
+///   label is empty for anonymous operations and for the requests which fail before the execution.
+/// - `graphql_field_resolves_total`, `graphql_field_errors_total` and `graphql_field_duration_seconds` labeled
+///   with the `parent_type` and the `field` name.
+///
+/// A request is counted as an error if the query fails or its response contains errors.
+#[derive(Clone)]
+pub struct MetricsRegistry(Arc<Mutex<Inner>>);
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS.to_vec())
+    }
+}
+
+impl MetricsRegistry {
+    /// Content type of the text exposition format.
+    pub const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4";
+
+    /// Create a registry with the default buckets of the latency histograms, from 5ms to 10s.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a registry with the upper bounds of the buckets of the latency histograms, in seconds.
+    ///
+    /// The bounds which are not finite are ignored, the `+Inf` bucket is always rendered.
+    pub fn with_buckets(mut buckets: Vec<f64>) -> Self {
+        buckets.retain(|bound| bound.is_finite());
+        buckets.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        buckets.dedup();
+        Self(Arc::new(Mutex::new(Inner {
+            buckets,
+            operations: Default::default(),
+            fields: Default::default(),
+        })))
+    }
+
+    /// Render the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let inner = self.0.lock();
+        let mut out = String::new();
+
+        let operations = inner
+            .operations
+            .iter()
+            .map(|(operation, series)| (format!("operation=\"{}\"", escape(operation)), series))
+            .collect::<Vec<_>>();
+        render_series(
+            &mut out,
+            &inner.buckets,
+            (
+                "graphql_requests_total",
+                "graphql_request_errors_total",
+                "graphql_request_duration_seconds",
+            ),
+            "GraphQL requests",
+            &operations,
+        );
+
+        let fields = inner
+            .fields
+            .iter()
+            .map(|((parent_type, field), series)| {
+                (
+                    format!(
+                        "parent_type=\"{}\",field=\"{}\"",
+                        escape(parent_type),
+                        escape(field)
+                    ),
+                    series,
+                )
+            })
+            .collect::<Vec<_>>();
+        render_series(
+            &mut out,
+            &inner.buckets,
+            (
+                "graphql_field_resolves_total",
+                "graphql_field_errors_total",
+                "graphql_field_duration_seconds",
+            ),
+            "field resolutions",
+            &fields,
+        );
+
+        out
+    }
+
+    fn observe_request(&self, operation: String, duration: f64, is_error: bool) {
+        let mut inner = self.0.lock();
+        let inner = &mut *inner;
+        Inner::series(
+            &inner.buckets,
+            &mut inner.operations,
+            operation,
+            duration,
+            is_error,
+        );
+    }
+
+    fn observe_field(&self, parent_type: &str, field: &str, duration: f64, is_error: bool) {
+        let mut inner = self.0.lock();
+        let inner = &mut *inner;
+        Inner::series(
+            &inner.buckets,
+            &mut inner.fields,
+            (parent_type.to_string(), field.to_string()),
+            duration,
+            is_error,
+        );
+    }
+}
+
+/// Write the counters and the latency histogram of a group of series.
+fn render_series(
+    out: &mut String,
+    buckets: &[f64],
+    (total, errors, duration): (&str, &str, &str),
+    desc: &str,
+    series: &[(String, &Series)],
+) {
+    writeln!(out, "# HELP {} Total number of {}.", total, desc).unwrap();
+    writeln!(out, "# TYPE {} counter", total).unwrap();
+    for (labels, series) in series {
+        writeln!(out, "{}{{{}}} {}", total, labels, series.total).unwrap();
+    }
+
+    writeln!(out, "# HELP {} Total number of failed {}.", errors, desc).unwrap();
+    writeln!(out, "# TYPE {} counter", errors).unwrap();
+    for (labels, series) in series {
+        writeln!(out, "{}{{{}}} {}", errors, labels, series.errors).unwrap();
+    }
+
+    writeln!(out, "# HELP {} Duration of {} in seconds.", duration, desc).unwrap();
+    writeln!(out, "# TYPE {} histogram", duration).unwrap();
+    for (labels, series) in series {
+        let mut count = 0;
+        for (bound, n) in buckets.iter().zip(&series.duration.counts) {
+            count += n;
+            writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                duration, labels, bound, count
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            duration, labels, series.duration.count
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "{}_sum{{{}}} {}",
+            duration, labels, series.duration.sum
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "{}_count{{{}}} {}",
+            duration, labels, series.duration.count
+        )
+        .unwrap();
+    }
+}
+
+/// Escape a label value of the text exposition format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Prometheus-style metrics extension
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::extensions::{Metrics, MetricsRegistry};
+///
+/// struct Query;
+///
+/// #[Object]
+/// impl Query {
+///     async fn value(&self) -> i32 {
+///         100
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let metrics = MetricsRegistry::new();
+///     let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+///         .extension({
+///             let metrics = metrics.clone();
+///             move || Metrics::new(metrics.clone())
+///         })
+///         .finish();
+///
+///     schema.execute("{ value }").await.unwrap();
+///     assert!(metrics
+///         .render()
+///         .contains("graphql_field_resolves_total{parent_type=\"Query\",field=\"value\"} 1"));
+/// });
+/// ```
+pub struct Metrics {
+    registry: MetricsRegistry,
+    start_time: Instant,
+    operation: Mutex<Option<String>>,
+    has_error: AtomicBool,
+}
+
+impl Metrics {
+    /// Create the extension instance of a query, which records its metrics in `registry`.
+    pub fn new(registry: MetricsRegistry) -> Self {
+        Self {
+            registry,
+            start_time: Instant::now(),
+            operation: Default::default(),
+            has_error: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for Metrics {
+    fn error(&self, _err: &Error) {
+        self.has_error.store(true, Ordering::Relaxed);
+    }
+
+    fn field_error(&self, _err: &Error) {
+        self.has_error.store(true, Ordering::Relaxed);
+    }
+
+    async fn execute(&self, next: NextExecute<'_>) -> Result<QueryResponse> {
+        *self.operation.lock() = next.operation_name().map(ToString::to_string);
+        next.run().await
+    }
+
+    async fn resolve(
+        &self,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        let start_time = Instant::now();
+        let res = next.run(info).await;
+        self.registry.observe_field(
+            info.parent_type,
+            info.field_name,
+            start_time.elapsed().as_secs_f64(),
+            res.is_err(),
+        );
+        res
+    }
+}
+
+// The extension instance is dropped when the query is completed, whichever way it ends.
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        self.registry.observe_request(
+            self.operation.lock().take().unwrap_or_default(),
+            self.start_time.elapsed().as_secs_f64(),
+            self.has_error.load(Ordering::Relaxed),
+        );
+    }
+}
@@ -1,11 +1,27 @@
 //! Extensions for schema
+//!
+//! Besides the synchronous hooks called at each stage of a query, an extension can wrap the asynchronous
+//! stages with the "around" hooks:
+//!
+//! - `Extension::prepare_request` modifies the query builder before the query is parsed, for example to rewrite
+//!   the query source or to add a context data.
+//! - `Extension::execute` wraps the execution of the query, it can return a response without calling
+//!   `NextExecute::run`, or modify the response it returns.
+//! - `Extension::resolve` wraps the resolution of each field.
+//!
+//! The hooks are called in the order the extensions are added to the schema, the first one is the outermost.
 
 mod apollo_tracing;
+mod metrics;
 mod tracing;
 
 pub use self::tracing::Tracing;
-use crate::context::{QueryPathNode, ResolveId};
+use crate::context::QueryPathNode;
+pub use crate::context::ResolveId;
+use crate::{Error, QueryBuilder, QueryResponse, Result};
 pub use apollo_tracing::ApolloTracing;
+use futures::future::BoxFuture;
+pub use metrics::{Metrics, MetricsRegistry};
 
 pub(crate) type BoxExtension = Box<dyn Extension>;
 
@@ -21,12 +37,119 @@ pub struct ResolveInfo<'a> {
     /// Parent type
     pub parent_type: &'a str,
 
+    /// Field name, the path node contains the alias if there is one.
+    pub field_name: &'a str,
+
     /// Current return type, is qualified name.
     pub return_type: &'a str,
 }
 
+/// Calls the end hook of the extensions when it is dropped, so that it is called on every exit path,
+/// including the errors and the cancellation of the query on timeout.
+pub(crate) struct EndGuard<'a, F: Fn(&BoxExtension)> {
+    extensions: &'a [BoxExtension],
+    end: F,
+}
+
+impl<'a, F: Fn(&BoxExtension)> EndGuard<'a, F> {
+    /// Call the start hook of the extensions, the end hook is called when the guard is dropped.
+    pub(crate) fn start<S: Fn(&BoxExtension)>(
+        extensions: &'a [BoxExtension],
+        start: S,
+        end: F,
+    ) -> Self {
+        extensions.iter().for_each(start);
+        Self { extensions, end }
+    }
+}
+
+impl<'a, F: Fn(&BoxExtension)> Drop for EndGuard<'a, F> {
+    fn drop(&mut self) {
+        self.extensions.iter().for_each(&self.end);
+    }
+}
+
+/// The rest of the execution, passed to `Extension::execute`
+pub struct NextExecute<'a> {
+    extensions: &'a [BoxExtension],
+    operation_name: Option<&'a str>,
+    execute: BoxFuture<'a, Result<QueryResponse>>,
+}
+
+impl<'a> NextExecute<'a> {
+    pub(crate) fn new(
+        extensions: &'a [BoxExtension],
+        operation_name: Option<&'a str>,
+        execute: BoxFuture<'a, Result<QueryResponse>>,
+    ) -> Self {
+        Self {
+            extensions,
+            operation_name,
+            execute,
+        }
+    }
+
+    /// Name of the operation selected in the validated document, `None` if it is anonymous.
+    pub fn operation_name(&self) -> Option<&'a str> {
+        self.operation_name
+    }
+
+    /// Call the next extension, or execute the query if it is the last one.
+    pub async fn run(self) -> Result<QueryResponse> {
+        match self.extensions.split_first() {
+            Some((extension, extensions)) => {
+                extension
+                    .execute(NextExecute {
+                        extensions,
+                        operation_name: self.operation_name,
+                        execute: self.execute,
+                    })
+                    .await
+            }
+            None => self.execute.await,
+        }
+    }
+}
+
+/// The rest of the resolution of a field, passed to `Extension::resolve`
+pub struct NextResolve<'a> {
+    extensions: &'a [BoxExtension],
+    resolve: BoxFuture<'a, Result<serde_json::Value>>,
+}
+
+impl<'a> NextResolve<'a> {
+    pub(crate) fn new(
+        extensions: &'a [BoxExtension],
+        resolve: BoxFuture<'a, Result<serde_json::Value>>,
+    ) -> Self {
+        Self {
+            extensions,
+            resolve,
+        }
+    }
+
+    /// Call the next extension, or resolve the field if it is the last one.
+    pub async fn run(self, info: &ResolveInfo<'_>) -> Result<serde_json::Value> {
+        match self.extensions.split_first() {
+            Some((extension, extensions)) => {
+                extension
+                    .resolve(
+                        info,
+                        NextResolve {
+                            extensions,
+                            resolve: self.resolve,
+                        },
+                    )
+                    .await
+            }
+            None => self.resolve.await,
+        }
+    }
+}
+
 /// Represents a GraphQL extension
 #[allow(unused_variables)]
+#[async_trait::async_trait]
 pub trait Extension: Sync + Send + 'static {
     /// If this extension needs to output data to query results, you need to specify a name.
     fn name(&self) -> Option<&'static str> {
@@ -57,8 +180,37 @@ pub trait Extension: Sync + Send + 'static {
     /// Called at the end of the resolve field.
     fn resolve_field_end(&self, resolve_id: ResolveId) {}
 
+    /// Called when the query ends with an error instead of a response, such as a parse or validation error.
+    fn error(&self, err: &Error) {}
+
+    /// Called for each error of a field, in the response or in a patch of the incremental delivery.
+    fn field_error(&self, err: &Error) {}
+
     /// Get the results
     fn result(&self) -> Option<serde_json::Value> {
         None
     }
+
+    /// Called before the query is parsed, the returned query builder is executed instead.
+    ///
+    /// Returning an error ends the query with it.
+    async fn prepare_request(&self, request: QueryBuilder) -> Result<QueryBuilder> {
+        Ok(request)
+    }
+
+    /// Wraps the execution of the query, after it is parsed and validated.
+    ///
+    /// With `QueryBuilder::execute_stream`, it wraps the execution of the initial response.
+    async fn execute(&self, next: NextExecute<'_>) -> Result<QueryResponse> {
+        next.run().await
+    }
+
+    /// Wraps the resolution of a field, between `Extension::resolve_field_start` and `Extension::resolve_field_end`.
+    async fn resolve(
+        &self,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        next.run(info).await
+    }
 }
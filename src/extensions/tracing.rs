@@ -1,6 +1,6 @@
 use crate::context::ResolveId;
 use crate::extensions::{Extension, ResolveInfo};
-use crate::QueryPathSegment;
+use crate::{Error, QueryPathSegment};
 use parking_lot::Mutex;
 use std::collections::BTreeMap;
 use tracing::{span, Id, Level};
@@ -43,6 +43,13 @@ impl Extension for Tracing {
         }
     }
 
+    fn error(&self, _err: &Error) {
+        // The query ended without a response, the errors of the fields are reported with `field_error`.
+        if let Some(id) = self.inner.lock().root_id.take() {
+            tracing::dispatcher::get_default(|d| d.exit(&id));
+        }
+    }
+
     fn resolve_field_start(&self, info: &ResolveInfo<'_>) {
         let mut inner = self.inner.lock();
         let parent_span = info
@@ -1,4 +1,16 @@
 //! Field guards
+//!
+//! The guards are specified with `#[field(guard(...))]`, they are all checked before the field is resolved.
+//! A guard specified on the type, such as `#[Object(guard(...))]`, applies to every field of the type and is checked
+//! before the guards of the field.
+//! Use `and(...)`, `or(...)` and `not(...)` to combine them:
+//!
+//! ```ignore
+//! #[field(guard(or(RoleGuard(role = "Role::Admin"), not(BannedGuard))))]
+//! ```
+//!
+//! The parameters of a guard can refer to the arguments of the field by name, such as `OwnerGuard(id = "id.clone()")`,
+//! such a guard is checked after the arguments are parsed, the other guards are checked before.
 
 use crate::{Context, FieldResult};
 
@@ -17,6 +29,16 @@ pub trait GuardExt: Guard + Sized {
     fn and<R: Guard>(self, other: R) -> GuardAnd<Self, R> {
         GuardAnd(self, other)
     }
+
+    /// Pass if any of the two guards passes, the error of the second guard is returned if both fail.
+    fn or<R: Guard>(self, other: R) -> GuardOr<Self, R> {
+        GuardOr(self, other)
+    }
+
+    /// Invert the guard.
+    fn not(self) -> GuardNot<Self> {
+        GuardNot(self)
+    }
 }
 
 impl<T: Guard> GuardExt for T {}
@@ -31,3 +53,29 @@ impl<A: Guard + Send + Sync, B: Guard + Send + Sync> Guard for GuardAnd<A, B> {
         self.1.check(ctx).await
     }
 }
+
+/// Guard for `GuardExt::or`
+pub struct GuardOr<A: Guard, B: Guard>(A, B);
+
+#[async_trait::async_trait]
+impl<A: Guard + Send + Sync, B: Guard + Send + Sync> Guard for GuardOr<A, B> {
+    async fn check(&self, ctx: &Context<'_>) -> FieldResult<()> {
+        match self.0.check(ctx).await {
+            Ok(()) => Ok(()),
+            Err(_) => self.1.check(ctx).await,
+        }
+    }
+}
+
+/// Guard for `GuardExt::not`
+pub struct GuardNot<A: Guard>(A);
+
+#[async_trait::async_trait]
+impl<A: Guard + Send + Sync> Guard for GuardNot<A> {
+    async fn check(&self, ctx: &Context<'_>) -> FieldResult<()> {
+        match self.0.check(ctx).await {
+            Ok(()) => Err("Forbidden".into()),
+            Err(_) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::http::{serialize_response_entries, GQLErrors};
+use crate::{IncrementalResponse, QueryPatch};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http::HeaderMap;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+/// Content type of the `multipart/mixed` body created by `multipart_mixed_stream`.
+pub const MULTIPART_MIXED_CONTENT_TYPE: &str = "multipart/mixed; boundary=\"-\"";
+
+/// Serializable response of a query executed with incremental delivery
+///
+/// Every payload contains `hasNext`, which is false in the last one.
+pub struct GQLIncrementalResponse(pub IncrementalResponse);
+
+impl Serialize for GQLIncrementalResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match &self.0 {
+            IncrementalResponse::Initial { response, has_next } => {
+                serialize_response_entries(&mut map, response)?;
+                map.serialize_entry("hasNext", has_next)?;
+            }
+            IncrementalResponse::Patch(QueryPatch::Defer {
+                label,
+                path,
+                data,
+                errors,
+            }) => {
+                if let Some(label) = label {
+                    map.serialize_entry("label", label)?;
+                }
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("data", data)?;
+                if !errors.is_empty() {
+                    map.serialize_entry("errors", &GQLErrors(errors))?;
+                }
+                map.serialize_entry("hasNext", &true)?;
+            }
+            IncrementalResponse::Patch(QueryPatch::Stream {
+                label,
+                path,
+                items,
+                errors,
+            }) => {
+                if let Some(label) = label {
+                    map.serialize_entry("label", label)?;
+                }
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("items", items)?;
+                if !errors.is_empty() {
+                    map.serialize_entry("errors", &GQLErrors(errors))?;
+                }
+                map.serialize_entry("hasNext", &true)?;
+            }
+            IncrementalResponse::Complete => {
+                map.serialize_entry("hasNext", &false)?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Encodes the responses of `QueryBuilder::execute_stream` as a `multipart/mixed` body,
+/// with the content type `MULTIPART_MIXED_CONTENT_TYPE`.
+pub fn multipart_mixed_stream<S>(stream: S) -> impl Stream<Item = Bytes>
+where
+    S: Stream<Item = IncrementalResponse>,
+{
+    stream
+        .map(|response| {
+            let mut data =
+                b"\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n".to_vec();
+            // The serialization of `GQLIncrementalResponse` never fails.
+            serde_json::to_writer(&mut data, &GQLIncrementalResponse(response)).unwrap();
+            Bytes::from(data)
+        })
+        .chain(futures::stream::once(futures::future::ready(
+            Bytes::from_static(b"\r\n-----\r\n"),
+        )))
+}
+
+/// Waits for the initial response of `QueryBuilder::execute_stream`, and returns the HTTP headers set by its resolvers
+/// with the stream of all the responses.
+pub async fn incremental_http_headers<S>(
+    stream: S,
+) -> (HeaderMap, impl Stream<Item = IncrementalResponse>)
+where
+    S: Stream<Item = IncrementalResponse>,
+{
+    let mut stream = Box::pin(stream);
+    let initial = stream.next().await;
+    let http_headers = match &initial {
+        Some(IncrementalResponse::Initial {
+            response: Ok(resp), ..
+        }) => resp.http_headers.clone(),
+        _ => HeaderMap::new(),
+    };
+    (http_headers, futures::stream::iter(initial).chain(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QueryResponse;
+    use serde_json::json;
+
+    #[test]
+    fn test_incremental_response() {
+        let resp = GQLIncrementalResponse(IncrementalResponse::Initial {
+            response: Ok(QueryResponse {
+                data: json!({"a": 1}),
+                errors: Vec::new(),
+                extensions: None,
+                cache_control: Default::default(),
+                http_headers: Default::default(),
+            }),
+            has_next: true,
+        });
+        assert_eq!(
+            serde_json::to_value(resp).unwrap(),
+            json!({"data": {"a": 1}, "hasNext": true})
+        );
+
+        let resp = GQLIncrementalResponse(IncrementalResponse::Patch(QueryPatch::Stream {
+            label: Some("items".to_string()),
+            path: json!(["items", 2]),
+            items: json!([3]),
+            errors: Vec::new(),
+        }));
+        assert_eq!(
+            serde_json::to_value(resp).unwrap(),
+            json!({"label": "items", "path": ["items", 2], "items": [3], "hasNext": true})
+        );
+
+        let resp = GQLIncrementalResponse(IncrementalResponse::Complete);
+        assert_eq!(
+            serde_json::to_value(resp).unwrap(),
+            json!({"hasNext": false})
+        );
+    }
+
+    #[async_std::test]
+    async fn test_multipart_mixed_stream() {
+        let body = multipart_mixed_stream(futures::stream::iter(vec![
+            IncrementalResponse::Patch(QueryPatch::Defer {
+                label: None,
+                path: json!([]),
+                data: json!({"a": 1}),
+                errors: Vec::new(),
+            }),
+            IncrementalResponse::Complete,
+        ]))
+        .collect::<Vec<_>>()
+        .await
+        .concat();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{\"path\":[],\"data\":{\"a\":1},\"hasNext\":true}\
+             \r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{\"hasNext\":false}\
+             \r\n-----\r\n"
+        );
+    }
+}
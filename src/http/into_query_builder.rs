@@ -1,10 +1,11 @@
 use crate::http::multipart::{Multipart, PartData};
-use crate::http::GQLRequest;
-use crate::query::{IntoQueryBuilder, IntoQueryBuilderOpts};
-use crate::{ParseRequestError, QueryBuilder};
+use crate::http::GQLBatchRequest;
+use crate::query::{IntoBatchRequest, IntoQueryBuilder, IntoQueryBuilderOpts};
+use crate::{BatchRequest, ParseRequestError, QueryBuilder};
 use futures::{AsyncRead, AsyncReadExt};
 use mime::Mime;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[async_trait::async_trait]
 impl<CT, Body> IntoQueryBuilder for (Option<CT>, Body)
@@ -13,9 +14,26 @@ where
     Body: AsyncRead + Send + Unpin,
 {
     async fn into_query_builder_opts(
-        mut self,
+        self,
         opts: &IntoQueryBuilderOpts,
     ) -> std::result::Result<QueryBuilder, ParseRequestError> {
+        match self.into_batch_request_opts(opts).await? {
+            BatchRequest::Single(builder) => Ok(builder),
+            BatchRequest::Batch(_) => Err(ParseRequestError::UnsupportedBatch),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<CT, Body> IntoBatchRequest for (Option<CT>, Body)
+where
+    CT: AsRef<str> + Send,
+    Body: AsyncRead + Send + Unpin,
+{
+    async fn into_batch_request_opts(
+        mut self,
+        opts: &IntoQueryBuilderOpts,
+    ) -> std::result::Result<BatchRequest, ParseRequestError> {
         if let Some(boundary) = self
             .0
             .and_then(|value| value.as_ref().parse::<Mime>().ok())
@@ -37,7 +55,7 @@ where
                 opts.max_num_files,
             )
             .await?;
-            let gql_request: GQLRequest = {
+            let gql_request: GQLBatchRequest = {
                 let part = multipart
                     .remove("operations")
                     .ok_or_else(|| ParseRequestError::MissingOperatorsPart)?;
@@ -52,7 +70,7 @@ where
                 serde_json::from_reader(reader).map_err(ParseRequestError::InvalidFilesMap)?
             };
 
-            let mut builder = gql_request.into_query_builder().await?;
+            let mut batch = gql_request.into_batch_request_opts(opts).await?;
 
             // read files
             for part in &multipart.parts {
@@ -62,12 +80,31 @@ where
                             if let (Some(filename), PartData::File(path)) =
                                 (&part.filename, &part.data)
                             {
-                                builder.set_upload(
-                                    &var_path,
-                                    &filename,
-                                    part.content_type.as_deref(),
-                                    path,
-                                );
+                                // In a batch, the paths are prefixed with the index of the operation.
+                                let target = match &mut batch {
+                                    BatchRequest::Single(builder) => {
+                                        Some((builder, var_path.as_str()))
+                                    }
+                                    BatchRequest::Batch(builders) => {
+                                        let mut s = var_path.splitn(2, '.');
+                                        let idx =
+                                            s.next().and_then(|idx| idx.parse::<usize>().ok());
+                                        match (idx, s.next()) {
+                                            (Some(idx), Some(var_path)) => builders
+                                                .get_mut(idx)
+                                                .map(|builder| (builder, var_path)),
+                                            _ => None,
+                                        }
+                                    }
+                                };
+                                if let Some((builder, var_path)) = target {
+                                    builder.set_upload(
+                                        var_path,
+                                        &filename,
+                                        part.content_type.as_deref(),
+                                        path,
+                                    );
+                                }
                             }
                         }
                     }
@@ -79,19 +116,27 @@ where
             }
 
             if let Some(temp_dir) = multipart.temp_dir {
-                builder.set_files_holder(temp_dir);
+                let temp_dir = Arc::new(temp_dir);
+                match &mut batch {
+                    BatchRequest::Single(builder) => builder.files_holder = Some(temp_dir),
+                    BatchRequest::Batch(builders) => {
+                        for builder in builders {
+                            builder.files_holder = Some(temp_dir.clone());
+                        }
+                    }
+                }
             }
 
-            Ok(builder)
+            Ok(batch)
         } else {
             let mut data = Vec::new();
             self.1
                 .read_to_end(&mut data)
                 .await
                 .map_err(ParseRequestError::Io)?;
-            let gql_request: GQLRequest =
+            let gql_request: GQLBatchRequest =
                 serde_json::from_slice(&data).map_err(ParseRequestError::InvalidRequest)?;
-            gql_request.into_query_builder().await
+            gql_request.into_batch_request_opts(opts).await
         }
     }
 }
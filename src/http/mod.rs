@@ -1,6 +1,7 @@
 //! A helper module that supports HTTP
 
 mod graphiql_source;
+mod incremental;
 mod into_query_builder;
 mod multipart;
 mod playground_source;
@@ -9,28 +10,68 @@ mod token_reader;
 
 use itertools::Itertools;
 
+pub use ::http::{HeaderMap, HeaderName, HeaderValue};
 pub use graphiql_source::graphiql_source;
+pub use incremental::{
+    incremental_http_headers, multipart_mixed_stream, GQLIncrementalResponse,
+    MULTIPART_MIXED_CONTENT_TYPE,
+};
 pub use playground_source::playground_source;
 pub use stream_body::StreamBody;
 
-use crate::query::{IntoQueryBuilder, IntoQueryBuilderOpts};
-use crate::{Error, ParseRequestError, QueryBuilder, QueryError, QueryResponse, Result, Variables};
+use crate::error::retry_after_secs;
+use crate::query::{IntoBatchRequest, IntoQueryBuilder, IntoQueryBuilderOpts};
+use crate::{
+    BatchRequest, BatchResponse, Error, ParseRequestError, QueryBuilder, QueryError, QueryResponse,
+    Result, Variables,
+};
 use graphql_parser::Pos;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
 use serde::ser::{SerializeMap, SerializeSeq};
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Deserializable GraphQL Request object
 #[derive(Deserialize, Clone, PartialEq, Debug)]
 pub struct GQLRequest {
     /// Query source
+    ///
+    /// It can be omitted if the request contains a persisted query hash or a document id.
+    #[serde(default)]
     pub query: String,
 
+    /// Id of a document in the safelist of the schema
+    #[serde(default)]
+    pub id: Option<String>,
+
     /// Operation name for this query
     #[serde(rename = "operationName")]
     pub operation_name: Option<String>,
 
     /// Variables for this query
     pub variables: Option<serde_json::Value>,
+
+    /// Extensions for this query
+    pub extensions: Option<GQLRequestExtensions>,
+}
+
+/// Deserializable extensions of the GraphQL Request object
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct GQLRequestExtensions {
+    /// Automatic persisted query
+    #[serde(rename = "persistedQuery")]
+    pub persisted_query: Option<GQLPersistedQuery>,
+}
+
+/// Deserializable automatic persisted query
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct GQLPersistedQuery {
+    /// Protocol version
+    pub version: i32,
+
+    /// SHA-256 hash of the query source
+    #[serde(rename = "sha256Hash")]
+    pub sha256_hash: String,
 }
 
 #[async_trait::async_trait]
@@ -43,39 +84,157 @@ impl IntoQueryBuilder for GQLRequest {
         if let Some(operation_name) = self.operation_name {
             builder = builder.operator_name(operation_name);
         }
+        if let Some(id) = self.id {
+            builder = builder.document_id(id);
+        }
         if let Some(variables) = self.variables {
             if let Ok(variables) = Variables::parse_from_json(variables) {
                 builder = builder.variables(variables);
             }
         }
+        if let Some(persisted_query) = self
+            .extensions
+            .and_then(|extensions| extensions.persisted_query)
+        {
+            builder = builder.persisted_query_hash(persisted_query.sha256_hash);
+        }
         Ok(builder)
     }
 }
 
+/// Deserializable GraphQL batch request object
+///
+/// It accepts a single request object, or an array of request objects.
+///
+/// The first token selects the kind of request, so the error of the request object is reported as is.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GQLBatchRequest {
+    /// Single request object
+    Single(GQLRequest),
+
+    /// Array of request objects
+    Batch(Vec<GQLRequest>),
+}
+
+impl<'de> Deserialize<'de> for GQLBatchRequest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct BatchRequestVisitor;
+
+        impl<'de> Visitor<'de> for BatchRequestVisitor {
+            type Value = GQLBatchRequest;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a request object or an array of request objects")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                GQLRequest::deserialize(MapAccessDeserializer::new(map))
+                    .map(GQLBatchRequest::Single)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                seq: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                Vec::deserialize(SeqAccessDeserializer::new(seq)).map(GQLBatchRequest::Batch)
+            }
+        }
+
+        deserializer.deserialize_any(BatchRequestVisitor)
+    }
+}
+
+#[async_trait::async_trait]
+impl IntoBatchRequest for GQLBatchRequest {
+    async fn into_batch_request_opts(
+        self,
+        opts: &IntoQueryBuilderOpts,
+    ) -> std::result::Result<BatchRequest, ParseRequestError> {
+        match self {
+            GQLBatchRequest::Single(request) => Ok(BatchRequest::Single(
+                request.into_query_builder_opts(opts).await?,
+            )),
+            GQLBatchRequest::Batch(requests) => {
+                if let Some(max_batch_size) = opts.max_batch_size {
+                    if requests.len() > max_batch_size {
+                        return Err(ParseRequestError::BatchTooLarge);
+                    }
+                }
+                let mut builders = Vec::with_capacity(requests.len());
+                for request in requests {
+                    builders.push(request.into_query_builder_opts(opts).await?);
+                }
+                Ok(BatchRequest::Batch(builders))
+            }
+        }
+    }
+}
+
 /// Serializable GraphQL Response object
 pub struct GQLResponse(pub Result<QueryResponse>);
 
 impl Serialize for GQLResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        GQLResponseRef(&self.0).serialize(serializer)
+    }
+}
+
+/// Serializable GraphQL batch response object
+///
+/// A single response is serialized as an object, and the responses of a batch as an array.
+pub struct GQLBatchResponse(pub BatchResponse);
+
+impl Serialize for GQLBatchResponse {
     fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
         match &self.0 {
-            Ok(res) => {
-                let mut map = serializer.serialize_map(None)?;
-                map.serialize_key("data")?;
-                map.serialize_value(&res.data)?;
-                if res.extensions.is_some() {
-                    map.serialize_key("extensions")?;
-                    map.serialize_value(&res.extensions)?;
+            BatchResponse::Single(res) => GQLResponseRef(res).serialize(serializer),
+            BatchResponse::Batch(responses) => {
+                let mut seq = serializer.serialize_seq(Some(responses.len()))?;
+                for res in responses {
+                    seq.serialize_element(&GQLResponseRef(res))?;
                 }
-                map.end()
+                seq.end()
             }
-            Err(err) => {
-                let mut map = serializer.serialize_map(None)?;
+        }
+    }
+}
+
+struct GQLResponseRef<'a>(&'a Result<QueryResponse>);
+
+impl<'a> Serialize for GQLResponseRef<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        serialize_response_entries(&mut map, self.0)?;
+        map.end()
+    }
+}
+
+fn serialize_response_entries<S: SerializeMap>(
+    map: &mut S,
+    res: &Result<QueryResponse>,
+) -> std::result::Result<(), S::Error> {
+    match res {
+        Ok(res) => {
+            map.serialize_key("data")?;
+            map.serialize_value(&res.data)?;
+            if !res.errors.is_empty() {
                 map.serialize_key("errors")?;
-                map.serialize_value(&GQLError(err))?;
-                map.end()
+                map.serialize_value(&GQLErrors(&res.errors))?;
+            }
+            if res.extensions.is_some() {
+                map.serialize_key("extensions")?;
+                map.serialize_value(&res.extensions)?;
             }
         }
+        Err(err) => {
+            map.serialize_key("errors")?;
+            map.serialize_value(&GQLError(err))?;
+        }
     }
+    Ok(())
 }
 
 /// Serializable error type
@@ -86,61 +245,87 @@ impl<'a> Serialize for GQLError<'a> {
     where
         S: Serializer,
     {
-        match self.0 {
-            Error::Parse {
-                line,
-                column,
-                message,
-            } => {
-                let mut seq = serializer.serialize_seq(Some(1))?;
-                seq.serialize_element(&serde_json::json! ({
-                    "message": message,
-                    "locations": [{"line": line, "column": column}]
-                }))?;
-                seq.end()
-            }
-            Error::Query { pos, path, err } => {
-                let mut seq = serializer.serialize_seq(Some(1))?;
-                if let QueryError::FieldError {
-                    err,
-                    extended_error,
-                } = err
-                {
-                    let mut map = serde_json::Map::new();
-
-                    map.insert("message".to_string(), err.to_string().into());
-                    map.insert(
-                        "locations".to_string(),
-                        serde_json::json!([{"line": pos.line, "column": pos.column}]),
-                    );
-
-                    if let Some(path) = path {
-                        map.insert("path".to_string(), path.clone());
-                    }
+        let mut seq = serializer.serialize_seq(None)?;
+        serialize_error_elements(&mut seq, self.0)?;
+        seq.end()
+    }
+}
 
-                    if let Some(obj @ serde_json::Value::Object(_)) = extended_error {
-                        map.insert("extensions".to_string(), obj.clone());
-                    }
+struct GQLErrors<'a>(&'a [Error]);
 
-                    seq.serialize_element(&serde_json::Value::Object(map))?;
-                } else {
-                    seq.serialize_element(&serde_json::json!({
-                        "message": err.to_string(),
-                        "locations": [{"line": pos.line, "column": pos.column}]
-                    }))?;
+impl<'a> Serialize for GQLErrors<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(None)?;
+        for err in self.0 {
+            serialize_error_elements(&mut seq, err)?;
+        }
+        seq.end()
+    }
+}
+
+fn serialize_error_elements<S: SerializeSeq>(
+    seq: &mut S,
+    err: &Error,
+) -> std::result::Result<(), S::Error> {
+    match err {
+        Error::Parse {
+            line,
+            column,
+            message,
+        } => seq.serialize_element(&serde_json::json! ({
+            "message": message,
+            "locations": [{"line": line, "column": column}]
+        })),
+        Error::Query { pos, path, err } => {
+            if let QueryError::FieldError {
+                err,
+                extended_error,
+            } = err
+            {
+                let mut map = serde_json::Map::new();
+
+                map.insert("message".to_string(), err.to_string().into());
+                map.insert(
+                    "locations".to_string(),
+                    serde_json::json!([{"line": pos.line, "column": pos.column}]),
+                );
+
+                if let Some(path) = path {
+                    map.insert("path".to_string(), path.clone());
                 }
-                seq.end()
-            }
-            Error::Rule { errors } => {
-                let mut seq = serializer.serialize_seq(Some(1))?;
-                for error in errors {
-                    seq.serialize_element(&serde_json::json!({
-                        "message": error.message,
-                        "locations": error.locations.iter().map(|pos| serde_json::json!({"line": pos.line, "column": pos.column})).collect_vec(),
-                    }))?;
+
+                if let Some(obj @ serde_json::Value::Object(_)) = extended_error {
+                    map.insert("extensions".to_string(), obj.clone());
                 }
-                seq.end()
+
+                seq.serialize_element(&serde_json::Value::Object(map))
+            } else if let QueryError::RateLimited { retry_after } = err {
+                // The whole request is limited, the error has no location in the query.
+                seq.serialize_element(&serde_json::json!({
+                    "message": err.to_string(),
+                    "extensions": {
+                        "code": "RATE_LIMITED",
+                        "retryAfter": retry_after_secs(*retry_after),
+                    }
+                }))
+            } else {
+                seq.serialize_element(&serde_json::json!({
+                    "message": err.to_string(),
+                    "locations": [{"line": pos.line, "column": pos.column}]
+                }))
+            }
+        }
+        Error::Rule { errors } => {
+            for error in errors {
+                seq.serialize_element(&serde_json::json!({
+                    "message": error.message,
+                    "locations": error.locations.iter().map(|pos| serde_json::json!({"line": pos.line, "column": pos.column})).collect_vec(),
+                }))?;
             }
+            Ok(())
         }
     }
 }
@@ -211,12 +396,102 @@ mod tests {
         assert_eq!(request.query, "{ a b c }");
     }
 
+    #[test]
+    fn test_request_with_persisted_query() {
+        let request: GQLRequest = serde_json::from_value(json! ({
+            "extensions": {
+                "persistedQuery": {
+                    "version": 1,
+                    "sha256Hash": "abc",
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(request.query, "");
+        assert_eq!(
+            request
+                .extensions
+                .and_then(|extensions| extensions.persisted_query)
+                .map(|persisted_query| persisted_query.sha256_hash)
+                .as_deref(),
+            Some("abc")
+        );
+    }
+
+    #[test]
+    fn test_batch_request() {
+        let request: GQLBatchRequest = serde_json::from_value(json! ({
+            "query": "{ a b c }"
+        }))
+        .unwrap();
+        assert!(matches!(request, GQLBatchRequest::Single(_)));
+
+        let request: GQLBatchRequest = serde_json::from_value(json! ([
+            {"query": "{ a }"},
+            {"query": "{ b }", "operationName": "b"},
+        ]))
+        .unwrap();
+        if let GQLBatchRequest::Batch(requests) = request {
+            assert_eq!(requests.len(), 2);
+            assert_eq!(requests[0].query, "{ a }");
+            assert_eq!(requests[1].operation_name.as_deref(), Some("b"));
+        } else {
+            panic!("expect a batch request");
+        }
+
+        let err = serde_json::from_str::<GQLBatchRequest>(r#"{"query": 1}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid type: integer `1`, expected a string at line 1 column 11"
+        );
+
+        let err =
+            serde_json::from_str::<GQLBatchRequest>(r#"[{"query": "{ a }"}, 1]"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid type: integer `1`, expected struct GQLRequest at line 1 column 22"
+        );
+    }
+
+    #[test]
+    fn test_batch_response() {
+        let resp = GQLBatchResponse(BatchResponse::Batch(vec![
+            Ok(QueryResponse {
+                data: json!({"ok": true}),
+                errors: Vec::new(),
+                extensions: None,
+                cache_control: Default::default(),
+                http_headers: Default::default(),
+            }),
+            Err(Error::Query {
+                pos: Pos {
+                    line: 10,
+                    column: 20,
+                },
+                path: None,
+                err: QueryError::NotSupported,
+            }),
+        ]));
+        assert_eq!(
+            serde_json::to_value(resp).unwrap(),
+            json!([
+                {"data": {"ok": true}},
+                {"errors": [{
+                    "message":"Not supported.",
+                    "locations": [{"line": 10, "column": 20}]
+                }]},
+            ])
+        );
+    }
+
     #[test]
     fn test_response_data() {
         let resp = GQLResponse(Ok(QueryResponse {
             data: json!({"ok": true}),
+            errors: Vec::new(),
             extensions: None,
             cache_control: Default::default(),
+            http_headers: Default::default(),
         }));
         assert_eq!(
             serde_json::to_value(resp).unwrap(),
@@ -228,6 +503,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_response_data_with_errors() {
+        let resp = GQLResponse(Ok(QueryResponse {
+            data: json!({"ok": null}),
+            errors: vec![Error::Query {
+                pos: Pos { line: 1, column: 3 },
+                path: Some(json!(["ok"])),
+                err: QueryError::FieldError {
+                    err: "MyErrorMessage".to_owned(),
+                    extended_error: None,
+                },
+            }],
+            extensions: None,
+            cache_control: Default::default(),
+            http_headers: Default::default(),
+        }));
+        assert_eq!(
+            serde_json::to_value(resp).unwrap(),
+            json! ({
+                "data": {
+                    "ok": null,
+                },
+                "errors": [{
+                    "message": "MyErrorMessage",
+                    "locations": [{"line": 1, "column": 3}],
+                    "path": ["ok"],
+                }]
+            })
+        );
+    }
+
     #[test]
     fn test_field_error_with_extension() {
         let err = Error::Query {
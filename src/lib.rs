@@ -40,6 +40,7 @@
 //! * Subscription (WebSocket transport)
 //! * Custom extension
 //! * Apollo Tracing extension
+//! * Prometheus metrics extension
 //! * Limit query complexity/depth
 //! * Error Extensions
 //! * Apollo Federation
@@ -80,6 +81,7 @@ extern crate serde_derive;
 mod base;
 mod context;
 mod error;
+mod look_ahead;
 mod model;
 mod mutation_resolver;
 mod query;
@@ -90,8 +92,15 @@ mod subscription;
 mod types;
 mod validation;
 
+pub mod dataloader;
+pub mod diff;
+pub mod directive;
+pub mod dynamic;
 pub mod extensions;
 pub mod guard;
+pub mod persisted_query;
+pub mod rate_limit;
+pub mod safelist;
 pub mod validators;
 
 #[doc(hidden)]
@@ -116,7 +125,11 @@ pub use error::{
 };
 pub use graphql_parser::query::Value;
 pub use graphql_parser::Pos;
-pub use query::{IntoQueryBuilder, IntoQueryBuilderOpts, QueryBuilder, QueryResponse};
+pub use look_ahead::Lookahead;
+pub use query::{
+    BatchRequest, BatchResponse, IncrementalResponse, IntoBatchRequest, IntoQueryBuilder,
+    IntoQueryBuilderOpts, QueryBuilder, QueryPatch, QueryResponse,
+};
 pub use registry::CacheControl;
 pub use scalars::{Any, ID};
 pub use schema::Schema;
@@ -157,6 +170,8 @@ pub use types::{EnumItem, EnumType};
 /// | desc          | Object description        | string   | Y        |
 /// | cache_control | Object cache control      | [`CacheControl`](struct.CacheControl.html) | Y        |
 /// | extends       | Add fields to an entity that's defined in another service | bool | Y |
+/// | guard         | Guard of all the fields, checked before the guards of the fields | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | visible       | Path of a `fn(&Data) -> bool` that decides whether the object is visible to a request | string | Y |
 ///
 /// # Field parameters
 ///
@@ -170,6 +185,8 @@ pub use types::{EnumItem, EnumType};
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | complexity    | Field complexity, a constant cost added to the complexity of the selection set, or an expression of type `usize` over the arguments and `child_complexity` | integer or string | Y |
+/// | visible       | Path of a `fn(&Data) -> bool` that decides whether the field is visible to a request | string | Y |
 ///
 /// # Field argument parameters
 ///
@@ -231,6 +248,11 @@ pub use types::{EnumItem, EnumType};
 ///     async fn value_with_arg(&self, #[arg(default = "1")] a: i32) -> i32 {
 ///         a
 ///     }
+///
+///     #[field(complexity = "count as usize * child_complexity")]
+///     async fn values(&self, #[arg(default = "10")] count: i32) -> Vec<i32> {
+///         (0..count).collect()
+///     }
 /// }
 ///
 /// #[async_std::main]
@@ -265,6 +287,8 @@ pub use async_graphql_derive::Object;
 /// | name          | Object name               | string   | Y        |
 /// | desc          | Object description        | string   | Y        |
 /// | cache_control | Object cache control      | [`CacheControl`](struct.CacheControl.html) | Y        |
+/// | guard         | Guard of all the fields, checked before the guards of the fields | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | visible       | Path of a `fn(&Data) -> bool` that decides whether the object is visible to a request | string | Y |
 ///
 /// # Field parameters
 ///
@@ -278,6 +302,8 @@ pub use async_graphql_derive::Object;
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | complexity    | Field complexity, a constant cost added to the complexity of the selection set, or an expression of type `usize` over `child_complexity` | integer or string | Y |
+/// | visible       | Path of a `fn(&Data) -> bool` that decides whether the field is visible to a request | string | Y |
 ///
 /// # Examples
 ///
@@ -364,6 +390,7 @@ pub use async_graphql_derive::Enum;
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Object name               | string   | Y        |
 /// | desc        | Object description        | string   | Y        |
+/// | validator   | Path of a `fn(&Self) -> Result<(), String>` that checks the object after its fields are parsed, the error is the reason why it is invalid | string | Y |
 ///
 /// # Field parameters
 ///
@@ -417,6 +444,8 @@ pub use async_graphql_derive::InputObject;
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Object name               | string   | Y        |
 /// | desc        | Object description        | string   | Y        |
+/// | guard       | Guard of all the fields   | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | visible     | Path of a `fn(&Data) -> bool` that decides whether the interface is visible to a request | string | Y |
 ///
 /// # Field parameters
 ///
@@ -427,6 +456,8 @@ pub use async_graphql_derive::InputObject;
 /// | desc        | Field description         | string   | Y        |
 /// | deprecation | Field deprecation reason  | string   | Y        |
 /// | args        | Field arguments           |          | Y        |
+/// | visible     | Path of a `fn(&Data) -> bool` that decides whether the field is visible to a request, it is also hidden if the field of an implementing object is hidden | string | Y |
+/// | complexity  | Field complexity, like the `complexity` of an object field. By default it is the maximum complexity of the field of the implementing objects | integer or string | Y |
 ///
 /// # Field argument parameters
 ///
@@ -543,6 +574,8 @@ pub use async_graphql_derive::Union;
 /// | desc        | Field description         | string   | Y        |
 /// | deprecation | Field deprecation reason  | string   | Y        |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | visible       | Path of a `fn(&Data) -> bool` that decides whether the field is visible to a request | string | Y |
+/// | complexity    | Field complexity, computed once when the subscription starts, like the `complexity` of an object field | integer or string | Y |
 ///
 /// # Field argument parameters
 ///
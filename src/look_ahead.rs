@@ -0,0 +1,201 @@
+use crate::base::parse_input_value;
+use crate::registry::{self, TypeName};
+use crate::{Context, InputValueType, Result};
+use graphql_parser::query::{Directive, Field, Selection, SelectionSet, Value};
+
+/// A view of the selection of a field, to know which subfields are queried before resolving them
+///
+/// It is created with `Context::look_ahead`.
+/// The fragment spreads and the inline fragments are expanded regardless of their type condition,
+/// and the fields skipped by `@skip` or `@include` are excluded.
+/// A field selected several times is merged, the subfields of all its selections are visible.
+///
+/// ```rust
+/// use async_graphql::*;
+///
+/// #[SimpleObject]
+/// struct User {
+///     name: String,
+///     age: i32,
+/// }
+///
+/// struct Query;
+///
+/// #[Object]
+/// impl Query {
+///     async fn user(&self, ctx: &Context<'_>) -> User {
+///         let look_ahead = ctx.look_ahead();
+///         // Only load the age column if it is queried.
+///         let age = if look_ahead.field("age").exists() { 30 } else { 0 };
+///         User { name: "Alice".to_string(), age }
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+///     let res = schema.execute("{ user { name ... on User { age } } }").await.unwrap();
+///     assert_eq!(res.data, serde_json::json!({ "user": { "name": "Alice", "age": 30 } }));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Lookahead<'a> {
+    ctx: &'a Context<'a>,
+    parent_type: Option<&'a str>,
+    fields: Vec<&'a Field>,
+}
+
+impl<'a> Lookahead<'a> {
+    pub(crate) fn new(ctx: &'a Context<'a>) -> Self {
+        Self {
+            ctx,
+            parent_type: ctx.parent_type,
+            fields: vec![ctx.item],
+        }
+    }
+
+    /// Returns true if the field is selected.
+    pub fn exists(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    /// Field name, `None` if the field is not selected.
+    pub fn name(&self) -> Option<&'a str> {
+        self.fields.first().map(|field| field.name.as_str())
+    }
+
+    /// Field alias, `None` if the field is not selected or has no alias.
+    pub fn alias(&self) -> Option<&'a str> {
+        self.fields.first().and_then(|field| field.alias.as_deref())
+    }
+
+    /// Get the value of an argument of the field, with the variables replaced by their values.
+    ///
+    /// The default value of the argument applies if it is not specified in the query, or if its variable is not provided.
+    /// Returns `None` if the field is not selected, or the argument is not specified and has no default value.
+    pub fn argument<T: InputValueType>(&self, name: &str) -> Result<Option<T>> {
+        let field = match self.fields.first() {
+            Some(field) => field,
+            None => return Ok(None),
+        };
+        let default = || {
+            self.definition()
+                .and_then(|definition| definition.args.get(name))
+                .and_then(|arg| arg.parsed_default_value.clone())
+        };
+        let value = match field.arguments.iter().find(|(n, _)| n == name) {
+            Some((_, Value::Variable(var_name))) => self
+                .ctx
+                .var_value(var_name, field.position)?
+                .or_else(default),
+            Some((_, value)) => Some(
+                self.ctx
+                    .resolve_input_value(value.clone(), field.position)?,
+            ),
+            None => default(),
+        };
+        match value {
+            Some(value) => Ok(Some(parse_input_value(value, field.position)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Look ahead at a subfield by name, whatever its alias.
+    ///
+    /// If the subfield is selected with several aliases, the result is the merge of all of them.
+    pub fn field(&self, name: &str) -> Lookahead<'a> {
+        let mut fields = Vec::new();
+        for field in &self.fields {
+            self.collect(&field.selection_set, &mut |field| {
+                if field.name == name {
+                    fields.push(field);
+                }
+            });
+        }
+        Lookahead {
+            ctx: self.ctx,
+            parent_type: self.child_type(),
+            fields,
+        }
+    }
+
+    /// Returns the subfields, one for each response key in the order of the query.
+    pub fn fields(&self) -> Vec<Lookahead<'a>> {
+        let parent_type = self.child_type();
+        let mut children: Vec<Lookahead<'a>> = Vec::new();
+        for field in &self.fields {
+            self.collect(&field.selection_set, &mut |field| {
+                let key = field.alias.as_deref().unwrap_or(&field.name);
+                match children.iter_mut().find(|child| {
+                    let child = child.fields[0];
+                    child.alias.as_deref().unwrap_or(&child.name) == key
+                }) {
+                    Some(child) => child.fields.push(field),
+                    None => children.push(Lookahead {
+                        ctx: self.ctx,
+                        parent_type,
+                        fields: vec![field],
+                    }),
+                }
+            });
+        }
+        children
+    }
+
+    /// The definition of the field in the registry.
+    ///
+    /// If the parent is an interface or a union without this field, it is looked up in the possible types.
+    fn definition(&self) -> Option<&'a registry::Field> {
+        let field = self.fields.first()?;
+        let registry = self.ctx.registry;
+        let parent_type = registry.types.get(self.parent_type?)?;
+        parent_type.field_by_name(&field.name).or_else(|| {
+            parent_type
+                .possible_types()?
+                .iter()
+                .filter_map(|name| registry.types.get(name))
+                .find_map(|ty| ty.field_by_name(&field.name))
+        })
+    }
+
+    /// The type of the field, which is the parent type of its subfields.
+    fn child_type(&self) -> Option<&'a str> {
+        let ty = &self.definition()?.ty;
+        let name = TypeName::concrete_typename(ty);
+        self.ctx
+            .registry
+            .types
+            .get_key_value(name)
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn collect<F: FnMut(&'a Field)>(&self, selection_set: &'a SelectionSet, f: &mut F) {
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    if !self.is_skip(&field.directives) {
+                        f(field);
+                    }
+                }
+                Selection::FragmentSpread(fragment_spread) => {
+                    if !self.is_skip(&fragment_spread.directives) {
+                        if let Some(fragment) =
+                            self.ctx.fragments.get(&fragment_spread.fragment_name)
+                        {
+                            self.collect(&fragment.selection_set, f);
+                        }
+                    }
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    if !self.is_skip(&inline_fragment.directives) {
+                        self.collect(&inline_fragment.selection_set, f);
+                    }
+                }
+            }
+        }
+    }
+
+    // An invalid directive is reported when the field is resolved, it is considered skipped here.
+    fn is_skip(&self, directives: &[Directive]) -> bool {
+        self.ctx.is_skip(directives).unwrap_or(true)
+    }
+}
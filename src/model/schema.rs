@@ -1,5 +1,5 @@
 use crate::model::{__Directive, __Type};
-use crate::registry;
+use crate::{registry, Context};
 use async_graphql_derive::Object;
 
 pub struct __Schema<'a> {
@@ -12,10 +12,11 @@ pub struct __Schema<'a> {
 )]
 impl<'a> __Schema<'a> {
     #[field(desc = "A list of all types supported by this server.")]
-    async fn types(&self) -> Vec<__Type<'a>> {
+    async fn types(&self, ctx: &Context<'_>) -> Vec<__Type<'a>> {
         self.registry
             .types
             .values()
+            .filter(|ty| ctx.is_visible_type(ty.name()))
             .map(|ty| __Type::new_simple(self.registry, ty))
             .collect()
     }
@@ -1,5 +1,5 @@
 use crate::model::{__EnumValue, __Field, __InputValue, __TypeKind};
-use crate::registry;
+use crate::{registry, Context};
 use async_graphql_derive::Object;
 use itertools::Itertools;
 
@@ -91,6 +91,7 @@ impl<'a> __Type<'a> {
 
     async fn fields(
         &self,
+        ctx: &Context<'_>,
         #[arg(default = "false")] include_deprecated: bool,
     ) -> Option<Vec<__Field<'a>>> {
         if let TypeDetail::Named(ty) = &self.detail {
@@ -100,6 +101,7 @@ impl<'a> __Type<'a> {
                     .filter(|field| {
                         (include_deprecated || field.deprecation.is_none())
                             && !field.name.starts_with("__")
+                            && ctx.is_visible_field(ty, field)
                     })
                     .map(|field| __Field {
                         registry: self.registry,
@@ -114,7 +116,7 @@ impl<'a> __Type<'a> {
         }
     }
 
-    async fn interfaces(&self) -> Option<Vec<__Type<'a>>> {
+    async fn interfaces(&self, ctx: &Context<'_>) -> Option<Vec<__Type<'a>>> {
         if let TypeDetail::Named(registry::Type::Object { name, .. }) = &self.detail {
             Some(
                 self.registry
@@ -122,6 +124,7 @@ impl<'a> __Type<'a> {
                     .get(name)
                     .unwrap_or(&Default::default())
                     .iter()
+                    .filter(|ty| ctx.is_visible_type(ty))
                     .map(|ty| __Type::new(self.registry, ty))
                     .collect(),
             )
@@ -130,11 +133,12 @@ impl<'a> __Type<'a> {
         }
     }
 
-    async fn possible_types(&self) -> Option<Vec<__Type<'a>>> {
+    async fn possible_types(&self, ctx: &Context<'_>) -> Option<Vec<__Type<'a>>> {
         if let TypeDetail::Named(registry::Type::Interface { possible_types, .. }) = &self.detail {
             Some(
                 possible_types
                     .iter()
+                    .filter(|ty| ctx.is_visible_type(ty))
                     .map(|ty| __Type::new(self.registry, ty))
                     .collect(),
             )
@@ -143,6 +147,7 @@ impl<'a> __Type<'a> {
             Some(
                 possible_types
                     .iter()
+                    .filter(|ty| ctx.is_visible_type(ty))
                     .map(|ty| __Type::new(self.registry, ty))
                     .collect(),
             )
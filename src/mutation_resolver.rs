@@ -1,4 +1,4 @@
-use crate::extensions::ResolveInfo;
+use crate::resolver::resolve_field_value;
 use crate::{ContextSelectionSet, Error, ObjectType, QueryError, Result};
 use graphql_parser::query::{Selection, TypeCondition};
 use std::future::Future;
@@ -27,7 +27,7 @@ fn do_resolve<'a, T: ObjectType + Send + Sync>(
                 pos: ctx.span.0,
                 path: None,
                 err: QueryError::MustHaveSubFields {
-                    object: T::type_name().to_string(),
+                    object: root.registry_type_name().to_string(),
                 },
             });
         }
@@ -47,49 +47,13 @@ fn do_resolve<'a, T: ObjectType + Send + Sync>(
                         continue;
                     }
 
-                    let ctx_field = ctx.with_field(field);
+                    let ctx_field = ctx
+                        .with_field(field)
+                        .with_parent_type(&root.registry_type_name());
                     let field_name = ctx_field.result_name().to_string();
 
-                    if !ctx_field.extensions.is_empty() {
-                        let resolve_info = ResolveInfo {
-                            resolve_id: ctx_field.resolve_id,
-                            path_node: ctx_field.path_node.as_ref().unwrap(),
-                            parent_type: &T::type_name(),
-                            return_type: match ctx_field
-                                .registry
-                                .types
-                                .get(T::type_name().as_ref())
-                                .and_then(|ty| ty.field_by_name(field.name.as_str()))
-                                .map(|field| &field.ty)
-                            {
-                                Some(ty) => &ty,
-                                None => {
-                                    return Err(Error::Query {
-                                        pos: field.position,
-                                        path: None,
-                                        err: QueryError::FieldNotFound {
-                                            field_name: field.name.clone(),
-                                            object: T::type_name().to_string(),
-                                        },
-                                    });
-                                }
-                            },
-                        };
-
-                        ctx_field
-                            .extensions
-                            .iter()
-                            .for_each(|e| e.resolve_field_start(&resolve_info));
-                    }
-
-                    let value = root.resolve_field(&ctx_field).await?;
-                    values.insert(field_name, value);
-
-                    if !ctx_field.extensions.is_empty() {
-                        ctx_field
-                            .extensions
-                            .iter()
-                            .for_each(|e| e.resolve_field_end(ctx_field.resolve_id));
+                    if let Some(value) = resolve_field_value(&ctx_field, root).await? {
+                        values.insert(field_name, value);
                     }
                 }
                 Selection::FragmentSpread(fragment_spread) => {
@@ -130,8 +94,9 @@ fn do_resolve<'a, T: ObjectType + Send + Sync>(
                             &mut futures,
                         )?;
                         for fut in futures {
-                            let (name, value) = fut.await?;
-                            values.insert(name, value);
+                            if let Some((name, value)) = fut.await? {
+                                values.insert(name, value);
+                            }
                         }
                     } else {
                         do_resolve(
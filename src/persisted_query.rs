@@ -0,0 +1,64 @@
+//! Automatic persisted queries
+//!
+//! Implements the [Apollo automatic persisted queries](https://www.apollographql.com/docs/apollo-server/performance/apq/) protocol.
+//! The client sends the SHA-256 hash of a query instead of the query source, and the server looks it up in a `PersistedQueryStore`.
+//! If the hash is unknown, the `PersistedQueryNotFound` error is returned, and the client sends the hash together with the query source to register it.
+//!
+//! ```rust
+//! use async_graphql::*;
+//! use async_graphql::persisted_query::LruPersistedQueryStore;
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn value(&self) -> i32 {
+//!         100
+//!     }
+//! }
+//!
+//! let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+//!     .persisted_query_store(LruPersistedQueryStore::new(1000))
+//!     .finish();
+//! ```
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+/// Persisted query store
+///
+/// Stores the query sources by their SHA-256 hash (hex encoded).
+#[async_trait::async_trait]
+pub trait PersistedQueryStore: Send + Sync + 'static {
+    /// Get the query source of `hash`.
+    async fn get(&self, hash: &str) -> Option<String>;
+
+    /// Store the query source of `hash`.
+    async fn set(&self, hash: &str, query: &str);
+}
+
+/// In-memory persisted query store that keeps the most recently used queries
+pub struct LruPersistedQueryStore(Mutex<LruCache<String, String>>);
+
+impl LruPersistedQueryStore {
+    /// Create a store that holds at most `capacity` queries.
+    pub fn new(capacity: usize) -> Self {
+        Self(Mutex::new(LruCache::new(capacity)))
+    }
+}
+
+#[async_trait::async_trait]
+impl PersistedQueryStore for LruPersistedQueryStore {
+    async fn get(&self, hash: &str) -> Option<String> {
+        self.0.lock().get(&hash.to_string()).cloned()
+    }
+
+    async fn set(&self, hash: &str, query: &str) {
+        self.0.lock().put(hash.to_string(), query.to_string());
+    }
+}
+
+pub(crate) fn sha256(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
@@ -1,19 +1,30 @@
-use crate::context::{Data, ResolveId};
+use crate::context::{Data, Incremental, ResolveId};
 use crate::error::ParseRequestError;
+use crate::extensions::{BoxExtension, EndGuard, NextExecute};
 use crate::mutation_resolver::do_mutation_resolve;
+use crate::persisted_query::sha256;
+use crate::rate_limit::ClientKey;
 use crate::registry::CacheControl;
-use crate::validation::{check_rules, CheckResult};
+use crate::validation::{calculate_complexity, check_rules, check_visibility, CheckResult};
 use crate::{do_resolve, ContextBase, Error, Result, Schema};
 use crate::{ObjectType, QueryError, Variables};
+use futures::channel::{mpsc, oneshot};
+use futures::future::{Either, FusedFuture, Shared};
+use futures::{FutureExt, Stream, StreamExt};
+use futures_timer::Delay;
 use graphql_parser::query::{
-    Definition, Document, OperationDefinition, SelectionSet, VariableDefinition,
+    Definition, Document, FragmentDefinition, OperationDefinition, SelectionSet, VariableDefinition,
 };
 use graphql_parser::{parse_query, Pos};
+use http::HeaderMap;
 use itertools::Itertools;
+use parking_lot::Mutex;
 use std::any::Any;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tempdir::TempDir;
 
 /// IntoQueryBuilder options
@@ -29,6 +40,9 @@ pub struct IntoQueryBuilderOpts {
 
     /// Maximum number of files.
     pub max_num_files: Option<usize>,
+
+    /// Maximum number of operations in a batch request.
+    pub max_batch_size: Option<usize>,
 }
 
 #[allow(missing_docs)]
@@ -44,17 +58,39 @@ pub trait IntoQueryBuilder: Sized {
     ) -> std::result::Result<QueryBuilder, ParseRequestError>;
 }
 
+#[allow(missing_docs)]
+#[async_trait::async_trait]
+pub trait IntoBatchRequest: Sized {
+    async fn into_batch_request(self) -> std::result::Result<BatchRequest, ParseRequestError> {
+        self.into_batch_request_opts(&Default::default()).await
+    }
+
+    async fn into_batch_request_opts(
+        self,
+        opts: &IntoQueryBuilderOpts,
+    ) -> std::result::Result<BatchRequest, ParseRequestError>;
+}
+
 /// Query response
 #[derive(Debug)]
 pub struct QueryResponse {
     /// Data of query result
     pub data: serde_json::Value,
 
+    /// Errors raised by the resolvers
+    ///
+    /// An error in a nullable field sets that field to `null`, an error in a non-null field is propagated to
+    /// the nearest nullable parent (or to `data` itself).
+    pub errors: Vec<Error>,
+
     /// Extensions result
     pub extensions: Option<serde_json::Map<String, serde_json::Value>>,
 
     /// Cache control value
     pub cache_control: CacheControl,
+
+    /// HTTP headers of the response, set by the resolvers with `Context::insert_http_header`
+    pub http_headers: HeaderMap,
 }
 
 /// Query builder
@@ -63,7 +99,11 @@ pub struct QueryBuilder {
     pub(crate) operation_name: Option<String>,
     pub(crate) variables: Variables,
     pub(crate) ctx_data: Option<Data>,
-    pub(crate) files_holder: Option<TempDir>,
+    pub(crate) files_holder: Option<Arc<TempDir>>,
+    pub(crate) persisted_query_hash: Option<String>,
+    pub(crate) document_id: Option<String>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) http_headers: HeaderMap,
 }
 
 impl QueryBuilder {
@@ -75,6 +115,10 @@ impl QueryBuilder {
             variables: Default::default(),
             ctx_data: None,
             files_holder: None,
+            persisted_query_hash: None,
+            document_id: None,
+            timeout: None,
+            http_headers: Default::default(),
         }
     }
 
@@ -86,6 +130,44 @@ impl QueryBuilder {
         }
     }
 
+    /// Specify the SHA-256 hash of the query for automatic persisted queries.
+    ///
+    /// If the query source is empty, it is loaded from the persisted query store of the schema,
+    /// otherwise it is added to the store.
+    pub fn persisted_query_hash<T: Into<String>>(self, hash: T) -> Self {
+        QueryBuilder {
+            persisted_query_hash: Some(hash.into()),
+            ..self
+        }
+    }
+
+    /// Specify the id of a document in the safelist of the schema, the query source is then ignored.
+    pub fn document_id<T: Into<String>>(self, id: T) -> Self {
+        QueryBuilder {
+            document_id: Some(id.into()),
+            ..self
+        }
+    }
+
+    /// Specify the execution timeout, it overrides the default timeout of the schema.
+    ///
+    /// When the query is not completed in time, the outstanding resolvers are dropped and fail with `QueryError::Timeout`,
+    /// the response contains the values resolved in time, as if the outstanding fields returned the error.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        QueryBuilder {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Specify the HTTP headers of the request, they can be accessed with `Context::http_headers`.
+    pub fn http_headers(self, http_headers: HeaderMap) -> Self {
+        QueryBuilder {
+            http_headers,
+            ..self
+        }
+    }
+
     /// Specify the variables.
     pub fn variables(self, variables: Variables) -> Self {
         QueryBuilder { variables, ..self }
@@ -107,7 +189,7 @@ impl QueryBuilder {
 
     /// Set file holder
     pub fn set_files_holder(&mut self, files_holder: TempDir) {
-        self.files_holder = Some(files_holder);
+        self.files_holder = Some(Arc::new(files_holder));
     }
 
     /// Set uploaded file path
@@ -122,43 +204,156 @@ impl QueryBuilder {
             .set_upload(var_path, filename, content_type, path);
     }
 
-    /// Execute the query.
-    pub async fn execute<Query, Mutation, Subscription>(
-        self,
+    /// Returns the name of the operation to execute.
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation_name.as_deref()
+    }
+
+    /// Returns the query source.
+    pub fn query_source(&self) -> &str {
+        &self.query_source
+    }
+
+    /// Replace the query source.
+    pub fn set_query_source<T: Into<String>>(&mut self, query_source: T) {
+        self.query_source = query_source.into();
+    }
+
+    /// Call `Extension::prepare_request` on the extension instances of the query.
+    async fn prepare_request(mut self, extensions: &[BoxExtension]) -> Result<Self> {
+        for extension in extensions {
+            self = extension.prepare_request(self).await?;
+        }
+        Ok(self)
+    }
+
+    /// Load the persisted query, then parse and validate the query source.
+    async fn prepare<Query, Mutation, Subscription>(
+        &mut self,
         schema: &Schema<Query, Mutation, Subscription>,
-    ) -> Result<QueryResponse>
-    where
-        Query: ObjectType + Send + Sync,
-        Mutation: ObjectType + Send + Sync,
-    {
-        // create extension instances
-        let extensions = schema
-            .0
-            .extensions
-            .iter()
-            .map(|factory| factory())
-            .collect_vec();
+        extensions: &[BoxExtension],
+    ) -> Result<Arc<CachedDocument>> {
+        // load safelisted document
+        if let Some(safelist) = &schema.0.safelist {
+            let document = self
+                .document_id
+                .iter()
+                .chain(&self.persisted_query_hash)
+                .find_map(|id| safelist.get(id));
+            if let Some(document) = document {
+                self.query_source = document.to_string();
+                self.persisted_query_hash = None;
+            } else if self.document_id.is_some() {
+                return Err(QueryError::NotSafelisted.into_error(Pos::default()));
+            }
+        }
+
+        // load persisted query
+        let mut register = None;
+        if let Some(hash) = &self.persisted_query_hash {
+            let store =
+                schema.0.persisted_query_store.as_ref().ok_or_else(|| {
+                    QueryError::PersistedQueryNotSupported.into_error(Pos::default())
+                })?;
+            if self.query_source.is_empty() {
+                self.query_source = store
+                    .get(hash)
+                    .await
+                    .ok_or_else(|| QueryError::PersistedQueryNotFound.into_error(Pos::default()))?;
+            } else if sha256(&self.query_source).eq_ignore_ascii_case(hash) {
+                register = Some((store, hash));
+            } else {
+                return Err(QueryError::InvalidPersistedQueryHash.into_error(Pos::default()));
+            }
+        }
+
+        // check safelist
+        if let Some(safelist) = &schema.0.safelist {
+            safelist.check(&self.query_source, self.operation_name.as_deref())?;
+        }
+
+        // register persisted query, only once it is allowed by the safelist
+        if let Some((store, hash)) = register {
+            store.set(hash, &self.query_source).await;
+        }
 
         // parse query source
-        extensions
-            .iter()
-            .for_each(|e| e.parse_start(&self.query_source));
-        let document = parse_query(&self.query_source).map_err(Into::<Error>::into)?;
-        extensions.iter().for_each(|e| e.parse_end());
-
-        // check rules
-        extensions.iter().for_each(|e| e.validation_start());
-        let CheckResult {
-            cache_control,
-            complexity,
-            depth,
-        } = check_rules(&schema.0.registry, &document, schema.0.validation_mode)?;
-        extensions.iter().for_each(|e| e.validation_end());
+        let parse_guard = EndGuard::start(
+            extensions,
+            |e| e.parse_start(&self.query_source),
+            |e| e.parse_end(),
+        );
+        let cached_document = schema
+            .0
+            .document_cache
+            .as_ref()
+            .and_then(|cache| cache.lock().get(&self.query_source).cloned());
+        let cached_document = match cached_document {
+            Some(cached_document) => {
+                drop(parse_guard);
+                cached_document
+            }
+            None => {
+                let document = parse_query(&self.query_source).map_err(Into::<Error>::into)?;
+                drop(parse_guard);
+
+                // check rules
+                let validation_guard =
+                    EndGuard::start(extensions, |e| e.validation_start(), |e| e.validation_end());
+                let check_result =
+                    check_rules(&schema.0.registry, &document, schema.0.validation_mode)?;
+                drop(validation_guard);
+
+                let cached_document = Arc::new(CachedDocument {
+                    document,
+                    check_result,
+                });
+                if let Some(cache) = &schema.0.document_cache {
+                    cache
+                        .lock()
+                        .put(self.query_source.clone(), cached_document.clone());
+                }
+                cached_document
+            }
+        };
+        let CheckResult { depth, .. } = cached_document.check_result;
+
+        // check visibility
+        if schema.0.has_visibility {
+            let empty = Data::default();
+            check_visibility(
+                &schema.0.registry,
+                &cached_document.document,
+                self.ctx_data.as_ref().unwrap_or(&empty),
+            )?;
+        }
 
         // check limit
-        if let Some(limit_complexity) = schema.0.complexity {
-            if complexity > limit_complexity {
-                return Err(QueryError::TooComplex.into_error(Pos::default()));
+        if schema.0.complexity.is_some() || schema.0.complexity_budget.is_some() {
+            let document = &cached_document.document;
+            if let Some((selection_set, variable_definitions, is_query, _)) =
+                current_operation(document, self.operation_name.as_deref())
+            {
+                let root_type = if is_query {
+                    schema.0.registry.query_type.as_str()
+                } else {
+                    schema
+                        .0
+                        .registry
+                        .mutation_type
+                        .as_deref()
+                        .unwrap_or_default()
+                };
+                check_complexity(
+                    schema,
+                    document,
+                    root_type,
+                    selection_set,
+                    variable_definitions,
+                    &self.variables,
+                    self.ctx_data.as_ref(),
+                )
+                .await?;
             }
         }
 
@@ -168,86 +363,625 @@ impl QueryBuilder {
             }
         }
 
-        // execute
-        let inc_resolve_id = AtomicUsize::default();
-        let mut fragments = HashMap::new();
-        let (selection_set, variable_definitions, is_query) =
-            current_operation(&document, self.operation_name.as_deref()).ok_or_else(|| {
-                Error::Query {
-                    pos: Pos::default(),
-                    path: None,
-                    err: QueryError::MissingOperation,
-                }
-            })?;
+        Ok(cached_document)
+    }
 
-        for definition in &document.definitions {
-            if let Definition::Fragment(fragment) = &definition {
-                fragments.insert(fragment.name.clone(), fragment.clone());
-            }
+    /// Execute the query.
+    pub async fn execute<Query, Mutation, Subscription>(
+        self,
+        schema: &Schema<Query, Mutation, Subscription>,
+    ) -> Result<QueryResponse>
+    where
+        Query: ObjectType + Send + Sync,
+        Mutation: ObjectType + Send + Sync,
+    {
+        let deadline = self
+            .timeout
+            .or(schema.0.timeout)
+            .map(|timeout| Instant::now() + timeout);
+        let extensions = create_extensions(schema);
+        let res = self.execute_inner(schema, &extensions, deadline).await;
+        if let Err(err) = &res {
+            extensions.iter().for_each(|e| e.error(err));
         }
+        res
+    }
+
+    async fn execute_inner<Query, Mutation, Subscription>(
+        self,
+        schema: &Schema<Query, Mutation, Subscription>,
+        extensions: &[BoxExtension],
+        deadline: Option<Instant>,
+    ) -> Result<QueryResponse>
+    where
+        Query: ObjectType + Send + Sync,
+        Mutation: ObjectType + Send + Sync,
+    {
+        // The timeout of the execution is handled by the fields, to keep the values resolved in time.
+        let deadline = deadline.map(Deadline::new);
+        let prepare = async {
+            let mut builder = self.prepare_request(extensions).await?;
+            let cached_document = builder.prepare(schema, extensions).await?;
+            Ok((builder, cached_document))
+        };
+        let (builder, cached_document) = with_deadline(deadline.as_ref(), prepare)
+            .await
+            .unwrap_or_else(|| Err(QueryError::Timeout.into_error(Pos::default())))?;
+        let document = &cached_document.document;
+        let (selection_set, variable_definitions, is_query, name) =
+            current_operation(document, builder.operation_name.as_deref())
+                .ok_or_else(|| QueryError::MissingOperation.into_error(Pos::default()))?;
+        let fragments = collect_fragments(document);
 
+        // execute
+        let inc_resolve_id = AtomicUsize::default();
+        let errors = Mutex::new(Vec::new());
+        let response_http_headers = Mutex::new(HeaderMap::new());
         let ctx = ContextBase {
             path_node: None,
             resolve_id: ResolveId::root(),
             inc_resolve_id: &inc_resolve_id,
-            extensions: &extensions,
+            extensions,
             item: selection_set,
-            variables: &self.variables,
+            variables: &builder.variables,
             variable_definitions,
             registry: &schema.0.registry,
             data: &schema.0.data,
-            ctx_data: self.ctx_data.as_ref(),
+            ctx_data: builder.ctx_data.as_ref(),
             fragments: &fragments,
+            errors: Some(&errors),
+            http_headers: &builder.http_headers,
+            response_http_headers: Some(&response_http_headers),
+            incremental: None,
+            directives: &schema.0.directives,
+            parent_type: None,
+            deadline: deadline.as_ref(),
         };
 
-        extensions.iter().for_each(|e| e.execution_start());
-        let data = if is_query {
-            do_resolve(&ctx, &schema.0.query).await?
-        } else {
-            do_mutation_resolve(&ctx, &schema.0.mutation).await?
-        };
-        extensions.iter().for_each(|e| e.execution_end());
-
-        let res = QueryResponse {
-            data,
-            extensions: if !extensions.is_empty() {
-                Some(
-                    extensions
-                        .iter()
-                        .filter_map(|e| {
-                            if let Some(name) = e.name() {
-                                e.result().map(|res| (name.to_string(), res))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<serde_json::Map<_, _>>(),
-                )
+        let (query, mutation) = (&schema.0.query, &schema.0.mutation);
+        let execute = async {
+            let execution_guard =
+                EndGuard::start(extensions, |e| e.execution_start(), |e| e.execution_end());
+            let res = if is_query {
+                do_resolve(&ctx, query).await
             } else {
-                None
-            },
-            cache_control,
+                do_mutation_resolve(&ctx, mutation).await
+            };
+            drop(execution_guard);
+
+            Ok(create_response(
+                res,
+                &errors,
+                &response_http_headers,
+                extensions,
+                cached_document.check_result.cache_control,
+            ))
         };
-        Ok(res)
+        NextExecute::new(extensions, name, Box::pin(execute))
+            .run()
+            .await
     }
+
+    /// Execute the query with incremental delivery.
+    ///
+    /// The stream starts with the response without the fragments marked with `@defer` and
+    /// the items of the lists marked with `@stream` beyond `initialCount`,
+    /// which are then delivered as patches while they are resolved.
+    /// They are resolved from the values of the initial response, once it is sent.
+    ///
+    /// Mutations are always executed without incremental delivery.
+    ///
+    /// If the timeout expires before the initial response, it is `QueryError::Timeout`,
+    /// otherwise the stream ends without the remaining patches.
+    pub fn execute_stream<Query, Mutation, Subscription>(
+        self,
+        schema: &Schema<Query, Mutation, Subscription>,
+    ) -> impl Stream<Item = IncrementalResponse> + Send + 'static
+    where
+        Query: ObjectType + Send + Sync + 'static,
+        Mutation: ObjectType + Send + Sync + 'static,
+        Subscription: Send + Sync + 'static,
+    {
+        let schema = schema.clone();
+        let deadline = self
+            .timeout
+            .or(schema.0.timeout)
+            .map(|timeout| Instant::now() + timeout);
+        let (tx_initial, rx_initial) = oneshot::channel();
+        let (tx_patches, rx_patches) = mpsc::unbounded();
+        let (tx_complete, rx_complete) = oneshot::channel();
+        let extensions = Arc::new(create_extensions(&schema));
+
+        let execute = {
+            let extensions = extensions.clone();
+            async move {
+                let extensions = extensions.as_slice();
+                let prepared = match self.prepare_request(extensions).await {
+                    Ok(mut builder) => builder
+                        .prepare(&schema, extensions)
+                        .await
+                        .map(|cached_document| (builder, cached_document)),
+                    Err(err) => Err(err),
+                };
+                let (builder, cached_document) = match prepared {
+                    Ok(res) => res,
+                    Err(err) => {
+                        tx_initial
+                            .send(IncrementalResponse::Initial {
+                                response: Err(err),
+                                has_next: false,
+                            })
+                            .ok();
+                        return;
+                    }
+                };
+                let document = &cached_document.document;
+                let (selection_set, variable_definitions, is_query, name) =
+                    match current_operation(document, builder.operation_name.as_deref()) {
+                        Some(operation) => operation,
+                        None => {
+                            tx_initial
+                                .send(IncrementalResponse::Initial {
+                                    response: Err(
+                                        QueryError::MissingOperation.into_error(Pos::default())
+                                    ),
+                                    has_next: false,
+                                })
+                                .ok();
+                            return;
+                        }
+                    };
+                let fragments = collect_fragments(document);
+
+                // execute
+                let inc_resolve_id = AtomicUsize::default();
+                let errors = Mutex::new(Vec::new());
+                let response_http_headers = Mutex::new(HeaderMap::new());
+                let has_next = AtomicBool::new(false);
+                let (tx_initial_sent, rx_initial_sent) = oneshot::channel();
+                let initial_sent = rx_initial_sent.shared();
+                let (tx_root, rx_root) = oneshot::channel();
+                let tx_root = Mutex::new(Some(tx_root));
+                let report_root = |value| {
+                    if let Some(tx) = tx_root.lock().take() {
+                        tx.send(value).ok();
+                    }
+                };
+                let mut ctx = ContextBase {
+                    path_node: None,
+                    resolve_id: ResolveId::root(),
+                    inc_resolve_id: &inc_resolve_id,
+                    extensions,
+                    item: selection_set,
+                    variables: &builder.variables,
+                    variable_definitions,
+                    registry: &schema.0.registry,
+                    data: &schema.0.data,
+                    ctx_data: builder.ctx_data.as_ref(),
+                    fragments: &fragments,
+                    errors: Some(&errors),
+                    http_headers: &builder.http_headers,
+                    response_http_headers: Some(&response_http_headers),
+                    incremental: None,
+                    directives: &schema.0.directives,
+                    parent_type: None,
+                    deadline: None,
+                };
+                if is_query && ctx.has_incremental_parts(selection_set) {
+                    ctx.incremental = Some(Incremental {
+                        has_next: &has_next,
+                        patches: &tx_patches,
+                        initial_sent: &initial_sent,
+                        initial: &report_root,
+                        fields: None,
+                        stream: None,
+                    });
+                }
+
+                // The query is resolved once, the deferred fragments and the streamed list items are resolved from
+                // the values of the initial response after it is sent.
+                let resolve = async {
+                    if is_query {
+                        do_resolve(&ctx, &schema.0.query).await
+                    } else {
+                        do_mutation_resolve(&ctx, &schema.0.mutation).await
+                    }
+                }
+                .fuse();
+                futures::pin_mut!(resolve);
+                let mut execution_guard = None;
+                let execute = async {
+                    execution_guard = Some(EndGuard::start(
+                        extensions,
+                        |e| e.execution_start(),
+                        |e| e.execution_end(),
+                    ));
+                    let res = match futures::future::select(resolve.as_mut(), rx_root).await {
+                        Either::Left((res, _)) => res,
+                        Either::Right((Ok(value), _)) => Ok(value),
+                        Either::Right((Err(_), resolve)) => resolve.await,
+                    };
+
+                    Ok(create_response(
+                        res,
+                        &errors,
+                        &response_http_headers,
+                        extensions,
+                        cached_document.check_result.cache_control,
+                    ))
+                };
+                let response = NextExecute::new(extensions, name, Box::pin(execute))
+                    .run()
+                    .await;
+
+                tx_initial
+                    .send(IncrementalResponse::Initial {
+                        response,
+                        has_next: has_next.load(Ordering::Relaxed),
+                    })
+                    .ok();
+                tx_initial_sent.send(()).ok();
+                if !resolve.is_terminated() {
+                    resolve.await.ok();
+                }
+                drop(execution_guard);
+                if has_next.load(Ordering::Relaxed) {
+                    tx_complete.send(IncrementalResponse::Complete).ok();
+                }
+            }
+        };
+
+        // The initial response is only canceled when the execution is dropped on timeout.
+        let rx_initial = rx_initial.map(move |res| {
+            let res = res.unwrap_or_else(|_| IncrementalResponse::Initial {
+                response: Err(QueryError::Timeout.into_error(Pos::default())),
+                has_next: false,
+            });
+            if let IncrementalResponse::Initial {
+                response: Err(err), ..
+            } = &res
+            {
+                extensions.iter().for_each(|e| e.error(err));
+            }
+            Ok::<_, oneshot::Canceled>(res)
+        });
+        let responses = rx_initial
+            .into_stream()
+            .chain(rx_patches.map(|patch| Ok(IncrementalResponse::Patch(patch))))
+            .chain(rx_complete.into_stream())
+            .filter_map(|res| futures::future::ready(res.ok()));
+        futures::stream::select(
+            async move { with_deadline(deadline.map(Deadline::new).as_ref(), execute).await }
+                .into_stream()
+                .filter_map(|_| futures::future::ready(None)),
+            responses,
+        )
+    }
+}
+
+/// Create the extension instances of a query.
+fn create_extensions<Query, Mutation, Subscription>(
+    schema: &Schema<Query, Mutation, Subscription>,
+) -> Vec<BoxExtension> {
+    schema
+        .0
+        .extensions
+        .iter()
+        .map(|factory| factory())
+        .collect_vec()
+}
+
+/// The deadline of a request, all the fields of the request race the same timer.
+pub(crate) struct Deadline {
+    instant: Instant,
+    timer: Shared<Delay>,
+}
+
+impl Deadline {
+    pub(crate) fn new(instant: Instant) -> Self {
+        Self {
+            instant,
+            timer: Delay::new(instant.saturating_duration_since(Instant::now())).shared(),
+        }
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub(crate) fn expired(&self) -> bool {
+        Instant::now() >= self.instant
+    }
+}
+
+/// Resolves to `None` if the future is not completed before the deadline, it is then dropped.
+pub(crate) async fn with_deadline<F: std::future::Future>(
+    deadline: Option<&Deadline>,
+    fut: F,
+) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) if deadline.expired() => None,
+        Some(deadline) => {
+            futures::pin_mut!(fut);
+            match futures::future::select(fut, deadline.timer.clone()).await {
+                Either::Left((res, _)) => Some(res),
+                Either::Right(_) => None,
+            }
+        }
+        None => Some(fut.await),
+    }
+}
+
+fn collect_fragments(document: &Document) -> HashMap<String, FragmentDefinition> {
+    let mut fragments = HashMap::new();
+    for definition in &document.definitions {
+        if let Definition::Fragment(fragment) = &definition {
+            fragments.insert(fragment.name.clone(), fragment.clone());
+        }
+    }
+    fragments
+}
+
+fn create_response(
+    res: Result<serde_json::Value>,
+    errors: &Mutex<Vec<Error>>,
+    http_headers: &Mutex<HeaderMap>,
+    extensions: &[BoxExtension],
+    cache_control: CacheControl,
+) -> QueryResponse {
+    let data = match res {
+        Ok(data) => data,
+        Err(err) => {
+            errors.lock().push(err);
+            serde_json::Value::Null
+        }
+    };
+    let errors = std::mem::take(&mut *errors.lock());
+    for err in &errors {
+        extensions.iter().for_each(|e| e.field_error(err));
+    }
+
+    QueryResponse {
+        data,
+        errors,
+        extensions: if !extensions.is_empty() {
+            Some(
+                extensions
+                    .iter()
+                    .filter_map(|e| {
+                        if let Some(name) = e.name() {
+                            e.result().map(|res| (name.to_string(), res))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<serde_json::Map<_, _>>(),
+            )
+        } else {
+            None
+        },
+        cache_control,
+        http_headers: std::mem::take(&mut *http_headers.lock()),
+    }
+}
+
+/// Response of a query executed with incremental delivery
+#[derive(Debug)]
+pub enum IncrementalResponse {
+    /// Response without the incremental parts, `has_next` is true if it is followed by patches
+    Initial {
+        /// Initial response
+        response: Result<QueryResponse>,
+
+        /// Whether any patch follows
+        has_next: bool,
+    },
+
+    /// Deferred fragment or streamed list item
+    Patch(QueryPatch),
+
+    /// Sent after the last patch
+    Complete,
 }
 
-fn current_operation<'a>(
+/// Incremental part of a query response
+#[derive(Debug)]
+pub enum QueryPatch {
+    /// Fields of a fragment marked with `@defer`
+    Defer {
+        /// Label of the `@defer` directive
+        label: Option<String>,
+
+        /// Path of the object containing the fragment
+        path: serde_json::Value,
+
+        /// Data of the fragment, `null` if an error was propagated to it
+        data: serde_json::Value,
+
+        /// Errors raised by the resolvers of the fragment
+        errors: Vec<Error>,
+    },
+
+    /// Item of a list marked with `@stream`
+    Stream {
+        /// Label of the `@stream` directive
+        label: Option<String>,
+
+        /// Path of the item
+        path: serde_json::Value,
+
+        /// Array containing the item, `null` if an error was propagated to it
+        items: serde_json::Value,
+
+        /// Errors raised by the resolvers of the item
+        errors: Vec<Error>,
+    },
+}
+
+/// Parsed and validated query document, stored in the document cache of the schema.
+pub(crate) struct CachedDocument {
+    pub(crate) document: Document,
+    pub(crate) check_result: CheckResult,
+}
+
+/// Batch query request
+///
+/// A single query, or a list of queries sent in one request.
+#[allow(clippy::large_enum_variant)]
+pub enum BatchRequest {
+    /// Single query
+    Single(QueryBuilder),
+
+    /// List of queries
+    Batch(Vec<QueryBuilder>),
+}
+
+impl BatchRequest {
+    /// Apply the function `f` to every query builder, for example to add a context data to each query.
+    pub fn map<F: FnMut(QueryBuilder) -> QueryBuilder>(self, mut f: F) -> Self {
+        match self {
+            BatchRequest::Single(builder) => BatchRequest::Single(f(builder)),
+            BatchRequest::Batch(builders) => {
+                BatchRequest::Batch(builders.into_iter().map(f).collect())
+            }
+        }
+    }
+
+    /// Execute the queries one after another.
+    pub async fn execute<Query, Mutation, Subscription>(
+        self,
+        schema: &Schema<Query, Mutation, Subscription>,
+    ) -> BatchResponse
+    where
+        Query: ObjectType + Send + Sync,
+        Mutation: ObjectType + Send + Sync,
+    {
+        match self {
+            BatchRequest::Single(builder) => BatchResponse::Single(builder.execute(schema).await),
+            BatchRequest::Batch(builders) => {
+                let mut responses = Vec::with_capacity(builders.len());
+                for builder in builders {
+                    responses.push(builder.execute(schema).await);
+                }
+                BatchResponse::Batch(responses)
+            }
+        }
+    }
+
+    /// Execute the queries concurrently.
+    pub async fn execute_concurrent<Query, Mutation, Subscription>(
+        self,
+        schema: &Schema<Query, Mutation, Subscription>,
+    ) -> BatchResponse
+    where
+        Query: ObjectType + Send + Sync,
+        Mutation: ObjectType + Send + Sync,
+    {
+        match self {
+            BatchRequest::Single(builder) => BatchResponse::Single(builder.execute(schema).await),
+            BatchRequest::Batch(builders) => BatchResponse::Batch(
+                futures::future::join_all(
+                    builders.into_iter().map(|builder| builder.execute(schema)),
+                )
+                .await,
+            ),
+        }
+    }
+}
+
+/// Batch query response
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum BatchResponse {
+    /// Response of a single query
+    Single(Result<QueryResponse>),
+
+    /// Responses of a list of queries, in the order of the request
+    Batch(Vec<Result<QueryResponse>>),
+}
+
+impl BatchResponse {
+    /// Returns the HTTP headers set by the queries, the values of a header set by several queries are all kept.
+    pub fn http_headers(&self) -> HeaderMap {
+        match self {
+            BatchResponse::Single(Ok(resp)) => resp.http_headers.clone(),
+            BatchResponse::Single(Err(_)) => HeaderMap::new(),
+            BatchResponse::Batch(responses) => {
+                let mut http_headers = HeaderMap::new();
+                for resp in responses.iter().filter_map(|res| res.as_ref().ok()) {
+                    for (name, value) in &resp.http_headers {
+                        http_headers.append(name, value.clone());
+                    }
+                }
+                http_headers
+            }
+        }
+    }
+}
+
+/// Check the complexity of the operation against the limit of the schema, and take it from the budget of the client.
+pub(crate) async fn check_complexity<Query, Mutation, Subscription>(
+    schema: &Schema<Query, Mutation, Subscription>,
+    document: &Document,
+    root_type: &str,
+    selection_set: &SelectionSet,
+    variable_definitions: &[VariableDefinition],
+    variables: &Variables,
+    ctx_data: Option<&Data>,
+) -> Result<()> {
+    let complexity = calculate_complexity(
+        &schema.0.registry,
+        document,
+        root_type,
+        selection_set,
+        variable_definitions,
+        variables,
+    )?;
+    if let Some(limit_complexity) = schema.0.complexity {
+        if complexity > limit_complexity {
+            return Err(QueryError::TooComplex.into_error(Pos::default()));
+        }
+    }
+
+    let client_key = ctx_data.and_then(|ctx_data| ctx_data.get::<ClientKey>());
+    if let (Some(budget), Some(ClientKey(key))) = (&schema.0.complexity_budget, client_key) {
+        if complexity > budget.capacity {
+            return Err(QueryError::TooComplex.into_error(Pos::default()));
+        }
+        budget
+            .store
+            .take(key, complexity, budget.capacity, budget.period)
+            .await
+            .map_err(|retry_after| {
+                QueryError::RateLimited { retry_after }.into_error(Pos::default())
+            })?;
+    }
+    Ok(())
+}
+
+pub(crate) fn current_operation<'a>(
     document: &'a Document,
     operation_name: Option<&str>,
-) -> Option<(&'a SelectionSet, &'a [VariableDefinition], bool)> {
+) -> Option<(
+    &'a SelectionSet,
+    &'a [VariableDefinition],
+    bool,
+    Option<&'a str>,
+)> {
     for definition in &document.definitions {
         match definition {
             Definition::Operation(operation_definition) => match operation_definition {
                 OperationDefinition::SelectionSet(s) => {
-                    return Some((s, &[], true));
+                    return Some((s, &[], true, None));
                 }
                 OperationDefinition::Query(query)
                     if query.name.is_none()
                         || operation_name.is_none()
                         || query.name.as_deref() == operation_name.as_deref() =>
                 {
-                    return Some((&query.selection_set, &query.variable_definitions, true));
+                    return Some((
+                        &query.selection_set,
+                        &query.variable_definitions,
+                        true,
+                        query.name.as_deref(),
+                    ));
                 }
                 OperationDefinition::Mutation(mutation)
                     if mutation.name.is_none()
@@ -258,6 +992,7 @@ fn current_operation<'a>(
                         &mutation.selection_set,
                         &mutation.variable_definitions,
                         false,
+                        mutation.name.as_deref(),
                     ));
                 }
                 OperationDefinition::Subscription(subscription)
@@ -0,0 +1,143 @@
+//! Complexity budget rate limiting
+//!
+//! Each client has a token bucket that holds at most `capacity` tokens and is refilled at the rate of
+//! `capacity` tokens per `period`. Executing a query takes as many tokens as its complexity, the query is
+//! rejected with `QueryError::RateLimited` if the bucket does not hold enough tokens.
+//!
+//! The client is identified by the `ClientKey` in the data of the request, the requests without it are not limited.
+//!
+//! ```rust
+//! use async_graphql::*;
+//! use async_graphql::rate_limit::{ClientKey, ComplexityBudget, MemoryTokenBucketStore};
+//! use std::time::Duration;
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn value(&self) -> i32 {
+//!         100
+//!     }
+//! }
+//!
+//! async_std::task::block_on(async move {
+//!     let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+//!         .complexity_budget(ComplexityBudget::new(
+//!             2,
+//!             Duration::from_secs(60),
+//!             MemoryTokenBucketStore::new(10000),
+//!         ))
+//!         .finish();
+//!
+//!     let query = || QueryBuilder::new("{ value }").data(ClientKey("127.0.0.1".to_string()));
+//!     assert!(query().execute(&schema).await.is_ok());
+//!     assert!(query().execute(&schema).await.is_ok());
+//!     assert!(query().execute(&schema).await.is_err());
+//! });
+//! ```
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// Key identifying the client of a request, add it to the data of the request with `QueryBuilder::data`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClientKey(pub String);
+
+/// Token bucket store
+///
+/// Taking the tokens must be atomic, so that the concurrent requests of a client do not exceed the budget.
+#[async_trait::async_trait]
+pub trait TokenBucketStore: Send + Sync + 'static {
+    /// Take `tokens` from the bucket of `key`, which holds at most `capacity` tokens and is refilled at the rate of
+    /// `capacity` tokens per `period`.
+    ///
+    /// If the bucket does not hold enough tokens, no token is taken and the time to wait for them is returned.
+    async fn take(
+        &self,
+        key: &str,
+        tokens: usize,
+        capacity: usize,
+        period: Duration,
+    ) -> Result<(), Duration>;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// In-memory token bucket store that keeps the buckets of the most recently seen clients
+///
+/// The bucket of an evicted client is full when it is seen again.
+pub struct MemoryTokenBucketStore(Mutex<LruCache<String, Bucket>>);
+
+impl MemoryTokenBucketStore {
+    /// Create a store that holds at most `capacity` buckets.
+    pub fn new(capacity: usize) -> Self {
+        Self(Mutex::new(LruCache::new(capacity)))
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenBucketStore for MemoryTokenBucketStore {
+    async fn take(
+        &self,
+        key: &str,
+        tokens: usize,
+        capacity: usize,
+        period: Duration,
+    ) -> Result<(), Duration> {
+        let now = Instant::now();
+        let rate = capacity as f64 / period.as_secs_f64();
+        let mut buckets = self.0.lock();
+        let key = key.to_string();
+        let bucket = match buckets.get_mut(&key) {
+            Some(bucket) => {
+                let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity as f64);
+                bucket.updated_at = now;
+                bucket
+            }
+            None => {
+                buckets.put(
+                    key.clone(),
+                    Bucket {
+                        tokens: capacity as f64,
+                        updated_at: now,
+                    },
+                );
+                buckets.get_mut(&key).unwrap()
+            }
+        };
+
+        if bucket.tokens >= tokens as f64 {
+            bucket.tokens -= tokens as f64;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (tokens as f64 - bucket.tokens) / rate,
+            ))
+        }
+    }
+}
+
+/// Complexity budget of the clients
+pub struct ComplexityBudget {
+    pub(crate) capacity: usize,
+    pub(crate) period: Duration,
+    pub(crate) store: Box<dyn TokenBucketStore>,
+}
+
+impl ComplexityBudget {
+    /// Create a budget of `capacity` complexity per `period` for each client, the buckets are kept in `store`.
+    ///
+    /// A query more complex than `capacity` is always rejected with `QueryError::TooComplex`.
+    pub fn new<S: TokenBucketStore>(capacity: usize, period: Duration, store: S) -> Self {
+        Self {
+            capacity,
+            period,
+            store: Box::new(store),
+        }
+    }
+}
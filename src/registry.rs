@@ -1,5 +1,5 @@
 use crate::validators::InputValueValidator;
-use crate::{model, Any, Type as _, Value};
+use crate::{model, Any, Data, FieldError, FieldResult, InputValueType, Type as _, Value};
 use graphql_parser::query::Type as ParsedType;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
@@ -93,6 +93,7 @@ pub struct InputValue {
     pub description: Option<&'static str>,
     pub ty: String,
     pub default_value: Option<&'static str>,
+    pub parsed_default_value: Option<Value>,
     pub validator: Option<Arc<dyn InputValueValidator>>,
 }
 
@@ -107,8 +108,125 @@ pub struct Field {
     pub external: bool,
     pub requires: Option<&'static str>,
     pub provides: Option<&'static str>,
+    pub compute_complexity: Option<ComplexityType>,
+    pub visible: Option<MetaVisibleFn>,
 }
 
+/// Decides from the data of a request whether a field or a type is visible to it.
+///
+/// The hidden fields and types are filtered from the introspection, and rejected by the validation as if they did not exist.
+pub type MetaVisibleFn = fn(&Data) -> bool;
+
+/// Computes the complexity of a field from its arguments, with the variables substituted, and the complexity of its selection set.
+pub type ComputeComplexityFn = fn(&[(String, Value)], usize) -> FieldResult<usize>;
+
+/// Custom complexity of a field
+#[derive(Clone, Copy)]
+pub enum ComplexityType {
+    /// Constant cost added to the complexity of the selection set.
+    Const(usize),
+
+    /// Cost computed by a function, it includes the complexity of the selection set.
+    Fn(ComputeComplexityFn),
+}
+
+#[doc(hidden)]
+pub fn complexity_arg<T: InputValueType, F: FnOnce() -> Value>(
+    args: &[(String, Value)],
+    name: &str,
+    default: F,
+) -> FieldResult<T> {
+    let value = args
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.clone())
+        .unwrap_or_else(default);
+    T::parse(&value).ok_or_else(|| {
+        FieldError(
+            format!(
+                "Invalid value for argument \"{}\", expected type \"{}\".",
+                name,
+                T::qualified_type_name()
+            ),
+            None,
+        )
+    })
+}
+
+/// The arithmetic operators of the complexity expressions, which fail instead of overflowing.
+#[doc(hidden)]
+pub trait ComplexityArith: Sized {
+    fn checked_add(self, rhs: Self) -> FieldResult<Self>;
+    fn checked_sub(self, rhs: Self) -> FieldResult<Self>;
+    fn checked_mul(self, rhs: Self) -> FieldResult<Self>;
+    fn checked_div(self, rhs: Self) -> FieldResult<Self>;
+    fn checked_rem(self, rhs: Self) -> FieldResult<Self>;
+}
+
+fn complexity_overflow() -> FieldError {
+    FieldError("Arithmetic overflow.".to_string(), None)
+}
+
+macro_rules! impl_complexity_arith_for_integers {
+    ($($ty:ty),*) => {
+        $(
+        impl ComplexityArith for $ty {
+            fn checked_add(self, rhs: Self) -> FieldResult<Self> {
+                <$ty>::checked_add(self, rhs).ok_or_else(complexity_overflow)
+            }
+
+            fn checked_sub(self, rhs: Self) -> FieldResult<Self> {
+                <$ty>::checked_sub(self, rhs).ok_or_else(complexity_overflow)
+            }
+
+            fn checked_mul(self, rhs: Self) -> FieldResult<Self> {
+                <$ty>::checked_mul(self, rhs).ok_or_else(complexity_overflow)
+            }
+
+            fn checked_div(self, rhs: Self) -> FieldResult<Self> {
+                <$ty>::checked_div(self, rhs).ok_or_else(complexity_overflow)
+            }
+
+            fn checked_rem(self, rhs: Self) -> FieldResult<Self> {
+                <$ty>::checked_rem(self, rhs).ok_or_else(complexity_overflow)
+            }
+        }
+        )*
+    };
+}
+
+impl_complexity_arith_for_integers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_complexity_arith_for_floats {
+    ($($ty:ty),*) => {
+        $(
+        impl ComplexityArith for $ty {
+            fn checked_add(self, rhs: Self) -> FieldResult<Self> {
+                Ok(self + rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> FieldResult<Self> {
+                Ok(self - rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> FieldResult<Self> {
+                Ok(self * rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> FieldResult<Self> {
+                Ok(self / rhs)
+            }
+
+            fn checked_rem(self, rhs: Self) -> FieldResult<Self> {
+                Ok(self % rhs)
+            }
+        }
+        )*
+    };
+}
+
+impl_complexity_arith_for_floats!(f32, f64);
+
 #[derive(Clone)]
 pub struct EnumValue {
     pub name: &'static str,
@@ -205,6 +323,7 @@ pub enum Type {
         cache_control: CacheControl,
         extends: bool,
         keys: Option<Vec<String>>,
+        visible: Option<MetaVisibleFn>,
     },
     Interface {
         name: String,
@@ -213,6 +332,7 @@ pub enum Type {
         possible_types: HashSet<String>,
         extends: bool,
         keys: Option<Vec<String>>,
+        visible: Option<MetaVisibleFn>,
     },
     Union {
         name: String,
@@ -255,6 +375,20 @@ impl Type {
         }
     }
 
+    pub fn is_visible(&self, data: &Data) -> bool {
+        match self {
+            Type::Object {
+                visible: Some(visible),
+                ..
+            } => visible(data),
+            Type::Interface {
+                visible: Some(visible),
+                ..
+            } => visible(data),
+            _ => true,
+        }
+    }
+
     pub fn is_composite(&self) -> bool {
         match self {
             Type::Object { .. } => true,
@@ -357,6 +491,7 @@ impl Registry {
                     cache_control: Default::default(),
                     extends: false,
                     keys: None,
+                    visible: None,
                 },
             );
             let ty = f(self);
@@ -408,6 +543,42 @@ impl Registry {
         }
     }
 
+    /// Returns true if the type named `type_name` (the wrappers are ignored) exists and is visible.
+    pub(crate) fn is_visible_type(&self, type_name: &str, data: &Data) -> bool {
+        self.concrete_type_by_name(type_name)
+            .map(|ty| ty.is_visible(data))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the field of `parent_type` and its type are visible.
+    ///
+    /// The field of an interface is hidden if it is hidden on any of the objects implementing it.
+    pub(crate) fn is_visible_field(&self, parent_type: &Type, field: &Field, data: &Data) -> bool {
+        let is_visible = |field: &Field| field.visible.map(|visible| visible(data)).unwrap_or(true);
+        let is_visible_on_possible_types = match parent_type {
+            Type::Interface { possible_types, .. } => possible_types
+                .iter()
+                .filter_map(|name| self.types.get(name))
+                .filter_map(|ty| ty.field_by_name(&field.name))
+                .all(is_visible),
+            _ => true,
+        };
+        is_visible(field) && is_visible_on_possible_types && self.is_visible_type(&field.ty, data)
+    }
+
+    /// Returns true if any field or type has a visibility predicate.
+    pub(crate) fn has_visibility(&self) -> bool {
+        self.types.values().any(|ty| match ty {
+            Type::Object {
+                visible, fields, ..
+            }
+            | Type::Interface {
+                visible, fields, ..
+            } => visible.is_some() || fields.values().any(|field| field.visible.is_some()),
+            _ => false,
+        })
+    }
+
     fn create_federation_fields<'a, I: Iterator<Item = &'a Field>>(sdl: &mut String, it: I) {
         for field in it {
             if field.name.starts_with("__") {
@@ -496,6 +667,157 @@ impl Registry {
         sdl
     }
 
+    /// Create the SDL of the schema, the definitions are sorted by name.
+    pub fn export_sdl(&self) -> String {
+        let mut sdl = String::new();
+
+        writeln!(sdl, "schema {{").ok();
+        writeln!(sdl, "  query: {}", self.query_type).ok();
+        if let Some(mutation_type) = &self.mutation_type {
+            writeln!(sdl, "  mutation: {}", mutation_type).ok();
+        }
+        if let Some(subscription_type) = &self.subscription_type {
+            writeln!(sdl, "  subscription: {}", subscription_type).ok();
+        }
+        writeln!(sdl, "}}").ok();
+
+        let mut directives = self
+            .directives
+            .values()
+            .filter(|directive| !is_builtin_directive(directive.name))
+            .collect::<Vec<_>>();
+        directives.sort_by_key(|directive| directive.name);
+        for directive in directives {
+            writeln!(sdl).ok();
+            export_description(&mut sdl, directive.description, "");
+            write!(sdl, "directive @{}", directive.name).ok();
+            export_args(&mut sdl, &directive.args, "");
+            writeln!(
+                sdl,
+                " on {}",
+                directive
+                    .locations
+                    .iter()
+                    .map(|location| format!("{:?}", location))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            )
+            .ok();
+        }
+
+        let mut types = self
+            .types
+            .values()
+            .filter(|ty| !ty.name().starts_with("__") && !is_builtin_scalar(ty.name()))
+            .collect::<Vec<_>>();
+        types.sort_by_key(|ty| ty.name());
+        for ty in types {
+            writeln!(sdl).ok();
+            self.export_type(&mut sdl, ty);
+        }
+
+        sdl
+    }
+
+    fn export_type(&self, sdl: &mut String, ty: &Type) {
+        match ty {
+            Type::Scalar {
+                name, description, ..
+            } => {
+                export_description(sdl, *description, "");
+                writeln!(sdl, "scalar {}", name).ok();
+            }
+            Type::Object {
+                name,
+                description,
+                fields,
+                ..
+            } => {
+                export_description(sdl, *description, "");
+                write!(sdl, "type {}", name).ok();
+                if let Some(implements) = self.implements.get(name) {
+                    let mut implements = implements.iter().collect::<Vec<_>>();
+                    implements.sort();
+                    write!(
+                        sdl,
+                        " implements {}",
+                        implements
+                            .into_iter()
+                            .map(String::as_str)
+                            .collect::<Vec<_>>()
+                            .join(" & ")
+                    )
+                    .ok();
+                }
+                export_fields(sdl, fields);
+            }
+            Type::Interface {
+                name,
+                description,
+                fields,
+                ..
+            } => {
+                export_description(sdl, *description, "");
+                write!(sdl, "interface {}", name).ok();
+                export_fields(sdl, fields);
+            }
+            Type::Union {
+                name,
+                description,
+                possible_types,
+            } => {
+                export_description(sdl, *description, "");
+                let mut possible_types = possible_types.iter().collect::<Vec<_>>();
+                possible_types.sort();
+                writeln!(
+                    sdl,
+                    "union {} = {}",
+                    name,
+                    possible_types
+                        .into_iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                )
+                .ok();
+            }
+            Type::Enum {
+                name,
+                description,
+                enum_values,
+            } => {
+                export_description(sdl, *description, "");
+                writeln!(sdl, "enum {} {{", name).ok();
+                let mut enum_values = enum_values.values().collect::<Vec<_>>();
+                enum_values.sort_by_key(|value| value.name);
+                for value in enum_values {
+                    export_description(sdl, value.description, "  ");
+                    write!(sdl, "  {}", value.name).ok();
+                    export_deprecation(sdl, value.deprecation);
+                    writeln!(sdl).ok();
+                }
+                writeln!(sdl, "}}").ok();
+            }
+            Type::InputObject {
+                name,
+                description,
+                input_fields,
+            } => {
+                export_description(sdl, *description, "");
+                writeln!(sdl, "input {} {{", name).ok();
+                let mut input_fields = input_fields.values().collect::<Vec<_>>();
+                input_fields.sort_by_key(|field| field.name);
+                for field in input_fields {
+                    export_description(sdl, field.description, "  ");
+                    write!(sdl, "  ").ok();
+                    export_input_value(sdl, field);
+                    writeln!(sdl).ok();
+                }
+                writeln!(sdl, "}}").ok();
+            }
+        }
+    }
+
     fn has_entities(&self) -> bool {
         self.types.values().any(|ty| match ty {
             Type::Object {
@@ -562,6 +884,8 @@ impl Registry {
                             cache_control: Default::default(),
                             external: false,
                             requires: None,
+                            compute_complexity: None,
+                            visible: None,
                             provides: None,
                         },
                     );
@@ -570,6 +894,7 @@ impl Registry {
                 cache_control: Default::default(),
                 extends: false,
                 keys: None,
+                visible: None,
             },
         );
 
@@ -588,6 +913,8 @@ impl Registry {
                     cache_control: Default::default(),
                     external: false,
                     requires: None,
+                    compute_complexity: None,
+                    visible: None,
                     provides: None,
                 },
             );
@@ -606,6 +933,7 @@ impl Registry {
                                 description: None,
                                 ty: "[_Any!]!".to_string(),
                                 default_value: None,
+                                parsed_default_value: None,
                                 validator: None,
                             },
                         );
@@ -616,9 +944,110 @@ impl Registry {
                     cache_control: Default::default(),
                     external: false,
                     requires: None,
+                    compute_complexity: None,
+                    visible: None,
                     provides: None,
                 },
             );
         }
     }
 }
+
+pub(crate) fn is_builtin_scalar(name: &str) -> bool {
+    matches!(name, "Int" | "Float" | "String" | "Boolean" | "ID")
+}
+
+pub(crate) fn is_builtin_directive(name: &str) -> bool {
+    matches!(name, "include" | "skip" | "deprecated" | "defer" | "stream")
+}
+
+fn export_description(sdl: &mut String, description: Option<&str>, indent: &str) {
+    if let Some(description) = description {
+        let description = description.replace("\"\"\"", "\\\"\"\"");
+        // A single line description ending with a quote would be closed too early.
+        if description.contains('\n') || description.ends_with('"') {
+            writeln!(sdl, "{}\"\"\"", indent).ok();
+            for line in description.lines() {
+                if line.is_empty() {
+                    writeln!(sdl).ok();
+                } else {
+                    writeln!(sdl, "{}{}", indent, line).ok();
+                }
+            }
+            writeln!(sdl, "{}\"\"\"", indent).ok();
+        } else {
+            writeln!(sdl, "{}\"\"\"{}\"\"\"", indent, description).ok();
+        }
+    }
+}
+
+fn export_deprecation(sdl: &mut String, deprecation: Option<&str>) {
+    if let Some(reason) = deprecation {
+        write!(
+            sdl,
+            " @deprecated(reason: {})",
+            serde_json::Value::String(reason.to_string())
+        )
+        .ok();
+    }
+}
+
+fn export_input_value(sdl: &mut String, input_value: &InputValue) {
+    write!(sdl, "{}: {}", input_value.name, input_value.ty).ok();
+    if let Some(default_value) = input_value.default_value {
+        write!(sdl, " = {}", default_value).ok();
+    }
+}
+
+fn export_args(sdl: &mut String, args: &HashMap<&'static str, InputValue>, indent: &str) {
+    if args.is_empty() {
+        return;
+    }
+    let mut args = args.values().collect::<Vec<_>>();
+    args.sort_by_key(|arg| arg.name);
+
+    if args.iter().any(|arg| arg.description.is_some()) {
+        // One argument per line, so that the descriptions can be printed
+        let arg_indent = format!("{}  ", indent);
+        writeln!(sdl, "(").ok();
+        for arg in args {
+            export_description(sdl, arg.description, &arg_indent);
+            write!(sdl, "{}", arg_indent).ok();
+            export_input_value(sdl, arg);
+            writeln!(sdl).ok();
+        }
+        write!(sdl, "{})", indent).ok();
+    } else {
+        write!(sdl, "(").ok();
+        for (idx, arg) in args.into_iter().enumerate() {
+            if idx > 0 {
+                write!(sdl, ", ").ok();
+            }
+            export_input_value(sdl, arg);
+        }
+        write!(sdl, ")").ok();
+    }
+}
+
+fn export_fields(sdl: &mut String, fields: &HashMap<String, Field>) {
+    let mut fields = fields
+        .values()
+        .filter(|field| !field.name.starts_with("__"))
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        writeln!(sdl).ok();
+        return;
+    }
+    fields.sort_by(|a, b| a.name.cmp(&b.name));
+
+    writeln!(sdl, " {{").ok();
+    for field in fields {
+        export_description(sdl, field.description, "  ");
+        write!(sdl, "  {}", field.name).ok();
+        export_args(sdl, &field.args, "  ");
+        write!(sdl, ": {}", field.ty).ok();
+        export_deprecation(sdl, field.deprecation);
+        writeln!(sdl).ok();
+    }
+    writeln!(sdl, "}}").ok();
+}
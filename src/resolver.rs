@@ -1,22 +1,91 @@
 use crate::base::BoxFieldFuture;
-use crate::extensions::ResolveInfo;
-use crate::{ContextSelectionSet, Error, ObjectType, QueryError, Result};
-use futures::{future, TryFutureExt};
+use crate::context::{Incremental, InitialValues};
+use crate::extensions::{EndGuard, NextResolve, ResolveInfo};
+use crate::query::with_deadline;
+use crate::registry::{Registry, TypeName};
+use crate::{
+    Context, ContextBase, ContextSelectionSet, Error, ObjectType, Pos, QueryError, QueryPatch,
+    Result,
+};
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::{future, FutureExt, TryFutureExt};
 use graphql_parser::query::{Selection, TypeCondition};
+use parking_lot::Mutex;
+use std::future::Future;
 use std::iter::FromIterator;
+use std::sync::atomic::Ordering;
+use std::task::Poll;
 
 #[allow(missing_docs)]
 pub async fn do_resolve<'a, T: ObjectType + Send + Sync>(
     ctx: &'a ContextSelectionSet<'a>,
     root: &'a T,
 ) -> Result<serde_json::Value> {
+    if let Some(incremental) = ctx.incremental {
+        let fields = InitialValues::default();
+        let ctx = ContextBase {
+            incremental: Some(Incremental {
+                fields: Some(&fields),
+                ..incremental
+            }),
+            ..ctx.clone()
+        };
+        let mut futures = Vec::new();
+        collect_fields(&ctx, root, &mut futures)?;
+        let res = join_initial(futures, &fields, |values| {
+            (incremental.initial)(serde_json::Map::from_iter(values.into_iter().flatten()).into())
+        })
+        .await?;
+        return Ok(serde_json::Map::from_iter(res.into_iter().flatten()).into());
+    }
+
     let mut futures = Vec::new();
     collect_fields(ctx, root, &mut futures)?;
     let res = futures::future::try_join_all(futures).await?;
-    let map = serde_json::Map::from_iter(res);
+    let map = serde_json::Map::from_iter(res.into_iter().flatten());
     Ok(map.into())
 }
 
+/// Resolves the fields of an object or the items of a list with incremental delivery.
+///
+/// The value of each future is stored in `initial` when it is resolved, or earlier if the future passes it on while
+/// resolving its incremental parts. Once every value is known, they are passed to `report` and the incremental parts
+/// keep being resolved.
+pub(crate) async fn join_initial<T, F>(
+    futures: Vec<F>,
+    initial: &InitialValues<T>,
+    report: impl FnOnce(Vec<T>),
+) -> Result<Vec<T>>
+where
+    T: Clone,
+    F: Future<Output = Result<T>>,
+{
+    let count = futures.len();
+    let mut futures = futures
+        .into_iter()
+        .enumerate()
+        .map(|(idx, fut)| fut.map(move |res| (idx, res)))
+        .collect::<FuturesUnordered<_>>();
+    let mut report = Some(report);
+    future::poll_fn(|cx| loop {
+        match futures.poll_next_unpin(cx) {
+            Poll::Ready(Some((idx, Ok(value)))) => initial.insert(idx, value),
+            Poll::Ready(Some((_, Err(err)))) => return Poll::Ready(Err(err)),
+            Poll::Ready(None) => return Poll::Ready(Ok(())),
+            Poll::Pending => {
+                if initial.len() == count {
+                    if let Some(report) = report.take() {
+                        report(initial.values());
+                    }
+                }
+                return Poll::Pending;
+            }
+        }
+    })
+    .await?;
+    Ok(initial.take_values())
+}
+
 #[allow(missing_docs)]
 pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
     ctx: &ContextSelectionSet<'a>,
@@ -28,7 +97,7 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
             pos: ctx.span.0,
             path: None,
             err: QueryError::MustHaveSubFields {
-                object: T::type_name().to_string(),
+                object: root.registry_type_name().to_string(),
             },
         });
     }
@@ -48,62 +117,53 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
                         future::ok::<serde_json::Value, Error>(
                             root.introspection_type_name().to_string().into(),
                         )
-                        .map_ok(move |value| (field_name, value)),
+                        .map_ok(move |value| Some((field_name, value))),
                     ));
                     continue;
                 }
 
+                let stream = ctx.stream_directive(&field.directives)?;
+                let idx = futures.len();
                 futures.push(Box::pin({
                     let ctx = ctx.clone();
                     async move {
-                        let ctx_field = ctx.with_field(field);
+                        let ctx_field = ctx
+                            .with_field(field)
+                            .with_parent_type(&root.registry_type_name());
                         let field_name = ctx_field.result_name().to_string();
-
-                        if !ctx_field.extensions.is_empty() {
-                            let resolve_info = ResolveInfo {
-                                resolve_id: ctx_field.resolve_id,
-                                path_node: ctx_field.path_node.as_ref().unwrap(),
-                                parent_type: &T::type_name(),
-                                return_type: match ctx_field
-                                    .registry
-                                    .types
-                                    .get(T::type_name().as_ref())
-                                    .and_then(|ty| ty.field_by_name(field.name.as_str()))
-                                    .map(|field| &field.ty)
+                        let report = |value| {
+                            if let Some(Incremental {
+                                fields: Some(fields),
+                                ..
+                            }) = ctx.incremental
+                            {
+                                fields.insert(idx, Some((field_name.clone(), value)));
+                            }
+                        };
+                        // The value transformed by a custom directive is only known once the field is resolved.
+                        let has_custom_directives = field
+                            .directives
+                            .iter()
+                            .any(|directive| ctx.directives.contains_key(&directive.name));
+                        let ctx_field = ContextBase {
+                            incremental: match ctx.incremental {
+                                Some(incremental)
+                                    if incremental.fields.is_some() && !has_custom_directives =>
                                 {
-                                    Some(ty) => &ty,
-                                    None => {
-                                        return Err(Error::Query {
-                                            pos: field.position,
-                                            path: None,
-                                            err: QueryError::FieldNotFound {
-                                                field_name: field.name.clone(),
-                                                object: T::type_name().to_string(),
-                                            },
-                                        });
-                                    }
-                                },
-                            };
-
-                            ctx_field
-                                .extensions
-                                .iter()
-                                .for_each(|e| e.resolve_field_start(&resolve_info));
-                        }
-
-                        let res = root
-                            .resolve_field(&ctx_field)
-                            .map_ok(move |value| (field_name, value))
-                            .await?;
-
-                        if !ctx_field.extensions.is_empty() {
-                            ctx_field
-                                .extensions
-                                .iter()
-                                .for_each(|e| e.resolve_field_end(ctx_field.resolve_id));
-                        }
-
-                        Ok(res)
+                                    Some(Incremental {
+                                        initial: &report,
+                                        fields: None,
+                                        stream,
+                                        ..incremental
+                                    })
+                                }
+                                _ => None,
+                            },
+                            ..ctx_field
+                        };
+
+                        let res = resolve_field_value(&ctx_field, root).await?;
+                        Ok(res.map(|value| (field_name, value)))
                     }
                 }))
             }
@@ -113,11 +173,19 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
                 }
 
                 if let Some(fragment) = ctx.fragments.get(fragment_spread.fragment_name.as_str()) {
-                    collect_fields(
-                        &ctx.with_selection_set(&fragment.selection_set),
-                        root,
-                        futures,
-                    )?;
+                    let ctx_fragment = ctx.with_selection_set(&fragment.selection_set);
+                    if let Some(label) = ctx.defer_directive(&fragment_spread.directives)? {
+                        defer_fragment(
+                            ctx_fragment,
+                            root,
+                            label,
+                            None,
+                            fragment_spread.position,
+                            futures,
+                        );
+                    } else {
+                        collect_fields(&ctx_fragment, root, futures)?;
+                    }
                 } else {
                     return Err(Error::Query {
                         pos: fragment_spread.position,
@@ -133,17 +201,26 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
                     continue;
                 }
 
-                if let Some(TypeCondition::On(name)) = &inline_fragment.type_condition {
-                    root.collect_inline_fields(
-                        name,
+                let ctx_fragment = ctx.with_selection_set(&inline_fragment.selection_set);
+                let type_condition = inline_fragment
+                    .type_condition
+                    .as_ref()
+                    .map(|TypeCondition::On(name)| name.as_str());
+                if let Some(label) = ctx.defer_directive(&inline_fragment.directives)? {
+                    defer_fragment(
+                        ctx_fragment,
+                        root,
+                        label,
+                        type_condition,
                         inline_fragment.position,
-                        &ctx.with_selection_set(&inline_fragment.selection_set),
                         futures,
-                    )?;
+                    );
                 } else {
-                    collect_fields(
-                        &ctx.with_selection_set(&inline_fragment.selection_set),
+                    collect_fragment_fields(
+                        &ctx_fragment,
                         root,
+                        type_condition,
+                        inline_fragment.position,
                         futures,
                     )?;
                 }
@@ -153,3 +230,168 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
 
     Ok(())
 }
+
+fn collect_fragment_fields<'a, T: ObjectType + Send + Sync>(
+    ctx: &ContextSelectionSet<'a>,
+    root: &'a T,
+    type_condition: Option<&str>,
+    pos: Pos,
+    futures: &mut Vec<BoxFieldFuture<'a>>,
+) -> Result<()> {
+    match type_condition {
+        Some(name) => root.collect_inline_fields(name, pos, ctx, futures),
+        None => collect_fields(ctx, root, futures),
+    }
+}
+
+/// Leaves a deferred fragment out of the initial response, and resolves it as a patch from the same object once
+/// the initial response is sent.
+///
+/// Incremental delivery is disabled inside the patch, so any nested `@defer` or `@stream` is resolved inline.
+fn defer_fragment<'a, T: ObjectType + Send + Sync>(
+    ctx: ContextSelectionSet<'a>,
+    root: &'a T,
+    label: Option<&'a str>,
+    type_condition: Option<&'a str>,
+    pos: Pos,
+    futures: &mut Vec<BoxFieldFuture<'a>>,
+) {
+    if let Some(Incremental {
+        has_next,
+        initial_sent,
+        fields: Some(fields),
+        ..
+    }) = ctx.incremental
+    {
+        has_next.store(true, Ordering::Relaxed);
+        fields.insert(futures.len(), None);
+        futures.push(Box::pin(async move {
+            initial_sent.clone().await.ok();
+            let path = ctx
+                .path_node
+                .as_ref()
+                .map(|path_node| path_node.to_json())
+                .unwrap_or_else(|| serde_json::Value::Array(Vec::new()));
+            let errors = Mutex::new(Vec::new());
+            let ctx_patch = ContextBase {
+                errors: Some(&errors),
+                incremental: None,
+                ..ctx.clone()
+            };
+            let mut fields = Vec::new();
+            let res =
+                match collect_fragment_fields(&ctx_patch, root, type_condition, pos, &mut fields) {
+                    Ok(()) if fields.is_empty() => None,
+                    Ok(()) => Some(future::try_join_all(fields).await),
+                    Err(err) => Some(Err(err)),
+                };
+            if let Some(res) = res {
+                let data = match res {
+                    Ok(values) => serde_json::Map::from_iter(values.into_iter().flatten()).into(),
+                    Err(err) => {
+                        errors.lock().push(err);
+                        serde_json::Value::Null
+                    }
+                };
+                ctx.send_patch(QueryPatch::Defer {
+                    label: label.map(ToString::to_string),
+                    path,
+                    data,
+                    errors: std::mem::take(&mut *errors.lock()),
+                });
+            }
+
+            Ok(None)
+        }));
+    }
+}
+
+/// Resolves the value of the field with its custom directives, returns `None` if a directive leaves it out of the response.
+pub(crate) async fn resolve_field_value<T: ObjectType + Send + Sync>(
+    ctx_field: &Context<'_>,
+    root: &T,
+) -> Result<Option<serde_json::Value>> {
+    let res = async {
+        if !ctx_field.include_by_directives().await? {
+            return Ok(None);
+        }
+        let resolve = async {
+            let value = root.resolve_field(ctx_field).await?;
+            ctx_field.resolve_by_directives(value).await
+        };
+        if ctx_field.extensions.is_empty() {
+            return resolve.await.map(Some);
+        }
+
+        let parent_type = root.registry_type_name();
+        let resolve_info = ResolveInfo {
+            resolve_id: ctx_field.resolve_id,
+            path_node: ctx_field.path_node.as_ref().unwrap(),
+            parent_type: &parent_type,
+            field_name: &ctx_field.name,
+            return_type: match ctx_field
+                .registry
+                .types
+                .get(parent_type.as_ref())
+                .and_then(|ty| ty.field_by_name(ctx_field.name.as_str()))
+                .map(|field| &field.ty)
+            {
+                Some(ty) => &ty,
+                None => {
+                    return Err(Error::Query {
+                        pos: ctx_field.position,
+                        path: None,
+                        err: QueryError::FieldNotFound {
+                            field_name: ctx_field.name.clone(),
+                            object: parent_type.to_string(),
+                        },
+                    });
+                }
+            },
+        };
+
+        let resolve_id = ctx_field.resolve_id;
+        let _guard = EndGuard::start(
+            ctx_field.extensions,
+            |e| e.resolve_field_start(&resolve_info),
+            |e| e.resolve_field_end(resolve_id),
+        );
+        NextResolve::new(ctx_field.extensions, Box::pin(resolve))
+            .run(&resolve_info)
+            .await
+            .map(Some)
+    };
+    // A field which is not resolved before the deadline fails, the other fields keep their values.
+    let res = with_deadline(ctx_field.deadline, res)
+        .await
+        .unwrap_or_else(|| {
+            Err(Error::Query {
+                pos: ctx_field.position,
+                path: ctx_field.path_node.as_ref().map(|path| path.to_json()),
+                err: QueryError::Timeout,
+            })
+        });
+    match res {
+        Ok(value) => Ok(value),
+        Err(err) => ctx_field
+            .resolve_error(
+                err,
+                is_non_null_field(
+                    ctx_field.registry,
+                    &root.registry_type_name(),
+                    &ctx_field.name,
+                ),
+            )
+            .map(Some),
+    }
+}
+
+/// Returns true if the field `name` of the type `type_name` is declared as non-null in the registry.
+pub(crate) fn is_non_null_field(registry: &Registry, type_name: &str, name: &str) -> bool {
+    registry
+        .types
+        .get(type_name)
+        .and_then(|ty| ty.field_by_name(name))
+        .map(|field| TypeName::create(&field.ty).is_non_null())
+        .unwrap_or(true)
+}
@@ -0,0 +1,144 @@
+//! Operation safelist
+//!
+//! In safelist mode, the schema only executes the documents of a manifest, which are usually extracted from the
+//! clients at build time. A request refers to a document by its id (the `id` of the request or the hash of an
+//! automatic persisted query), or sends a query source that exactly matches one of the documents.
+//! Any other request is rejected with `QueryError::NotSafelisted`.
+//!
+//! ```rust
+//! use async_graphql::*;
+//! use async_graphql::safelist::Safelist;
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn value(&self) -> i32 {
+//!         100
+//!     }
+//! }
+//!
+//! async_std::task::block_on(async move {
+//!     let safelist = Safelist::from_json(r#"{ "GetValue": "query GetValue { value }" }"#).unwrap();
+//!     let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+//!         .safelist(safelist)
+//!         .finish();
+//!
+//!     let res = QueryBuilder::new("").document_id("GetValue").execute(&schema).await.unwrap();
+//!     assert_eq!(res.data, serde_json::json!({ "value": 100 }));
+//!     assert!(schema.execute("query GetValue { value }").await.is_ok());
+//!     assert!(schema.execute("{ value }").await.is_err());
+//! });
+//! ```
+
+use crate::query::current_operation;
+use crate::{Pos, QueryError, Result};
+use graphql_parser::parse_query;
+use graphql_parser::query::{Definition, Document, Selection, SelectionSet};
+use std::collections::{HashMap, HashSet};
+
+/// Manifest of the documents allowed by the schema
+#[derive(Default)]
+pub struct Safelist {
+    documents: HashMap<String, String>,
+    sources: HashSet<String>,
+    allow_introspection: bool,
+}
+
+impl Safelist {
+    /// Create an empty safelist.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Load a manifest, which is a JSON object mapping the ids (or hashes) to the documents.
+    pub fn from_json(manifest: &str) -> serde_json::Result<Self> {
+        let documents: HashMap<String, String> = serde_json::from_str(manifest)?;
+        Ok(documents
+            .into_iter()
+            .fold(Self::new(), |safelist, (id, document)| {
+                safelist.document(id, document)
+            }))
+    }
+
+    /// Add the document `id` to the safelist.
+    pub fn document<I: Into<String>, D: Into<String>>(mut self, id: I, document: D) -> Self {
+        let document = document.into();
+        self.sources.insert(document.clone());
+        self.documents.insert(id.into(), document);
+        self
+    }
+
+    /// Allow the queries that only select introspection fields, such as `__schema` and `__type`.
+    pub fn allow_introspection(self) -> Self {
+        Self {
+            allow_introspection: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<&str> {
+        self.documents.get(id).map(String::as_str)
+    }
+
+    /// Check that the query source is one of the documents, or an introspection query if it is allowed.
+    ///
+    /// It is shared by the queries and the subscriptions, whose operations are never introspection queries.
+    pub(crate) fn check(&self, source: &str, operation_name: Option<&str>) -> Result<()> {
+        if self.sources.contains(source) {
+            return Ok(());
+        }
+        let is_introspection = self.allow_introspection
+            && parse_query(source)
+                .ok()
+                .and_then(|document| {
+                    current_operation(&document, operation_name).map(
+                        |(selection_set, _, is_query, _)| {
+                            is_query && is_introspection_only(&document, selection_set)
+                        },
+                    )
+                })
+                .unwrap_or_default();
+        if is_introspection {
+            Ok(())
+        } else {
+            Err(QueryError::NotSafelisted.into_error(Pos::default()))
+        }
+    }
+}
+
+/// Returns true if the selection set only selects the introspection fields, the fragments are expanded.
+fn is_introspection_only(document: &Document, selection_set: &SelectionSet) -> bool {
+    check_introspection_only(document, selection_set, &mut HashSet::new())
+}
+
+// The document is not validated yet, so the fragments may be cyclic, `visited` holds the fragments being expanded.
+fn check_introspection_only<'a>(
+    document: &'a Document,
+    selection_set: &'a SelectionSet,
+    visited: &mut HashSet<&'a str>,
+) -> bool {
+    selection_set.items.iter().all(|selection| match selection {
+        Selection::Field(field) => field.name.starts_with("__"),
+        Selection::FragmentSpread(fragment_spread) => {
+            if !visited.insert(&fragment_spread.fragment_name) {
+                return false;
+            }
+            let res = document
+                .definitions
+                .iter()
+                .any(|definition| match definition {
+                    Definition::Fragment(fragment) => {
+                        fragment.name == fragment_spread.fragment_name
+                            && check_introspection_only(document, &fragment.selection_set, visited)
+                    }
+                    _ => false,
+                });
+            visited.remove(fragment_spread.fragment_name.as_str());
+            res
+        }
+        Selection::InlineFragment(inline_fragment) => {
+            check_introspection_only(document, &inline_fragment.selection_set, visited)
+        }
+    })
+}
@@ -1,3 +1,4 @@
+use crate::scalars::int_value;
 use crate::{Result, ScalarType, Value};
 use async_graphql_derive::Scalar;
 
@@ -16,12 +17,40 @@ macro_rules! impl_float_scalars {
 
             fn parse(value: &Value) -> Option<Self> {
                 match value {
-                    Value::Int(n) => Some(n.as_i64().unwrap() as Self),
-                    Value::Float(n) => Some(*n as Self),
+                    Value::Float(n) => {
+                        let f = *n as Self;
+                        if f.is_finite() {
+                            Some(f)
+                        } else {
+                            None
+                        }
+                    }
+                    // The integers are rounded to the nearest representable value.
+                    _ => int_value(value).map(|n| n as Self),
+                }
+            }
+
+            fn parse_error(value: &Value) -> Option<String> {
+                match value {
+                    Value::Float(n) if Self::parse(value).is_none() => Some(format!(
+                        "{:e} is out of range, the value must be between {:e} and {:e}",
+                        n,
+                        Self::MIN,
+                        Self::MAX
+                    )),
                     _ => None
                 }
             }
 
+            // Both types are named `Float`, the range of each one is only checked when the value is parsed.
+            fn is_valid(value: &Value) -> bool {
+                match value {
+                    Value::Int(_) => true,
+                    Value::Float(_) => <f64 as ScalarType>::parse(value).is_some(),
+                    _ => false
+                }
+            }
+
             fn to_json(&self) -> Result<serde_json::Value> {
                 Ok((*self).into())
             }
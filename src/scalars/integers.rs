@@ -1,5 +1,17 @@
 use crate::{Result, ScalarType, Value};
 use async_graphql_derive::Scalar;
+use std::convert::TryFrom;
+
+/// Returns the integer of an `Int` value.
+///
+/// `Number` can only be created from an `i32`, the variables hold the integers outside of its range as strings.
+pub(crate) fn int_value(value: &Value) -> Option<i128> {
+    match value {
+        Value::Int(n) => n.as_i64().map(i128::from),
+        Value::String(s) => s.parse().ok().filter(|n| i32::try_from(*n).is_err()),
+        _ => None,
+    }
+}
 
 macro_rules! impl_integer_scalars {
     ($($ty:ty),*) => {
@@ -15,12 +27,32 @@ macro_rules! impl_integer_scalars {
             }
 
             fn parse(value: &Value) -> Option<Self> {
-                match value {
-                    Value::Int(n) => Some(n.as_i64().unwrap() as Self),
+                int_value(value).and_then(|n| Self::try_from(n).ok())
+            }
+
+            fn parse_error(value: &Value) -> Option<String> {
+                match int_value(value) {
+                    Some(n) if Self::try_from(n).is_err() => Some(format!(
+                        "{} is out of range, the value must be between {} and {}",
+                        n,
+                        Self::MIN,
+                        Self::MAX
+                    )),
                     _ => None
                 }
             }
 
+            // All these types are named `Int`, the range of each one is only checked when the value is parsed.
+            fn is_valid(value: &Value) -> bool {
+                match value {
+                    Value::Int(n) => {
+                        let n = n.as_i64().unwrap();
+                        n >= i64::from(i32::MIN) && n <= i64::from(u32::MAX)
+                    }
+                    _ => false
+                }
+            }
+
             fn to_json(&self) -> Result<serde_json::Value> {
                 Ok((*self).into())
             }
@@ -46,12 +78,39 @@ macro_rules! impl_int64_scalars {
 
             fn parse(value: &Value) -> Option<Self> {
                 match value {
-                    Value::Int(n) => Some(n.as_i64().unwrap() as Self),
+                    Value::Int(n) => Self::try_from(n.as_i64().unwrap()).ok(),
                     Value::String(s) => s.parse().ok(),
                     _ => None
                 }
             }
 
+            fn parse_error(value: &Value) -> Option<String> {
+                let n = match value {
+                    Value::Int(n) => i128::from(n.as_i64().unwrap()),
+                    Value::String(s) => s.parse::<i128>().ok()?,
+                    _ => return None,
+                };
+                if Self::parse(value).is_none() {
+                    Some(format!(
+                        "{} is out of range, the value must be between {} and {}",
+                        n,
+                        Self::MIN,
+                        Self::MAX
+                    ))
+                } else {
+                    None
+                }
+            }
+
+            // Both types are named `Int64`, the range of each one is only checked when the value is parsed.
+            fn is_valid(value: &Value) -> bool {
+                match value {
+                    Value::Int(_) => true,
+                    Value::String(s) => s.parse::<i64>().is_ok() || s.parse::<u64>().is_ok(),
+                    _ => false
+                }
+            }
+
             fn to_json(&self) -> Result<serde_json::Value> {
                 Ok(self.to_string().into())
             }
@@ -16,6 +16,8 @@ mod uuid;
 pub use any::Any;
 pub use id::ID;
 
+pub(crate) use integers::int_value;
+
 #[cfg(test)]
 mod tests {
     use super::ID;
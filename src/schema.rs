@@ -1,24 +1,32 @@
 use crate::context::Data;
+use crate::directive::Directive as CustomDirective;
 use crate::extensions::{BoxExtension, Extension};
 use crate::model::__DirectiveLocation;
+use crate::persisted_query::PersistedQueryStore;
 use crate::query::QueryBuilder;
+use crate::query::{check_complexity, CachedDocument};
+use crate::rate_limit::ComplexityBudget;
 use crate::registry::{Directive, InputValue, Registry};
+use crate::safelist::Safelist;
 use crate::subscription::{create_connection, create_subscription_stream, SubscriptionTransport};
 use crate::types::QueryRoot;
-use crate::validation::{check_rules, ValidationMode};
+use crate::validation::{check_rules, check_visibility, CheckResult, ValidationMode};
 use crate::{
     Environment, Error, ObjectType, Pos, QueryError, QueryResponse, Result, SubscriptionStream,
-    SubscriptionType, Type, Variables,
+    SubscriptionType, Type, Value, Variables,
 };
 use bytes::Bytes;
 use futures::channel::mpsc;
 use futures::Stream;
 use graphql_parser::parse_query;
 use graphql_parser::query::{Definition, OperationDefinition};
+use lru::LruCache;
+use parking_lot::Mutex;
 use std::any::Any;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub(crate) struct SchemaInner<Query, Mutation, Subscription> {
     pub(crate) validation_mode: ValidationMode,
@@ -26,10 +34,17 @@ pub(crate) struct SchemaInner<Query, Mutation, Subscription> {
     pub(crate) mutation: Mutation,
     pub(crate) subscription: Subscription,
     pub(crate) registry: Registry,
+    pub(crate) has_visibility: bool,
     pub(crate) data: Data,
     pub(crate) complexity: Option<usize>,
     pub(crate) depth: Option<usize>,
+    pub(crate) timeout: Option<Duration>,
     pub(crate) extensions: Vec<Box<dyn Fn() -> BoxExtension + Send + Sync>>,
+    pub(crate) persisted_query_store: Option<Box<dyn PersistedQueryStore>>,
+    pub(crate) complexity_budget: Option<ComplexityBudget>,
+    pub(crate) safelist: Option<Safelist>,
+    pub(crate) document_cache: Option<Mutex<LruCache<String, Arc<CachedDocument>>>>,
+    pub(crate) directives: HashMap<String, Box<dyn CustomDirective>>,
 }
 
 /// Schema builder
@@ -38,6 +53,38 @@ pub struct SchemaBuilder<Query, Mutation, Subscription>(SchemaInner<Query, Mutat
 impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
     SchemaBuilder<Query, Mutation, Subscription>
 {
+    pub(crate) fn new(
+        query: Query,
+        mutation: Mutation,
+        subscription: Subscription,
+        mut registry: Registry,
+    ) -> Self {
+        // federation
+        registry.create_federation_types();
+
+        SchemaBuilder(SchemaInner {
+            validation_mode: ValidationMode::Strict,
+            query: QueryRoot {
+                inner: query,
+                disable_introspection: false,
+            },
+            mutation,
+            subscription,
+            registry,
+            has_visibility: false,
+            data: Default::default(),
+            complexity: None,
+            depth: None,
+            timeout: None,
+            extensions: Default::default(),
+            persisted_query_store: None,
+            complexity_budget: None,
+            safelist: None,
+            document_cache: None,
+            directives: Default::default(),
+        })
+    }
+
     /// You can use this function to register types that are not directly referenced.
     pub fn register_type<T: Type>(mut self) -> Self {
         T::create_type_info(&mut self.0.registry);
@@ -62,6 +109,12 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
         self
     }
 
+    /// Set the default execution timeout of the queries, `QueryBuilder::timeout` overrides it. Default no timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.0.timeout = Some(timeout);
+        self
+    }
+
     /// Add an extension
     pub fn extension<F: Fn() -> E + Send + Sync + 'static, E: Extension>(
         mut self,
@@ -85,8 +138,65 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
         self
     }
 
+    /// Enable automatic persisted queries with the specified query store.
+    pub fn persisted_query_store<S: PersistedQueryStore>(mut self, store: S) -> Self {
+        self.0.persisted_query_store = Some(Box::new(store));
+        self
+    }
+
+    /// Only execute the documents of the safelist, see [`safelist`](safelist/index.html).
+    pub fn safelist(mut self, safelist: Safelist) -> Self {
+        self.0.safelist = Some(safelist);
+        self
+    }
+
+    /// Limit the cumulative complexity of the queries of each client over time, see [`rate_limit`](rate_limit/index.html).
+    pub fn complexity_budget(mut self, budget: ComplexityBudget) -> Self {
+        self.0.complexity_budget = Some(budget);
+        self
+    }
+
+    /// Cache the parsed and validated documents of at most `capacity` query sources, the least recently used are evicted first.
+    ///
+    /// Executing a query that is in the cache skips the parsing and the validation. Default no cache.
+    pub fn document_cache(mut self, capacity: usize) -> Self {
+        self.0.document_cache = Some(Mutex::new(LruCache::new(capacity)));
+        self
+    }
+
+    /// Add a custom directive, it can be used on the fields of the queries.
+    pub fn directive<D: CustomDirective>(mut self, directive: D) -> Self {
+        self.0.registry.add_directive(Directive {
+            name: directive.name(),
+            description: directive.description(),
+            locations: vec![__DirectiveLocation::FIELD],
+            args: directive
+                .args()
+                .into_iter()
+                .map(|arg| {
+                    (
+                        arg.name,
+                        InputValue {
+                            name: arg.name,
+                            description: arg.description,
+                            ty: arg.ty,
+                            default_value: None,
+                            parsed_default_value: None,
+                            validator: None,
+                        },
+                    )
+                })
+                .collect(),
+        });
+        self.0
+            .directives
+            .insert(directive.name().to_string(), Box::new(directive));
+        self
+    }
+
     /// Build schema.
-    pub fn finish(self) -> Schema<Query, Mutation, Subscription> {
+    pub fn finish(mut self) -> Schema<Query, Mutation, Subscription> {
+        self.0.has_visibility = self.0.registry.has_visibility();
         Schema(Arc::new(self.0))
     }
 }
@@ -118,70 +228,19 @@ where
         mutation: Mutation,
         subscription: Subscription,
     ) -> SchemaBuilder<Query, Mutation, Subscription> {
-        let mut registry = Registry {
-            types: Default::default(),
-            directives: Default::default(),
-            implements: Default::default(),
-            query_type: Query::type_name().to_string(),
-            mutation_type: if Mutation::is_empty() {
+        let mut registry = create_registry(
+            Query::type_name().to_string(),
+            if Mutation::is_empty() {
                 None
             } else {
                 Some(Mutation::type_name().to_string())
             },
-            subscription_type: if Subscription::is_empty() {
+            if Subscription::is_empty() {
                 None
             } else {
                 Some(Subscription::type_name().to_string())
             },
-        };
-
-        registry.add_directive(Directive {
-            name: "include",
-            description: Some("Directs the executor to include this field or fragment only when the `if` argument is true."),
-            locations: vec![
-                __DirectiveLocation::FIELD,
-                __DirectiveLocation::FRAGMENT_SPREAD,
-                __DirectiveLocation::INLINE_FRAGMENT
-            ],
-            args: {
-                let mut args = HashMap::new();
-                args.insert("if", InputValue {
-                    name: "if",
-                    description: Some("Included when true."),
-                    ty: "Boolean!".to_string(),
-                    default_value: None,
-                    validator: None,
-                });
-                args
-            }
-        });
-
-        registry.add_directive(Directive {
-            name: "skip",
-            description: Some("Directs the executor to skip this field or fragment when the `if` argument is true."),
-            locations: vec![
-                __DirectiveLocation::FIELD,
-                __DirectiveLocation::FRAGMENT_SPREAD,
-                __DirectiveLocation::INLINE_FRAGMENT
-            ],
-            args: {
-                let mut args = HashMap::new();
-                args.insert("if", InputValue {
-                    name: "if",
-                    description: Some("Skipped when true."),
-                    ty: "Boolean!".to_string(),
-                    default_value: None,
-                    validator: None,
-                });
-                args
-            }
-        });
-
-        // register scalars
-        bool::create_type_info(&mut registry);
-        i32::create_type_info(&mut registry);
-        f32::create_type_info(&mut registry);
-        String::create_type_info(&mut registry);
+        );
 
         QueryRoot::<Query>::create_type_info(&mut registry);
         if !Mutation::is_empty() {
@@ -191,23 +250,7 @@ where
             Subscription::create_type_info(&mut registry);
         }
 
-        // federation
-        registry.create_federation_types();
-
-        SchemaBuilder(SchemaInner {
-            validation_mode: ValidationMode::Strict,
-            query: QueryRoot {
-                inner: query,
-                disable_introspection: false,
-            },
-            mutation,
-            subscription,
-            registry,
-            data: Default::default(),
-            complexity: None,
-            depth: None,
-            extensions: Default::default(),
-        })
+        SchemaBuilder::new(query, mutation, subscription, registry)
     }
 
     /// Create a schema
@@ -229,6 +272,14 @@ where
         &self.0.registry
     }
 
+    /// Returns the SDL of the schema.
+    ///
+    /// Types, fields, arguments and enum values are sorted by name, so the output is stable
+    /// and can be checked into source control.
+    pub fn sdl(&self) -> String {
+        self.0.registry.export_sdl()
+    }
+
     /// Execute query without create the `QueryBuilder`.
     pub async fn execute(&self, query_source: &str) -> Result<QueryResponse> {
         QueryBuilder::new(query_source).execute(self).await
@@ -242,8 +293,58 @@ where
         variables: Variables,
         ctx_data: Option<Arc<Data>>,
     ) -> Result<impl Stream<Item = Result<serde_json::Value>> + Send> {
+        if let Some(safelist) = &self.0.safelist {
+            safelist.check(source, operation_name)?;
+        }
         let document = parse_query(source).map_err(Into::<Error>::into)?;
-        check_rules(&self.0.registry, &document, self.0.validation_mode)?;
+        let CheckResult { depth, .. } =
+            check_rules(&self.0.registry, &document, self.0.validation_mode)?;
+        if self.0.has_visibility {
+            let empty = Data::default();
+            check_visibility(
+                &self.0.registry,
+                &document,
+                ctx_data.as_deref().unwrap_or(&empty),
+            )?;
+        }
+
+        // check limit
+        if self.0.complexity.is_some() || self.0.complexity_budget.is_some() {
+            let subscription =
+                document
+                    .definitions
+                    .iter()
+                    .find_map(|definition| match definition {
+                        Definition::Operation(OperationDefinition::Subscription(s))
+                            if s.name.as_deref() == operation_name || operation_name.is_none() =>
+                        {
+                            Some(s)
+                        }
+                        _ => None,
+                    });
+            if let Some(subscription) = subscription {
+                check_complexity(
+                    self,
+                    &document,
+                    self.0
+                        .registry
+                        .subscription_type
+                        .as_deref()
+                        .unwrap_or_default(),
+                    &subscription.selection_set,
+                    &subscription.variable_definitions,
+                    &variables,
+                    ctx_data.as_deref(),
+                )
+                .await?;
+            }
+        }
+
+        if let Some(limit_depth) = self.0.depth {
+            if depth > limit_depth {
+                return Err(QueryError::TooDeep.into_error(Pos::default()));
+            }
+        }
 
         let mut fragments = HashMap::new();
         let mut subscription = None;
@@ -279,6 +380,7 @@ where
             variable_definitions: subscription.variable_definitions,
             fragments,
             ctx_data: ctx_data.unwrap_or_default(),
+            http_headers: Default::default(),
         });
         let ctx = environment.create_context(self, None, &subscription.selection_set, &resolve_id);
         let mut streams = Vec::new();
@@ -297,3 +399,141 @@ where
         create_connection(self.clone(), transport)
     }
 }
+
+/// Creates a registry with the built-in directives and scalars.
+pub(crate) fn create_registry(
+    query_type: String,
+    mutation_type: Option<String>,
+    subscription_type: Option<String>,
+) -> Registry {
+    let mut registry = Registry {
+        types: Default::default(),
+        directives: Default::default(),
+        implements: Default::default(),
+        query_type,
+        mutation_type,
+        subscription_type,
+    };
+
+    registry.add_directive(Directive {
+        name: "include",
+        description: Some("Directs the executor to include this field or fragment only when the `if` argument is true."),
+        locations: vec![
+            __DirectiveLocation::FIELD,
+            __DirectiveLocation::FRAGMENT_SPREAD,
+            __DirectiveLocation::INLINE_FRAGMENT
+        ],
+        args: {
+            let mut args = HashMap::new();
+            args.insert("if", InputValue {
+                name: "if",
+                description: Some("Included when true."),
+                ty: "Boolean!".to_string(),
+                default_value: None,
+                parsed_default_value: None,
+                validator: None,
+            });
+            args
+        }
+    });
+
+    registry.add_directive(Directive {
+        name: "skip",
+        description: Some(
+            "Directs the executor to skip this field or fragment when the `if` argument is true.",
+        ),
+        locations: vec![
+            __DirectiveLocation::FIELD,
+            __DirectiveLocation::FRAGMENT_SPREAD,
+            __DirectiveLocation::INLINE_FRAGMENT,
+        ],
+        args: {
+            let mut args = HashMap::new();
+            args.insert(
+                "if",
+                InputValue {
+                    name: "if",
+                    description: Some("Skipped when true."),
+                    ty: "Boolean!".to_string(),
+                    default_value: None,
+                    parsed_default_value: None,
+                    validator: None,
+                },
+            );
+            args
+        },
+    });
+
+    registry.add_directive(Directive {
+        name: "defer",
+        description: Some("Directs the executor to deliver this fragment after the rest of the response, when the query is executed with incremental delivery."),
+        locations: vec![
+            __DirectiveLocation::FRAGMENT_SPREAD,
+            __DirectiveLocation::INLINE_FRAGMENT
+        ],
+        args: {
+            let mut args = HashMap::new();
+            args.insert("if", InputValue {
+                name: "if",
+                description: Some("Deferred when true."),
+                ty: "Boolean".to_string(),
+                default_value: Some("true"),
+                parsed_default_value: Some(Value::Boolean(true)),
+                validator: None,
+            });
+            args.insert("label", InputValue {
+                name: "label",
+                description: Some("Identifies the patch containing this fragment."),
+                ty: "String".to_string(),
+                default_value: None,
+                parsed_default_value: None,
+                validator: None,
+            });
+            args
+        }
+    });
+
+    registry.add_directive(Directive {
+        name: "stream",
+        description: Some("Directs the executor to deliver the items of this list one by one after the first `initialCount` items, when the query is executed with incremental delivery."),
+        locations: vec![
+            __DirectiveLocation::FIELD
+        ],
+        args: {
+            let mut args = HashMap::new();
+            args.insert("if", InputValue {
+                name: "if",
+                description: Some("Streamed when true."),
+                ty: "Boolean".to_string(),
+                default_value: Some("true"),
+                parsed_default_value: Some(Value::Boolean(true)),
+                validator: None,
+            });
+            args.insert("label", InputValue {
+                name: "label",
+                description: Some("Identifies the patches containing the items of this list."),
+                ty: "String".to_string(),
+                default_value: None,
+                parsed_default_value: None,
+                validator: None,
+            });
+            args.insert("initialCount", InputValue {
+                name: "initialCount",
+                description: Some("Number of items in the initial response."),
+                ty: "Int".to_string(),
+                default_value: Some("0"),
+                parsed_default_value: Some(Value::Int(0.into())),
+                validator: None,
+            });
+            args
+        }
+    });
+
+    // register scalars
+    bool::create_type_info(&mut registry);
+    i32::create_type_info(&mut registry);
+    f32::create_type_info(&mut registry);
+    String::create_type_info(&mut registry);
+
+    registry
+}
@@ -52,7 +52,8 @@ where
                             .0
                             .subscription
                             .create_field_stream(
-                                &ctx.with_field(field),
+                                &ctx.with_field(field)
+                                    .with_parent_type(&Subscription::type_name()),
                                 schema,
                                 environment.clone(),
                             )
@@ -79,8 +79,7 @@ impl SubscriptionTransport for WebSocketTransport {
                         if let Ok(request) = serde_json::from_value::<GQLRequest>(payload) {
                             let variables = request
                                 .variables
-                                .map(|value| Variables::parse_from_json(value).ok())
-                                .flatten()
+                                .and_then(|value| Variables::parse_from_json(value).ok())
                                 .unwrap_or_default();
                             match schema
                                 .create_subscription_stream(
@@ -142,8 +141,10 @@ impl SubscriptionTransport for WebSocketTransport {
                         payload: Some(
                             serde_json::to_value(GQLResponse(Ok(QueryResponse {
                                 data: value,
+                                errors: Vec::new(),
                                 extensions: None,
                                 cache_control: Default::default(),
+                                http_headers: Default::default(),
                             })))
                             .unwrap(),
                         ),
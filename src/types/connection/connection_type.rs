@@ -116,6 +116,8 @@ impl<T: OutputValueType + Send + Sync, E: ObjectType + Sync + Send> Type for Con
                         cache_control: Default::default(),
                         external: false,
                         requires: None,
+                        compute_complexity: None,
+                        visible: None,
                         provides: None
                     },
                 );
@@ -131,6 +133,8 @@ impl<T: OutputValueType + Send + Sync, E: ObjectType + Sync + Send> Type for Con
                         cache_control: Default::default(),
                         external: false,
                         requires: None,
+                        compute_complexity: None,
+                        visible: None,
                         provides: None
                     },
                 );
@@ -146,6 +150,8 @@ impl<T: OutputValueType + Send + Sync, E: ObjectType + Sync + Send> Type for Con
                         cache_control: Default::default(),
                         external: false,
                         requires: None,
+                        compute_complexity: None,
+                        visible: None,
                         provides: None
                     },
                 );
@@ -160,6 +166,8 @@ impl<T: OutputValueType + Send + Sync, E: ObjectType + Sync + Send> Type for Con
                     cache_control: Default::default(),
                     external: false,
                     requires: None,
+                    compute_complexity: None,
+                    visible: None,
                     provides: None
                 });
 
@@ -167,7 +175,8 @@ impl<T: OutputValueType + Send + Sync, E: ObjectType + Sync + Send> Type for Con
             },
             cache_control: Default::default(),
             extends: false,
-            keys: None
+            keys: None,
+            visible: None,
         })
     }
 }
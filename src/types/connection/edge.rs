@@ -66,6 +66,8 @@ where
                             cache_control: Default::default(),
                             external: false,
                             requires: None,
+                            compute_complexity: None,
+                            visible: None,
                             provides: None,
                         },
                     );
@@ -81,6 +83,8 @@ where
                             cache_control: Default::default(),
                             external: false,
                             requires: None,
+                            compute_complexity: None,
+                            visible: None,
                             provides: None,
                         },
                     );
@@ -91,6 +95,7 @@ where
                 cache_control: Default::default(),
                 extends: false,
                 keys: None,
+                visible: None,
             }
         })
     }
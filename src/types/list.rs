@@ -1,6 +1,16 @@
-use crate::{registry, ContextSelectionSet, InputValueType, OutputValueType, Result, Type, Value};
+use crate::context::{Incremental, InitialValues};
+use crate::registry::TypeName;
+use crate::resolver::join_initial;
+use crate::{
+    registry, ContextBase, ContextSelectionSet, InputValueType, OutputValueType, QueryPatch,
+    Result, Type, Value,
+};
+use futures::stream::{FuturesOrdered, StreamExt};
+use futures::FutureExt;
 use graphql_parser::Pos;
+use parking_lot::Mutex;
 use std::borrow::Cow;
+use std::sync::atomic::Ordering;
 
 impl<T: Type> Type for Vec<T> {
     fn type_name() -> Cow<'static, str> {
@@ -30,18 +40,23 @@ impl<T: InputValueType> InputValueType for Vec<T> {
             _ => Some(vec![InputValueType::parse(value)?]),
         }
     }
+
+    fn parse_error(value: &Value) -> Option<String> {
+        match value {
+            Value::List(values) => values
+                .iter()
+                .filter(|elem_value| T::parse(elem_value).is_none())
+                .find_map(T::parse_error),
+            _ => T::parse_error(value),
+        }
+    }
 }
 
 #[allow(clippy::ptr_arg)]
 #[async_trait::async_trait]
 impl<T: OutputValueType + Send + Sync> OutputValueType for Vec<T> {
     async fn resolve(&self, ctx: &ContextSelectionSet<'_>, pos: Pos) -> Result<serde_json::Value> {
-        let mut futures = Vec::with_capacity(self.len());
-        for (idx, item) in self.iter().enumerate() {
-            let ctx_idx = ctx.with_index(idx);
-            futures.push(async move { OutputValueType::resolve(item, &ctx_idx, pos).await });
-        }
-        Ok(futures::future::try_join_all(futures).await?.into())
+        resolve_list(ctx, self, pos).await
     }
 }
 
@@ -58,13 +73,119 @@ impl<T: Type> Type for &[T] {
 #[async_trait::async_trait]
 impl<T: OutputValueType + Send + Sync> OutputValueType for &[T] {
     async fn resolve(&self, ctx: &ContextSelectionSet<'_>, pos: Pos) -> Result<serde_json::Value> {
-        let mut futures = Vec::with_capacity(self.len());
-        for (idx, item) in (*self).iter().enumerate() {
-            let ctx_idx = ctx.with_index(idx);
-            futures.push(async move { OutputValueType::resolve(item, &ctx_idx, pos).await });
+        resolve_list(ctx, self, pos).await
+    }
+}
+
+async fn resolve_list<T: OutputValueType + Send + Sync>(
+    ctx: &ContextSelectionSet<'_>,
+    items: &[T],
+    pos: Pos,
+) -> Result<serde_json::Value> {
+    let non_null = TypeName::create(&T::qualified_type_name()).is_non_null();
+    let incremental = match ctx.incremental {
+        Some(incremental) => incremental,
+        None => {
+            let mut futures = Vec::with_capacity(items.len());
+            for (idx, item) in items.iter().enumerate() {
+                let ctx_idx = ctx.with_index(idx);
+                futures.push(async move {
+                    match OutputValueType::resolve(item, &ctx_idx, pos).await {
+                        Ok(value) => Ok(value),
+                        Err(err) => ctx_idx.resolve_error(err, non_null),
+                    }
+                });
+            }
+            return Ok(futures::future::try_join_all(futures).await?.into());
         }
-        Ok(futures::future::try_join_all(futures).await?.into())
+    };
+
+    // With `@stream`, only the first `initialCount` items are in the initial response,
+    // the rest of them are sent one by one once it is sent.
+    let resolve_count = match incremental.stream {
+        Some(stream) if stream.initial_count < items.len() => {
+            incremental.has_next.store(true, Ordering::Relaxed);
+            stream.initial_count
+        }
+        _ => items.len(),
+    };
+
+    let initial = InitialValues::default();
+    let mut futures = Vec::with_capacity(resolve_count + 1);
+    for (idx, item) in items[..resolve_count].iter().enumerate() {
+        let initial = &initial;
+        futures.push(
+            async move {
+                let report = |value| initial.insert(idx, Some(value));
+                let ctx_idx = ctx.with_index(idx);
+                let ctx_idx = ContextBase {
+                    incremental: Some(Incremental {
+                        initial: &report,
+                        fields: None,
+                        stream: None,
+                        ..incremental
+                    }),
+                    ..ctx_idx
+                };
+                match OutputValueType::resolve(item, &ctx_idx, pos).await {
+                    Ok(value) => Ok(Some(value)),
+                    Err(err) => ctx_idx.resolve_error(err, non_null).map(Some),
+                }
+            }
+            .boxed(),
+        );
     }
+
+    if let Some(stream) = incremental.stream.filter(|_| resolve_count < items.len()) {
+        initial.insert(futures.len(), None);
+        futures.push(
+            async move {
+                incremental.initial_sent.clone().await.ok();
+                let mut stream_futures = items
+                    .iter()
+                    .enumerate()
+                    .skip(resolve_count)
+                    .map(|(idx, item)| async move {
+                        let ctx_idx = ctx.with_index(idx);
+                        let path = ctx_idx.path_node.as_ref().unwrap().to_json();
+                        let errors = Mutex::new(Vec::new());
+                        let ctx_item = ContextBase {
+                            errors: Some(&errors),
+                            ..ctx_idx
+                        };
+                        let items = match OutputValueType::resolve(item, &ctx_item, pos).await {
+                            Ok(value) => serde_json::Value::Array(vec![value]),
+                            Err(err) => match ctx_item.resolve_error(err, non_null) {
+                                Ok(value) => serde_json::Value::Array(vec![value]),
+                                Err(err) => {
+                                    errors.lock().push(err);
+                                    serde_json::Value::Null
+                                }
+                            },
+                        };
+                        let errors = std::mem::take(&mut *errors.lock());
+                        QueryPatch::Stream {
+                            label: stream.label.map(ToString::to_string),
+                            path,
+                            items,
+                            errors,
+                        }
+                    })
+                    .collect::<FuturesOrdered<_>>();
+                while let Some(patch) = stream_futures.next().await {
+                    ctx.send_patch(patch);
+                }
+                Ok(None)
+            }
+            .boxed(),
+        );
+    }
+
+    let res = join_initial(futures, &initial, |values| {
+        (incremental.initial)(values.into_iter().flatten().collect::<Vec<_>>().into())
+    })
+    .await?;
+    Ok(res.into_iter().flatten().collect::<Vec<_>>().into())
 }
 
 #[cfg(test)]
@@ -10,6 +10,7 @@ mod upload;
 pub use connection::{Connection, Cursor, DataSource, EmptyEdgeFields, PageInfo, QueryOperation};
 pub use empty_mutation::EmptyMutation;
 pub use empty_subscription::EmptySubscription;
+pub(crate) use query_root::create_introspection_type_info;
 pub use query_root::QueryRoot;
 pub use r#enum::{EnumItem, EnumType};
 pub use upload::Upload;
@@ -25,6 +25,13 @@ impl<T: InputValueType> InputValueType for Option<T> {
             _ => Some(Some(T::parse(value)?)),
         }
     }
+
+    fn parse_error(value: &Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            _ => T::parse_error(value),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -25,61 +25,77 @@ impl<T: Type> Type for QueryRoot<T> {
         T::type_name()
     }
 
+    fn introspection_type_name(&self) -> Cow<'static, str> {
+        self.inner.introspection_type_name()
+    }
+
     fn create_type_info(registry: &mut registry::Registry) -> String {
-        let schema_type = __Schema::create_type_info(registry);
         let root = T::create_type_info(registry);
-        if let Some(registry::Type::Object { fields, .. }) =
-            registry.types.get_mut(T::type_name().as_ref())
-        {
-            fields.insert(
-                "__schema".to_string(),
-                registry::Field {
-                    name: "__schema".to_string(),
-                    description: Some("Access the current type schema of this server."),
-                    args: Default::default(),
-                    ty: schema_type,
-                    deprecation: None,
-                    cache_control: Default::default(),
-                    external: false,
-                    requires: None,
-                    provides: None,
-                },
-            );
+        create_introspection_type_info(registry, &T::type_name());
+        root
+    }
+}
 
-            fields.insert(
-                "__type".to_string(),
-                registry::Field {
-                    name: "__type".to_string(),
-                    description: Some("Request the type information of a single type."),
-                    args: {
-                        let mut args = HashMap::new();
-                        args.insert(
-                            "name",
-                            registry::InputValue {
-                                name: "name",
-                                description: None,
-                                ty: "String!".to_string(),
-                                default_value: None,
-                                validator: None,
-                            },
-                        );
-                        args
-                    },
-                    ty: "__Type".to_string(),
-                    deprecation: None,
-                    cache_control: Default::default(),
-                    external: false,
-                    requires: None,
-                    provides: None,
+/// Adds the `__schema` and `__type` fields to the query type.
+pub(crate) fn create_introspection_type_info(registry: &mut registry::Registry, query_type: &str) {
+    let schema_type = __Schema::create_type_info(registry);
+    if let Some(registry::Type::Object { fields, .. }) = registry.types.get_mut(query_type) {
+        fields.insert(
+            "__schema".to_string(),
+            registry::Field {
+                name: "__schema".to_string(),
+                description: Some("Access the current type schema of this server."),
+                args: Default::default(),
+                ty: schema_type,
+                deprecation: None,
+                cache_control: Default::default(),
+                external: false,
+                requires: None,
+                compute_complexity: None,
+                visible: None,
+                provides: None,
+            },
+        );
+
+        fields.insert(
+            "__type".to_string(),
+            registry::Field {
+                name: "__type".to_string(),
+                description: Some("Request the type information of a single type."),
+                args: {
+                    let mut args = HashMap::new();
+                    args.insert(
+                        "name",
+                        registry::InputValue {
+                            name: "name",
+                            description: None,
+                            ty: "String!".to_string(),
+                            default_value: None,
+                            parsed_default_value: None,
+                            validator: None,
+                        },
+                    );
+                    args
                 },
-            );
-        }
-        root
+                ty: "__Type".to_string(),
+                deprecation: None,
+                cache_control: Default::default(),
+                external: false,
+                requires: None,
+                compute_complexity: None,
+                visible: None,
+                provides: None,
+            },
+        );
     }
 }
 
 #[async_trait::async_trait]
 impl<T: ObjectType + Send + Sync> ObjectType for QueryRoot<T> {
+    fn registry_type_name(&self) -> Cow<'static, str> {
+        self.inner.registry_type_name()
+    }
+
     async fn resolve_field(&self, ctx: &Context<'_>) -> Result<serde_json::Value> {
         if ctx.name.as_str() == "__schema" {
             if self.disable_introspection {
@@ -88,7 +104,7 @@ impl<T: ObjectType + Send + Sync> ObjectType for QueryRoot<T> {
                     path: Some(ctx.path_node.as_ref().unwrap().to_json()),
                     err: QueryError::FieldNotFound {
                         field_name: ctx.name.clone(),
-                        object: Self::type_name().to_string(),
+                        object: self.registry_type_name().to_string(),
                     },
                 });
             }
@@ -109,6 +125,7 @@ impl<T: ObjectType + Send + Sync> ObjectType for QueryRoot<T> {
                 &ctx.registry
                     .types
                     .get(&type_name)
+                    .filter(|_| ctx.is_visible_type(&type_name))
                     .map(|ty| __Type::new_simple(ctx.registry, ty)),
                 &ctx_obj,
                 ctx.position,
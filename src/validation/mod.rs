@@ -8,13 +8,13 @@ mod visitors;
 mod test_harness;
 
 use crate::registry::Registry;
-use crate::{CacheControl, Error, Result};
-use graphql_parser::query::Document;
-use visitor::{visit, VisitorContext, VisitorNil};
+use crate::{CacheControl, Data, Error, Result, Variables};
+use graphql_parser::query::{Document, SelectionSet, VariableDefinition};
+use visitor::{visit, visit_selection_set, VisitorContext, VisitorNil};
 
+#[derive(Clone, Copy)]
 pub struct CheckResult {
     pub cache_control: CacheControl,
-    pub complexity: usize,
     pub depth: usize,
 }
 
@@ -35,7 +35,6 @@ pub fn check_rules(
 ) -> Result<CheckResult> {
     let mut ctx = VisitorContext::new(registry, doc);
     let mut cache_control = CacheControl::default();
-    let mut complexity = 0;
     let mut depth = 0;
 
     match mode {
@@ -68,9 +67,6 @@ pub fn check_rules(
                 .with(visitors::CacheControlCalculate {
                     cache_control: &mut cache_control,
                 })
-                .with(visitors::ComplexityCalculate {
-                    complexity: &mut complexity,
-                })
                 .with(visitors::DepthCalculate::new(&mut depth));
             visit(&mut visitor, &mut ctx, doc);
         }
@@ -81,9 +77,6 @@ pub fn check_rules(
                 .with(visitors::CacheControlCalculate {
                     cache_control: &mut cache_control,
                 })
-                .with(visitors::ComplexityCalculate {
-                    complexity: &mut complexity,
-                })
                 .with(visitors::DepthCalculate::new(&mut depth));
             visit(&mut visitor, &mut ctx, doc);
         }
@@ -94,7 +87,45 @@ pub fn check_rules(
     }
     Ok(CheckResult {
         cache_control,
-        complexity,
         depth: depth as usize,
     })
 }
+
+/// Rejects the fields and types that are not visible to the request data, as if they did not exist.
+///
+/// It is checked for each request, because the result of `check_rules` is cached by the query source.
+pub fn check_visibility(registry: &Registry, doc: &Document, data: &Data) -> Result<()> {
+    let mut ctx = VisitorContext::new(registry, doc).with_visibility(data);
+    let mut visitor = VisitorNil
+        .with(rules::FieldsOnCorrectType)
+        .with(rules::KnownTypeNames);
+    visit(&mut visitor, &mut ctx, doc);
+
+    if !ctx.errors.is_empty() {
+        return Err(Error::Rule { errors: ctx.errors });
+    }
+    Ok(())
+}
+
+/// Calculates the complexity of an operation, which depends on the values of its variables.
+pub fn calculate_complexity(
+    registry: &Registry,
+    doc: &Document,
+    root_type: &str,
+    selection_set: &SelectionSet,
+    variable_definitions: &[VariableDefinition],
+    variables: &Variables,
+) -> Result<usize> {
+    let mut ctx = VisitorContext::new(registry, doc);
+    let mut complexity = 0;
+    let mut visitor =
+        visitors::ComplexityCalculate::new(&mut complexity, variables, variable_definitions);
+    ctx.with_type(registry.types.get(root_type), |ctx| {
+        visit_selection_set(&mut visitor, ctx, selection_set)
+    });
+
+    if !ctx.errors.is_empty() {
+        return Err(Error::Rule { errors: ctx.errors });
+    }
+    Ok(complexity)
+}
@@ -20,6 +20,7 @@ impl<'a> Visitor<'a> for FieldsOnCorrectType {
             if parent_type
                 .fields()
                 .and_then(|fields| fields.get(field.name.as_str()))
+                .filter(|field| ctx.is_visible_field(parent_type, field))
                 .is_none()
             {
                 ctx.report_error(
@@ -33,9 +34,10 @@ impl<'a> Visitor<'a> for FieldsOnCorrectType {
                             parent_type
                                 .fields()
                                 .iter()
-                                .map(|fields| fields.keys())
+                                .map(|fields| fields.values())
                                 .flatten()
-                                .map(|s| s.as_str()),
+                                .filter(|field| ctx.is_visible_field(parent_type, field))
+                                .map(|field| field.name.as_str()),
                             &field.name
                         )
                         .unwrap_or_default()
@@ -42,7 +42,7 @@ impl<'a> Visitor<'a> for KnownTypeNames {
 }
 
 fn validate_type(ctx: &mut VisitorContext<'_>, type_name: &str, pos: Pos) {
-    if ctx.registry.types.get(type_name).is_none() {
+    if !ctx.is_visible_type(type_name) {
         ctx.report_error(vec![pos], format!(r#"Unknown type "{}""#, type_name));
     }
 }
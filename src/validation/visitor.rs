@@ -1,6 +1,7 @@
 use crate::error::RuleError;
 use crate::registry;
 use crate::registry::{Type, TypeName};
+use crate::Data;
 use graphql_parser::query::{
     Definition, Directive, Document, Field, FragmentDefinition, FragmentSpread, InlineFragment,
     OperationDefinition, Selection, SelectionSet, TypeCondition, Value, VariableDefinition,
@@ -14,6 +15,7 @@ pub struct VisitorContext<'a> {
     type_stack: Vec<Option<&'a registry::Type>>,
     input_type: Vec<Option<TypeName<'a>>>,
     fragments: HashMap<&'a str, &'a FragmentDefinition>,
+    data: Option<&'a Data>,
 }
 
 impl<'a> VisitorContext<'a> {
@@ -31,6 +33,29 @@ impl<'a> VisitorContext<'a> {
                     _ => None,
                 })
                 .collect(),
+            data: None,
+        }
+    }
+
+    /// Hide the fields and types that are not visible to the request data.
+    pub fn with_visibility(self, data: &'a Data) -> Self {
+        Self {
+            data: Some(data),
+            ..self
+        }
+    }
+
+    pub fn is_visible_type(&self, name: &str) -> bool {
+        match self.data {
+            Some(data) => self.registry.is_visible_type(name, data),
+            None => self.registry.types.contains_key(name),
+        }
+    }
+
+    pub fn is_visible_field(&self, parent_type: &registry::Type, field: &registry::Field) -> bool {
+        match self.data {
+            Some(data) => self.registry.is_visible_field(parent_type, field, data),
+            None => true,
         }
     }
 
@@ -486,7 +511,7 @@ fn visit_operation_definition<'a, V: Visitor<'a>>(
     v.exit_operation_definition(ctx, operation);
 }
 
-fn visit_selection_set<'a, V: Visitor<'a>>(
+pub fn visit_selection_set<'a, V: Visitor<'a>>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     selection_set: &'a SelectionSet,
@@ -524,13 +549,14 @@ fn visit_selection<'a, V: Visitor<'a>>(
         Selection::FragmentSpread(fragment_spread) => {
             visit_fragment_spread(v, ctx, fragment_spread)
         }
-        Selection::InlineFragment(inline_fragment) => {
-            if let Some(TypeCondition::On(name)) = &inline_fragment.type_condition {
+        Selection::InlineFragment(inline_fragment) => match &inline_fragment.type_condition {
+            Some(TypeCondition::On(name)) => {
                 ctx.with_type(ctx.registry.types.get(name), |ctx| {
                     visit_inline_fragment(v, ctx, inline_fragment)
                 });
             }
-        }
+            None => visit_inline_fragment(v, ctx, inline_fragment),
+        },
     }
     v.exit_selection(ctx, selection);
 }
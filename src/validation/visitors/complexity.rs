@@ -1,12 +1,142 @@
-use crate::validation::visitor::{Visitor, VisitorContext};
-use graphql_parser::query::Field;
+use crate::registry::{self, ComplexityType};
+use crate::validation::visitor::{visit_selection_set, Visitor, VisitorContext};
+use crate::Variables;
+use graphql_parser::query::{Field, FragmentSpread, TypeCondition, Value, VariableDefinition};
 
+/// Calculates the complexity of the operation, the fragment spreads are expanded in place.
+///
+/// A field costs 1 plus the complexity of its selection set, unless it has a custom complexity.
 pub struct ComplexityCalculate<'a> {
-    pub complexity: &'a mut usize,
+    complexity: &'a mut usize,
+    variables: &'a Variables,
+    variable_definitions: &'a [VariableDefinition],
+    stack: Vec<usize>,
+}
+
+impl<'a> ComplexityCalculate<'a> {
+    pub fn new(
+        complexity: &'a mut usize,
+        variables: &'a Variables,
+        variable_definitions: &'a [VariableDefinition],
+    ) -> Self {
+        *complexity = 0;
+        Self {
+            complexity,
+            variables,
+            variable_definitions,
+            stack: Vec::new(),
+        }
+    }
+
+    fn field_complexity(
+        &self,
+        ctx: &mut VisitorContext<'_>,
+        field: &Field,
+        compute_complexity: Option<ComplexityType>,
+        child_complexity: usize,
+    ) -> usize {
+        match compute_complexity {
+            None => child_complexity.saturating_add(1),
+            Some(ComplexityType::Const(n)) => child_complexity.saturating_add(n),
+            Some(ComplexityType::Fn(f)) => {
+                let args = field
+                    .arguments
+                    .iter()
+                    .map(|(name, value)| (name.clone(), self.resolve_value(value)))
+                    .collect::<Vec<_>>();
+                match f(&args, child_complexity) {
+                    Ok(complexity) => complexity,
+                    Err(err) => {
+                        ctx.report_error(
+                            vec![field.position],
+                            format!(
+                                "Failed to compute the complexity of field \"{}\": {}",
+                                field.name, err.0
+                            ),
+                        );
+                        0
+                    }
+                }
+            }
+        }
+    }
+
+    fn resolve_value(&self, value: &Value) -> Value {
+        match value {
+            Value::Variable(name) => self
+                .variables
+                .get(name)
+                .cloned()
+                .or_else(|| {
+                    self.variable_definitions
+                        .iter()
+                        .find(|def| &def.name == name)
+                        .and_then(|def| def.default_value.clone())
+                })
+                .unwrap_or(Value::Null),
+            Value::List(ls) => {
+                Value::List(ls.iter().map(|value| self.resolve_value(value)).collect())
+            }
+            Value::Object(obj) => Value::Object(
+                obj.iter()
+                    .map(|(name, value)| (name.clone(), self.resolve_value(value)))
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
 }
 
 impl<'ctx, 'a> Visitor<'ctx> for ComplexityCalculate<'a> {
-    fn enter_field(&mut self, _ctx: &mut VisitorContext<'_>, _field: &Field) {
-        *self.complexity += 1;
+    fn enter_field(&mut self, _ctx: &mut VisitorContext<'ctx>, _field: &'ctx Field) {
+        self.stack.push(0);
+    }
+
+    fn exit_field(&mut self, ctx: &mut VisitorContext<'ctx>, field: &'ctx Field) {
+        let child_complexity = self.stack.pop().unwrap_or_default();
+        let parent_type = ctx.parent_type();
+        let compute_complexity = parent_type
+            .and_then(|ty| ty.field_by_name(&field.name))
+            .and_then(|schema_field| schema_field.compute_complexity);
+        let complexity = match (parent_type, compute_complexity) {
+            // The field of an interface costs as much as the most complex field of the implementing objects.
+            (Some(registry::Type::Interface { possible_types, .. }), None) => {
+                let registry = ctx.registry;
+                possible_types
+                    .iter()
+                    .filter_map(|name| registry.types.get(name))
+                    .filter_map(|ty| ty.field_by_name(&field.name))
+                    .map(|schema_field| {
+                        self.field_complexity(
+                            ctx,
+                            field,
+                            schema_field.compute_complexity,
+                            child_complexity,
+                        )
+                    })
+                    .max()
+                    .unwrap_or_else(|| child_complexity.saturating_add(1))
+            }
+            (_, compute_complexity) => {
+                self.field_complexity(ctx, field, compute_complexity, child_complexity)
+            }
+        };
+        match self.stack.last_mut() {
+            Some(parent) => *parent = parent.saturating_add(complexity),
+            None => *self.complexity = self.complexity.saturating_add(complexity),
+        }
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        ctx: &mut VisitorContext<'ctx>,
+        fragment_spread: &'ctx FragmentSpread,
+    ) {
+        if let Some(fragment) = ctx.fragment(&fragment_spread.fragment_name) {
+            let TypeCondition::On(name) = &fragment.type_condition;
+            ctx.with_type(ctx.registry.types.get(name), |ctx| {
+                visit_selection_set(self, ctx, &fragment.selection_set)
+            });
+        }
     }
 }
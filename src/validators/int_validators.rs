@@ -1,3 +1,4 @@
+use crate::scalars::int_value;
 use crate::validators::InputValueValidator;
 use graphql_parser::query::Value;
 
@@ -12,13 +13,11 @@ pub struct IntRange {
 
 impl InputValueValidator for IntRange {
     fn is_valid(&self, value: &Value) -> Option<String> {
-        if let Value::Int(n) = value {
-            if n.as_i64().unwrap() < self.min || n.as_i64().unwrap() > self.max {
+        if let Some(n) = int_value(value) {
+            if n < i128::from(self.min) || n > i128::from(self.max) {
                 Some(format!(
                     "the value is {}, but the range must be between {} and {}",
-                    n.as_i64().unwrap(),
-                    self.min,
-                    self.max
+                    n, self.min, self.max
                 ))
             } else {
                 None
@@ -37,12 +36,11 @@ pub struct IntLessThan {
 
 impl InputValueValidator for IntLessThan {
     fn is_valid(&self, value: &Value) -> Option<String> {
-        if let Value::Int(n) = value {
-            if n.as_i64().unwrap() >= self.value {
+        if let Some(n) = int_value(value) {
+            if n >= i128::from(self.value) {
                 Some(format!(
                     "the value is {}, must be less than {}",
-                    n.as_i64().unwrap(),
-                    self.value
+                    n, self.value
                 ))
             } else {
                 None
@@ -61,12 +59,11 @@ pub struct IntGreaterThan {
 
 impl InputValueValidator for IntGreaterThan {
     fn is_valid(&self, value: &Value) -> Option<String> {
-        if let Value::Int(n) = value {
-            if n.as_i64().unwrap() <= self.value {
+        if let Some(n) = int_value(value) {
+            if n <= i128::from(self.value) {
                 Some(format!(
                     "the value is {}, must be greater than {}",
-                    n.as_i64().unwrap(),
-                    self.value
+                    n, self.value
                 ))
             } else {
                 None
@@ -82,12 +79,9 @@ pub struct IntNonZero {}
 
 impl InputValueValidator for IntNonZero {
     fn is_valid(&self, value: &Value) -> Option<String> {
-        if let Value::Int(n) = value {
-            if n.as_i64().unwrap() == 0 {
-                Some(format!(
-                    "the value is {}, but must be nonzero",
-                    n.as_i64().unwrap(),
-                ))
+        if let Some(n) = int_value(value) {
+            if n == 0 {
+                Some(format!("the value is {}, but must be nonzero", n))
             } else {
                 None
             }
@@ -105,13 +99,9 @@ pub struct IntEqual {
 
 impl InputValueValidator for IntEqual {
     fn is_valid(&self, value: &Value) -> Option<String> {
-        if let Value::Int(n) = value {
-            if n.as_i64().unwrap() != self.value {
-                Some(format!(
-                    "the value is {}, must be equal {}",
-                    n.as_i64().unwrap(),
-                    self.value
-                ))
+        if let Some(n) = int_value(value) {
+            if n != i128::from(self.value) {
+                Some(format!("the value is {}, must be equal {}", n, self.value))
             } else {
                 None
             }
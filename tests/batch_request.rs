@@ -0,0 +1,70 @@
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_batch_request() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self, n: i32) -> i32 {
+            n
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let body = r#"[
+        {"query": "{ value(n: 1) }"},
+        {"query": "query($n: Int!) { value(n: $n) }", "variables": {"n": 2}}
+    ]"#;
+
+    let batch = (None::<&str>, body.as_bytes())
+        .into_batch_request()
+        .await
+        .unwrap();
+    let resp = batch.execute(&schema).await;
+    assert_eq!(
+        serde_json::to_value(http::GQLBatchResponse(resp)).unwrap(),
+        serde_json::json!([
+            {"data": {"value": 1}},
+            {"data": {"value": 2}},
+        ])
+    );
+
+    let batch = (None::<&str>, body.as_bytes())
+        .into_batch_request()
+        .await
+        .unwrap();
+    let resp = batch.execute_concurrent(&schema).await;
+    assert_eq!(
+        serde_json::to_value(http::GQLBatchResponse(resp)).unwrap(),
+        serde_json::json!([
+            {"data": {"value": 1}},
+            {"data": {"value": 2}},
+        ])
+    );
+
+    let batch = (None::<&str>, r#"{"query": "{ value(n: 3) }"}"#.as_bytes())
+        .into_batch_request()
+        .await
+        .unwrap();
+    let resp = batch.execute(&schema).await;
+    assert_eq!(
+        serde_json::to_value(http::GQLBatchResponse(resp)).unwrap(),
+        serde_json::json!({"data": {"value": 3}})
+    );
+
+    assert!(matches!(
+        (None::<&str>, body.as_bytes())
+            .into_batch_request_opts(&IntoQueryBuilderOpts {
+                max_batch_size: Some(1),
+                ..Default::default()
+            })
+            .await,
+        Err(ParseRequestError::BatchTooLarge)
+    ));
+
+    assert!(matches!(
+        (None::<&str>, body.as_bytes()).into_query_builder().await,
+        Err(ParseRequestError::UnsupportedBatch)
+    ));
+}
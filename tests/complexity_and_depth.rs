@@ -134,3 +134,223 @@ pub async fn test_complexity_and_depth() {
         })
     );
 }
+
+#[async_std::test]
+pub async fn test_field_complexity() {
+    struct Query;
+
+    #[SimpleObject]
+    struct User {
+        #[field]
+        name: String,
+
+        #[field(complexity = 5)]
+        avatar: String,
+    }
+
+    #[Object]
+    impl Query {
+        #[field(complexity = "first as usize * child_complexity")]
+        async fn users(&self, #[arg(default = "10")] first: i32) -> Vec<User> {
+            (0..first)
+                .map(|idx| User {
+                    name: idx.to_string(),
+                    avatar: String::new(),
+                })
+                .collect()
+        }
+
+        #[field(complexity = 10)]
+        async fn expensive(&self) -> i32 {
+            1
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .limit_complexity(20)
+        .finish();
+
+    let too_complex = Error::Query {
+        pos: Pos::default(),
+        path: None,
+        err: QueryError::TooComplex,
+    };
+
+    // 2 * (1 + 5) + 10 = 22
+    assert_eq!(
+        schema
+            .execute("{ users(first: 2) { name avatar } expensive }")
+            .await
+            .unwrap_err(),
+        too_complex
+    );
+
+    // 2 * 1 + 10 = 12
+    assert_eq!(
+        schema
+            .execute("{ users(first: 2) { name } expensive }")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({
+            "users": [{ "name": "0" }, { "name": "1" }],
+            "expensive": 1,
+        })
+    );
+
+    // The default value of the argument is 10.
+    assert_eq!(
+        schema
+            .execute("{ users { name avatar } }")
+            .await
+            .unwrap_err(),
+        too_complex
+    );
+
+    // The arguments are resolved with the variables, the fragments are expanded in place.
+    let query = r#"
+        query($first: Int!) {
+            users(first: $first) { ...UserFields }
+        }
+
+        fragment UserFields on User {
+            name
+        }"#;
+    assert_eq!(
+        QueryBuilder::new(query)
+            .variables(Variables::parse_from_json(serde_json::json!({ "first": 21 })).unwrap())
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        too_complex
+    );
+    assert!(QueryBuilder::new(query)
+        .variables(Variables::parse_from_json(serde_json::json!({ "first": 20 })).unwrap())
+        .execute(&schema)
+        .await
+        .is_ok());
+
+    // An overflow of the complexity expression is an error instead of a panic.
+    match schema
+        .execute("{ users(first: -1) { name avatar } }")
+        .await
+        .unwrap_err()
+    {
+        Error::Rule { errors } => assert_eq!(
+            errors[0].message,
+            r#"Failed to compute the complexity of field "users": Arithmetic overflow."#
+        ),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[async_std::test]
+pub async fn test_interface_and_subscription_complexity() {
+    struct Cheap {
+        value: i32,
+    }
+
+    #[Object]
+    impl Cheap {
+        async fn value(&self) -> i32 {
+            self.value
+        }
+
+        async fn double(&self) -> i32 {
+            self.value * 2
+        }
+    }
+
+    struct Expensive;
+
+    #[Object]
+    impl Expensive {
+        #[field(complexity = 10)]
+        async fn value(&self) -> i32 {
+            0
+        }
+
+        async fn double(&self) -> i32 {
+            0
+        }
+    }
+
+    #[Interface(
+        field(name = "value", type = "i32"),
+        field(
+            name = "double",
+            type = "i32",
+            complexity = "2 * child_complexity + 20"
+        )
+    )]
+    struct Node(Cheap, Expensive);
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn node(&self) -> Node {
+            Cheap { value: 1 }.into()
+        }
+    }
+
+    struct Subscription;
+
+    #[Subscription]
+    impl Subscription {
+        #[field(complexity = "count as usize * child_complexity")]
+        async fn nodes(&self, count: i32) -> impl futures::Stream<Item = Cheap> {
+            futures::stream::iter((0..count).map(|value| Cheap { value }))
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, Subscription)
+        .limit_complexity(10)
+        .finish();
+
+    let too_complex = Error::Query {
+        pos: Pos::default(),
+        path: None,
+        err: QueryError::TooComplex,
+    };
+
+    // The field of the interface costs as much as the field of `Expensive`: 1 + 10 = 11
+    assert_eq!(
+        schema.execute("{ node { value } }").await.unwrap_err(),
+        too_complex
+    );
+    assert!(schema
+        .execute("{ node { ... on Cheap { value } } }")
+        .await
+        .is_ok());
+
+    // The complexity of the field of the interface: 1 + 2 * 0 + 20 = 21
+    assert_eq!(
+        schema.execute("{ node { double } }").await.unwrap_err(),
+        too_complex
+    );
+
+    // 11 * 1 = 11
+    assert_eq!(
+        schema
+            .create_subscription_stream(
+                "subscription { nodes(count: 11) { value } }",
+                None,
+                Default::default(),
+                None
+            )
+            .await
+            .err()
+            .unwrap(),
+        too_complex
+    );
+    assert!(schema
+        .create_subscription_stream(
+            "subscription { nodes(count: 10) { value } }",
+            None,
+            Default::default(),
+            None
+        )
+        .await
+        .is_ok());
+}
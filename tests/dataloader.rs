@@ -0,0 +1,120 @@
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::*;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[async_std::test]
+pub async fn test_dataloader() {
+    struct NameLoader {
+        batches: Arc<Mutex<Vec<Vec<i32>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Loader for NameLoader {
+        type Key = i32;
+        type Value = String;
+
+        async fn load(&self, keys: &[i32]) -> HashMap<i32, String> {
+            let mut keys = keys.to_vec();
+            keys.sort();
+            self.batches.lock().push(keys.clone());
+            keys.into_iter()
+                .filter(|id| *id != 0)
+                .map(|id| (id, format!("user{}", id)))
+                .collect()
+        }
+    }
+
+    struct User(i32);
+
+    #[Object]
+    impl User {
+        async fn name(&self, ctx: &Context<'_>) -> Option<String> {
+            ctx.data::<DataLoader<NameLoader>>().load(self.0).await
+        }
+
+        async fn me(&self) -> User {
+            User(self.0)
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn user(&self, id: i32) -> User {
+            User(id)
+        }
+
+        async fn users(&self, ids: Vec<i32>) -> Vec<User> {
+            ids.into_iter().map(User).collect()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let loader = DataLoader::new(NameLoader {
+        batches: batches.clone(),
+    });
+    let query = r#"{
+        a: user(id: 1) { name me { name } }
+        b: user(id: 2) { name }
+        users(ids: [1, 3, 0]) { name }
+    }"#;
+    let resp = QueryBuilder::new(query)
+        .data(loader)
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.data,
+        serde_json::json!({
+            "a": {"name": "user1", "me": {"name": "user1"}},
+            "b": {"name": "user2"},
+            "users": [{"name": "user1"}, {"name": "user3"}, {"name": null}],
+        })
+    );
+    assert_eq!(*batches.lock(), vec![vec![0, 1, 2, 3]]);
+}
+
+#[async_std::test]
+pub async fn test_dataloader_missing_and_in_flight_keys() {
+    struct SlowLoader {
+        batches: Arc<Mutex<Vec<Vec<i32>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Loader for SlowLoader {
+        type Key = i32;
+        type Value = i32;
+
+        async fn load(&self, keys: &[i32]) -> HashMap<i32, i32> {
+            self.batches.lock().push(keys.to_vec());
+            async_std::task::sleep(Duration::from_millis(100)).await;
+            keys.iter()
+                .filter(|id| **id != 0)
+                .map(|id| (*id, *id * 10))
+                .collect()
+        }
+    }
+
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let loader = DataLoader::new(SlowLoader {
+        batches: batches.clone(),
+    });
+
+    // The second call is made while the batch loading the key is in flight.
+    let (a, b) = futures::join!(loader.load(1), async {
+        async_std::task::sleep(Duration::from_millis(20)).await;
+        loader.load(1).await
+    });
+    assert_eq!((a, b), (Some(10), Some(10)));
+    assert_eq!(*batches.lock(), vec![vec![1]]);
+
+    // A key without a value is only loaded once.
+    assert_eq!(loader.load(0).await, None);
+    assert_eq!(loader.load(0).await, None);
+    assert_eq!(*batches.lock(), vec![vec![1], vec![0]]);
+}
@@ -0,0 +1,357 @@
+use async_graphql::http::{self, GQLIncrementalResponse};
+use async_graphql::*;
+use futures::channel::oneshot;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct MyObj;
+
+#[Object]
+impl MyObj {
+    async fn a(&self) -> i32 {
+        1
+    }
+
+    async fn b(&self) -> FieldResult<Option<i32>> {
+        Err("error".into())
+    }
+
+    async fn c(&self) -> Vec<i32> {
+        vec![1, 2, 3]
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    async fn obj(&self) -> MyObj {
+        MyObj
+    }
+
+    async fn items(&self) -> Vec<MyObj> {
+        vec![MyObj, MyObj, MyObj]
+    }
+}
+
+async fn execute_stream(
+    schema: &Schema<Query, EmptyMutation, EmptySubscription>,
+    query: &str,
+) -> Vec<serde_json::Value> {
+    QueryBuilder::new(query)
+        .execute_stream(schema)
+        .map(|resp| serde_json::to_value(GQLIncrementalResponse(resp)).unwrap())
+        .collect()
+        .await
+}
+
+#[async_std::test]
+pub async fn test_defer() {
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let query = r#"{
+        value
+        obj {
+            a
+            ... @defer(label: "b") { b }
+            ... MyFragment @defer
+        }
+    }
+
+    fragment MyFragment on MyObj {
+        c
+    }"#;
+
+    let mut responses = execute_stream(&schema, query).await;
+    assert_eq!(
+        responses.remove(0),
+        json!({
+            "data": {"value": 10, "obj": {"a": 1}},
+            "hasNext": true,
+        })
+    );
+    assert_eq!(responses.pop(), Some(json!({"hasNext": false})));
+    responses.sort_by_key(|resp| resp["label"].is_null());
+    assert_eq!(
+        responses,
+        vec![
+            json!({
+                "label": "b",
+                "path": ["obj"],
+                "data": {"b": null},
+                "errors": [{
+                    "message": "error",
+                    "locations": [{"line": 5, "column": 38}],
+                    "path": ["obj", "b"],
+                }],
+                "hasNext": true,
+            }),
+            json!({
+                "path": ["obj"],
+                "data": {"c": [1, 2, 3]},
+                "hasNext": true,
+            }),
+        ]
+    );
+
+    // Without incremental delivery, the deferred fragments are resolved inline.
+    assert_eq!(
+        schema.execute(query).await.unwrap().data,
+        json!({"value": 10, "obj": {"a": 1, "b": null, "c": [1, 2, 3]}})
+    );
+}
+
+#[async_std::test]
+pub async fn test_defer_if_false() {
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let responses = execute_stream(&schema, "{ obj { a ... @defer(if: false) { c } } }").await;
+    assert_eq!(
+        responses,
+        vec![json!({
+            "data": {"obj": {"a": 1, "c": [1, 2, 3]}},
+            "hasNext": false,
+        })]
+    );
+}
+
+#[async_std::test]
+pub async fn test_stream() {
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let responses = execute_stream(
+        &schema,
+        r#"{ items @stream(label: "items", initialCount: 1) { a } }"#,
+    )
+    .await;
+    assert_eq!(
+        responses,
+        vec![
+            json!({
+                "data": {"items": [{"a": 1}]},
+                "hasNext": true,
+            }),
+            json!({
+                "label": "items",
+                "path": ["items", 1],
+                "items": [{"a": 1}],
+                "hasNext": true,
+            }),
+            json!({
+                "label": "items",
+                "path": ["items", 2],
+                "items": [{"a": 1}],
+                "hasNext": true,
+            }),
+            json!({"hasNext": false}),
+        ]
+    );
+
+    let responses = execute_stream(&schema, "{ obj { c @stream(initialCount: 2) } }").await;
+    assert_eq!(
+        responses,
+        vec![
+            json!({
+                "data": {"obj": {"c": [1, 2]}},
+                "hasNext": true,
+            }),
+            json!({
+                "path": ["obj", "c", 2],
+                "items": [3],
+                "hasNext": true,
+            }),
+            json!({"hasNext": false}),
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_no_incremental_parts() {
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let responses = execute_stream(&schema, "{ value obj { a } }").await;
+    assert_eq!(
+        responses,
+        vec![json!({
+            "data": {"value": 10, "obj": {"a": 1}},
+            "hasNext": false,
+        })]
+    );
+}
+
+#[async_std::test]
+pub async fn test_incremental_parts_resolved_once() {
+    struct ErrorRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl extensions::Extension for ErrorRecorder {
+        fn field_error(&self, err: &Error) {
+            self.0.lock().push(err.to_string());
+        }
+    }
+
+    struct CountedQuery {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[Object]
+    impl CountedQuery {
+        async fn obj(&self) -> MyObj {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            MyObj
+        }
+
+        async fn items(&self) -> Vec<MyObj> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            vec![MyObj, MyObj]
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let schema = Schema::build(
+        CountedQuery {
+            calls: calls.clone(),
+        },
+        EmptyMutation,
+        EmptySubscription,
+    )
+    .extension({
+        let errors = errors.clone();
+        move || ErrorRecorder(errors.clone())
+    })
+    .finish();
+
+    let responses =
+        QueryBuilder::new("{ obj { a ... @defer { b } } items @stream(initialCount: 1) { a } }")
+            .execute_stream(&schema)
+            .collect::<Vec<_>>()
+            .await;
+    assert_eq!(responses.len(), 4);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        *errors.lock(),
+        vec!["Query error: Failed to resolve field: error".to_string()]
+    );
+
+    // The directives whose `if` argument is false are resolved inline, without incremental delivery.
+    let responses = QueryBuilder::new(
+        "{ obj { a ... @defer(if: false) { b } } items @stream(if: false) { a } }",
+    )
+    .execute_stream(&schema)
+    .map(|resp| serde_json::to_value(GQLIncrementalResponse(resp)).unwrap())
+    .collect::<Vec<_>>()
+    .await;
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["hasNext"], false);
+}
+
+#[async_std::test]
+pub async fn test_initial_response_before_deferred_fragments() {
+    struct Waiting(Mutex<Option<oneshot::Receiver<i32>>>);
+
+    #[Object]
+    impl Waiting {
+        async fn value(&self) -> i32 {
+            let rx = self.0.lock().take().unwrap();
+            rx.await.unwrap()
+        }
+    }
+
+    struct WaitingQuery(Mutex<Option<oneshot::Receiver<i32>>>);
+
+    #[Object]
+    impl WaitingQuery {
+        async fn ready(&self) -> i32 {
+            1
+        }
+
+        async fn waiting(&self) -> Waiting {
+            Waiting(Mutex::new(self.0.lock().take()))
+        }
+    }
+
+    let (tx, rx) = oneshot::channel();
+    let schema = Schema::new(
+        WaitingQuery(Mutex::new(Some(rx))),
+        EmptyMutation,
+        EmptySubscription,
+    );
+    let mut responses = QueryBuilder::new("{ ready waiting { ... @defer { value } } }")
+        .execute_stream(&schema)
+        .map(|resp| serde_json::to_value(GQLIncrementalResponse(resp)).unwrap())
+        .boxed();
+
+    // The deferred field only resolves once the initial response is received.
+    assert_eq!(
+        responses.next().await,
+        Some(json!({
+            "data": {"ready": 1, "waiting": {}},
+            "hasNext": true,
+        }))
+    );
+    tx.send(2).unwrap();
+    assert_eq!(
+        responses.collect::<Vec<_>>().await,
+        vec![
+            json!({
+                "path": ["waiting"],
+                "data": {"value": 2},
+                "hasNext": true,
+            }),
+            json!({"hasNext": false}),
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_defer_http_headers() {
+    struct HeaderQuery;
+
+    #[Object]
+    impl HeaderQuery {
+        async fn value(&self, ctx: &Context<'_>) -> FieldResult<i32> {
+            ctx.insert_http_header("x-value", "10")?;
+            Ok(10)
+        }
+
+        async fn obj(&self) -> MyObj {
+            MyObj
+        }
+    }
+
+    let schema = Schema::new(HeaderQuery, EmptyMutation, EmptySubscription);
+    let (http_headers, responses) = http::incremental_http_headers(
+        QueryBuilder::new("{ value obj { ... @defer { a } } }").execute_stream(&schema),
+    )
+    .await;
+    assert_eq!(
+        http_headers
+            .get("x-value")
+            .and_then(|value| value.to_str().ok()),
+        Some("10")
+    );
+
+    // The initial response is still part of the stream.
+    assert_eq!(
+        responses
+            .map(|resp| serde_json::to_value(GQLIncrementalResponse(resp)).unwrap())
+            .collect::<Vec<_>>()
+            .await,
+        vec![
+            json!({
+                "data": {"value": 10, "obj": {}},
+                "hasNext": true,
+            }),
+            json!({
+                "path": ["obj"],
+                "data": {"a": 1},
+                "hasNext": true,
+            }),
+            json!({"hasNext": false}),
+        ]
+    );
+}
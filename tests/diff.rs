@@ -0,0 +1,213 @@
+use async_graphql::diff::*;
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_diff_registries() {
+    mod v1 {
+        use async_graphql::*;
+
+        #[Enum]
+        pub enum Color {
+            Red,
+            Green,
+        }
+
+        pub struct QueryRoot;
+
+        #[Object]
+        impl QueryRoot {
+            async fn value(&self, a: i32) -> i32 {
+                a
+            }
+
+            async fn color(&self) -> Option<Color> {
+                None
+            }
+
+            async fn name(&self) -> String {
+                String::new()
+            }
+        }
+    }
+
+    mod v2 {
+        use async_graphql::*;
+
+        #[Enum]
+        pub enum Color {
+            Red,
+            Blue,
+        }
+
+        pub struct QueryRoot;
+
+        #[Object]
+        impl QueryRoot {
+            async fn value(&self, a: i32, b: Option<i32>) -> i32 {
+                a + b.unwrap_or_default()
+            }
+
+            async fn color(&self) -> Color {
+                Color::Red
+            }
+
+            async fn count(&self) -> i32 {
+                0
+            }
+        }
+    }
+
+    let old = Schema::new(v1::QueryRoot, EmptyMutation, EmptySubscription);
+    let new = Schema::new(v2::QueryRoot, EmptyMutation, EmptySubscription);
+    let changes = diff_registries(old.registry(), new.registry());
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| (change.criticality, change.kind, change.path.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Criticality::Breaking,
+                ChangeKind::EnumValueRemoved,
+                "Color.GREEN"
+            ),
+            (
+                Criticality::Dangerous,
+                ChangeKind::EnumValueAdded,
+                "Color.BLUE"
+            ),
+            (
+                Criticality::Safe,
+                ChangeKind::FieldTypeChanged,
+                "QueryRoot.color"
+            ),
+            (
+                Criticality::Breaking,
+                ChangeKind::FieldRemoved,
+                "QueryRoot.name"
+            ),
+            (
+                Criticality::Dangerous,
+                ChangeKind::ArgumentAdded,
+                "QueryRoot.value(b:)"
+            ),
+            (Criticality::Safe, ChangeKind::FieldAdded, "QueryRoot.count"),
+        ]
+    );
+    assert!(diff_registries(old.registry(), old.registry()).is_empty());
+}
+
+#[async_std::test]
+pub async fn test_diff_sdl() {
+    let old = r#"
+        directive @auth(role: String) on FIELD_DEFINITION | OBJECT
+
+        interface Node {
+            id: ID!
+        }
+
+        type User implements Node {
+            id: ID!
+            name: String!
+            friends(first: Int = 10): [User!]!
+        }
+
+        type Group {
+            id: ID!
+        }
+
+        union SearchResult = User | Group
+
+        input UserFilter {
+            name: String
+        }
+
+        type Query {
+            users(filter: UserFilter): [User!]!
+            search: [SearchResult!]!
+        }
+    "#;
+
+    let new = r#"
+        directive @auth(role: String!) on FIELD_DEFINITION
+
+        interface Node {
+            id: ID!
+        }
+
+        type User {
+            id: ID!
+            name: String @deprecated(reason: "Use fullName")
+            fullName: String!
+            friends(first: Int = 20): [User!]!
+        }
+
+        extend type User implements Node
+
+        union SearchResult = User
+
+        input UserFilter {
+            name: String
+            age: Int!
+        }
+
+        type Query {
+            users(filter: UserFilter!): [User!]!
+            search: [SearchResult!]!
+        }
+    "#;
+
+    let changes = diff_sdl(old, new).unwrap();
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| (change.criticality, change.to_string()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Criticality::Breaking,
+                "Location \"OBJECT\" was removed from directive \"@auth\".".to_string()
+            ),
+            (
+                Criticality::Breaking,
+                "Argument \"@auth(role:)\" changed type from \"String\" to \"String!\".".to_string()
+            ),
+            (
+                Criticality::Breaking,
+                "Type \"Group\" was removed.".to_string()
+            ),
+            (
+                Criticality::Breaking,
+                "Argument \"Query.users(filter:)\" changed type from \"UserFilter\" to \"UserFilter!\"."
+                    .to_string()
+            ),
+            (
+                Criticality::Breaking,
+                "Member \"Group\" was removed from union \"SearchResult\".".to_string()
+            ),
+            (
+                Criticality::Dangerous,
+                "Argument \"User.friends(first:)\" changed default value from 10 to 20.".to_string()
+            ),
+            (
+                Criticality::Breaking,
+                "Field \"User.name\" changed type from \"String!\" to \"String\".".to_string()
+            ),
+            (
+                Criticality::Safe,
+                "Field \"User.name\" was deprecated.".to_string()
+            ),
+            (
+                Criticality::Safe,
+                "Field \"User.fullName\" was added.".to_string()
+            ),
+            (
+                Criticality::Breaking,
+                "Required input field \"UserFilter.age\" was added.".to_string()
+            ),
+        ]
+    );
+    assert!(changes.iter().any(SchemaChange::is_breaking));
+    assert!(diff_sdl(new, new).unwrap().is_empty());
+    assert!(diff_sdl(old, "type Query {").is_err());
+}
@@ -1,4 +1,6 @@
+use async_graphql::directive::{Directive, DirectiveArgs, DirectiveArgument};
 use async_graphql::*;
+use serde_json::json;
 
 #[async_std::test]
 pub async fn test_directive_skip() {
@@ -61,3 +63,138 @@ pub async fn test_directive_include() {
         })
     );
 }
+
+struct Uppercase;
+
+#[async_trait::async_trait]
+impl Directive for Uppercase {
+    fn name(&self) -> &'static str {
+        "uppercase"
+    }
+
+    async fn resolve(
+        &self,
+        _ctx: &Context<'_>,
+        _args: &DirectiveArgs<'_>,
+        value: serde_json::Value,
+    ) -> FieldResult<serde_json::Value> {
+        match value {
+            serde_json::Value::String(s) => Ok(s.to_uppercase().into()),
+            _ => Err("expected a string".into()),
+        }
+    }
+}
+
+struct FeatureFlag;
+
+#[async_trait::async_trait]
+impl Directive for FeatureFlag {
+    fn name(&self) -> &'static str {
+        "featureFlag"
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        Some("Includes the field only if the feature is enabled.")
+    }
+
+    fn args(&self) -> Vec<DirectiveArgument> {
+        vec![DirectiveArgument::new::<String>("name").description("Name of the feature.")]
+    }
+
+    async fn include(&self, _ctx: &Context<'_>, args: &DirectiveArgs<'_>) -> FieldResult<bool> {
+        Ok(args.get::<String>("name")? == "enabled")
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> String {
+        "abc".to_string()
+    }
+
+    async fn num(&self) -> Option<i32> {
+        Some(10)
+    }
+}
+
+fn schema() -> Schema<Query, EmptyMutation, EmptySubscription> {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .directive(Uppercase)
+        .directive(FeatureFlag)
+        .finish()
+}
+
+#[async_std::test]
+pub async fn test_custom_directive() {
+    let schema = schema();
+    let query = r#"query($feature: String!) {
+        a: value @uppercase
+        b: value @featureFlag(name: "enabled") @uppercase
+        c: value @featureFlag(name: $feature)
+        num @uppercase
+    }"#;
+    let resp = QueryBuilder::new(query)
+        .variables(Variables::parse_from_json(json!({"feature": "disabled"})).unwrap())
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(resp.data, json!({"a": "ABC", "b": "ABC", "num": null}));
+    assert_eq!(
+        resp.errors,
+        vec![Error::Query {
+            pos: Pos {
+                line: 5,
+                column: 13
+            },
+            path: Some(json!(["num"])),
+            err: QueryError::FieldError {
+                err: "expected a string".to_string(),
+                extended_error: None,
+            },
+        }]
+    );
+}
+
+#[async_std::test]
+pub async fn test_custom_directive_validation() {
+    let schema = schema();
+    assert!(schema.execute("{ value @unknown }").await.is_err());
+    assert!(schema.execute("{ value @featureFlag }").await.is_err());
+    assert!(schema
+        .execute("{ ... @uppercase { value } }")
+        .await
+        .is_err());
+}
+
+#[async_std::test]
+pub async fn test_custom_directive_introspection() {
+    let schema = schema();
+    let res = schema
+        .execute(
+            r#"{
+                __schema {
+                    directives {
+                        name
+                        description
+                        locations
+                        args { name description type { kind ofType { name } } }
+                    }
+                }
+            }"#,
+        )
+        .await
+        .unwrap();
+    let directives = res.data["__schema"]["directives"].as_array().unwrap();
+    assert!(directives.contains(&json!({
+        "name": "featureFlag",
+        "description": "Includes the field only if the feature is enabled.",
+        "locations": ["FIELD"],
+        "args": [{
+            "name": "name",
+            "description": "Name of the feature.",
+            "type": {"kind": "NON_NULL", "ofType": {"name": "String"}},
+        }],
+    })));
+}
@@ -0,0 +1,71 @@
+use async_graphql::extensions::Extension;
+use async_graphql::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[async_std::test]
+pub async fn test_document_cache() {
+    struct CountValidation(Arc<AtomicUsize>);
+
+    impl Extension for CountValidation {
+        fn validation_start(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self, n: i32) -> i32 {
+            n
+        }
+    }
+
+    let count = Arc::new(AtomicUsize::default());
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .document_cache(1)
+        .extension({
+            let count = count.clone();
+            move || CountValidation(count.clone())
+        })
+        .finish();
+
+    let query = "query($n: Int!) { value(n: $n) }";
+    for n in 0..3 {
+        assert_eq!(
+            QueryBuilder::new(query)
+                .variables(Variables::parse_from_json(serde_json::json!({ "n": n })).unwrap())
+                .execute(&schema)
+                .await
+                .unwrap()
+                .data,
+            serde_json::json!({ "value": n })
+        );
+    }
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+
+    // Evicts the first query from the cache
+    assert_eq!(
+        schema.execute("{ value(n: 10) }").await.unwrap().data,
+        serde_json::json!({ "value": 10 })
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 2);
+
+    assert!(schema.execute("{ value }").await.is_err());
+    assert!(schema.execute("{ value }").await.is_err());
+    assert_eq!(count.load(Ordering::SeqCst), 4);
+
+    assert_eq!(
+        schema.execute("{ value(n: 10) }").await.unwrap().data,
+        serde_json::json!({ "value": 10 })
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 4);
+
+    QueryBuilder::new(query)
+        .variables(Variables::parse_from_json(serde_json::json!({ "n": 1 })).unwrap())
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(count.load(Ordering::SeqCst), 5);
+}
@@ -0,0 +1,448 @@
+use async_graphql::dynamic::*;
+use async_graphql::{FieldError, Pos, QueryError, Value};
+
+struct Dog {
+    name: String,
+    barks: bool,
+}
+
+struct Cat {
+    name: String,
+    lives: i32,
+}
+
+fn pets_schema() -> Schema {
+    let node = Interface::new("Pet").field(InterfaceField::new("name", "String!"));
+
+    let dog = Object::new("Dog")
+        .implement("Pet")
+        .field(Field::new("name", "String!", |ctx| {
+            Box::pin(async move {
+                let dog = ctx.parent_value.downcast_ref::<Dog>().unwrap();
+                Ok(FieldValue::value(dog.name.clone()))
+            })
+        }))
+        .field(Field::new("barks", "Boolean!", |ctx| {
+            Box::pin(async move {
+                let dog = ctx.parent_value.downcast_ref::<Dog>().unwrap();
+                Ok(FieldValue::value(dog.barks))
+            })
+        }));
+
+    let cat = Object::new("Cat")
+        .implement("Pet")
+        .field(Field::new("name", "String!", |ctx| {
+            Box::pin(async move {
+                let cat = ctx.parent_value.downcast_ref::<Cat>().unwrap();
+                Ok(FieldValue::value(cat.name.clone()))
+            })
+        }))
+        .field(Field::new("lives", "Int!", |ctx| {
+            Box::pin(async move {
+                let cat = ctx.parent_value.downcast_ref::<Cat>().unwrap();
+                Ok(FieldValue::value(cat.lives))
+            })
+        }));
+
+    let search_result = Union::new("SearchResult")
+        .possible_type("Dog")
+        .possible_type("Cat");
+
+    let query = Object::new("Query")
+        .field(Field::new("pets", "[Pet!]!", |_| {
+            Box::pin(async move {
+                Ok(FieldValue::list(vec![
+                    FieldValue::owned_any(Dog {
+                        name: "Rex".to_string(),
+                        barks: true,
+                    })
+                    .with_type("Dog"),
+                    FieldValue::owned_any(Cat {
+                        name: "Tom".to_string(),
+                        lives: 9,
+                    })
+                    .with_type("Cat"),
+                ]))
+            })
+        }))
+        .field(Field::new("search", "[SearchResult!]!", |_| {
+            Box::pin(async move {
+                Ok(FieldValue::list(vec![FieldValue::owned_any(Cat {
+                    name: "Felix".to_string(),
+                    lives: 7,
+                })
+                .with_type("Cat")]))
+            })
+        }))
+        .field(Field::new("untyped", "Pet", |_| {
+            Box::pin(async move {
+                Ok(FieldValue::owned_any(Dog {
+                    name: "Rex".to_string(),
+                    barks: true,
+                }))
+            })
+        }));
+
+    SchemaBuilder::new("Query")
+        .register(node)
+        .register(dog)
+        .register(cat)
+        .register(search_result)
+        .register(query)
+        .build()
+        .unwrap()
+        .finish()
+}
+
+#[async_std::test]
+pub async fn test_dynamic_scalars_and_arguments() {
+    let color = Enum::new("Color")
+        .item("RED")
+        .item(EnumItem::new("GREEN").description("Green color"));
+
+    let filter = InputObject::new("Filter")
+        .field(InputValue::new("limit", "Int!").default_value(Value::Int(10.into())))
+        .field(InputValue::new("color", "Color"));
+
+    let query = Object::new("Query")
+        .field(
+            Field::new("add", "Int!", |ctx| {
+                Box::pin(async move {
+                    let a = ctx.arg::<i32>("a")?;
+                    let b = ctx.arg::<i32>("b")?;
+                    Ok(FieldValue::value(a + b))
+                })
+            })
+            .argument(InputValue::new("a", "Int!"))
+            .argument(InputValue::new("b", "Int!").default_value(Value::Int(1.into()))),
+        )
+        .field(
+            Field::new("filter", "String!", |ctx| {
+                Box::pin(async move { Ok(FieldValue::value(ctx.arg_value("filter")?.to_string())) })
+            })
+            .argument(InputValue::new("filter", "Filter!")),
+        )
+        .field(Field::new("colors", "[Color!]!", |_| {
+            Box::pin(async move { Ok(FieldValue::value(serde_json::json!(["RED", "GREEN"]))) })
+        }))
+        .field(Field::new("optional", "String", |_| {
+            Box::pin(async move { Ok(FieldValue::NULL) })
+        }));
+
+    let schema: Schema = SchemaBuilder::new("Query")
+        .register(color)
+        .register(filter)
+        .register(query)
+        .build()
+        .unwrap()
+        .finish();
+
+    let query = r#"{
+        a: add(a: 10, b: 20)
+        b: add(a: 10)
+        filter(filter: { color: RED })
+        colors
+        optional
+        __typename
+    }"#;
+    assert_eq!(
+        schema.execute(query).await.unwrap().data,
+        serde_json::json!({
+            "a": 30,
+            "b": 11,
+            "filter": "{color: RED, limit: 10}",
+            "colors": ["RED", "GREEN"],
+            "optional": null,
+            "__typename": "Query",
+        })
+    );
+
+    assert_eq!(
+        schema
+            .execute("{ add(a: \"abc\") }")
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Rule error"
+    );
+
+    let res = schema
+        .execute(
+            r#"{
+                color: __type(name: "Color") { enumValues { name description } }
+                query: __type(name: "Query") { fields { name args { name defaultValue } } }
+            }"#,
+        )
+        .await
+        .unwrap()
+        .data;
+    let mut enum_values = res["color"]["enumValues"].as_array().unwrap().clone();
+    enum_values.sort_by_key(|value| value["name"].as_str().unwrap().to_string());
+    assert_eq!(
+        enum_values,
+        vec![
+            serde_json::json!({ "name": "GREEN", "description": "Green color" }),
+            serde_json::json!({ "name": "RED", "description": null }),
+        ]
+    );
+    let add = res["query"]["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|field| field["name"] == "add")
+        .unwrap();
+    assert_eq!(
+        add["args"],
+        serde_json::json!([
+            { "name": "a", "defaultValue": null },
+            { "name": "b", "defaultValue": "1" },
+        ])
+    );
+}
+
+#[async_std::test]
+pub async fn test_dynamic_interface_and_union() {
+    let schema = pets_schema();
+
+    let query = r#"{
+        pets {
+            __typename
+            name
+            ... on Dog { barks }
+            ... on Cat { lives }
+        }
+        search {
+            ... on Pet { name }
+            ... on Cat { lives }
+        }
+    }"#;
+    assert_eq!(
+        schema.execute(query).await.unwrap().data,
+        serde_json::json!({
+            "pets": [
+                { "__typename": "Dog", "name": "Rex", "barks": true },
+                { "__typename": "Cat", "name": "Tom", "lives": 9 },
+            ],
+            "search": [
+                { "name": "Felix", "lives": 7 },
+            ],
+        })
+    );
+
+    let res = schema.execute("{ untyped { name } }").await.unwrap();
+    assert_eq!(res.data, serde_json::json!({ "untyped": null }));
+    assert_eq!(
+        res.errors,
+        vec![async_graphql::Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["untyped"])),
+            err: QueryError::FieldError {
+                err: "The value of the abstract type \"Pet\" must be created with `FieldValue::with_type` and one of its possible types.".to_string(),
+                extended_error: None,
+            },
+        }]
+    );
+}
+
+#[async_std::test]
+pub async fn test_dynamic_errors() {
+    let query = Object::new("Query")
+        .field(Field::new("value", "Int", |_| {
+            Box::pin(async move { Err(FieldError("Bad value".to_string(), None)) })
+        }))
+        .field(Field::new("required", "Int!", |_| {
+            Box::pin(async move { Ok(FieldValue::NULL) })
+        }));
+
+    let schema: Schema = SchemaBuilder::new("Query")
+        .register(query)
+        .build()
+        .unwrap()
+        .finish();
+
+    let res = schema.execute("{ value }").await.unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": null }));
+    assert_eq!(
+        res.errors,
+        vec![async_graphql::Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["value"])),
+            err: QueryError::FieldError {
+                err: "Bad value".to_string(),
+                extended_error: None,
+            },
+        }]
+    );
+
+    let res = schema.execute("{ required }").await.unwrap();
+    assert_eq!(res.data, serde_json::Value::Null);
+    assert_eq!(
+        res.errors,
+        vec![async_graphql::Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["required"])),
+            err: QueryError::FieldError {
+                err: "Expected a non-null value of type \"Int!\".".to_string(),
+                extended_error: None,
+            },
+        }]
+    );
+}
+
+#[async_std::test]
+pub async fn test_dynamic_mutation() {
+    let query = Object::new("Query").field(Field::new("value", "Int!", |ctx| {
+        Box::pin(async move {
+            Ok(FieldValue::value(
+                *ctx.ctx.data::<async_std::sync::Mutex<i32>>().lock().await,
+            ))
+        })
+    }));
+    let mutation = Object::new("Mutation").field(
+        Field::new("setValue", "Int!", |ctx| {
+            Box::pin(async move {
+                let value = ctx.arg::<i32>("value")?;
+                *ctx.ctx.data::<async_std::sync::Mutex<i32>>().lock().await = value;
+                Ok(FieldValue::value(value))
+            })
+        })
+        .argument(InputValue::new("value", "Int!")),
+    );
+
+    let schema: Schema = SchemaBuilder::new("Query")
+        .mutation("Mutation")
+        .register(query)
+        .register(mutation)
+        .build()
+        .unwrap()
+        .data(async_std::sync::Mutex::new(0))
+        .finish();
+
+    assert_eq!(
+        schema
+            .execute("mutation { setValue(value: 10) }")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "setValue": 10 })
+    );
+    assert_eq!(
+        schema.execute("{ value }").await.unwrap().data,
+        serde_json::json!({ "value": 10 })
+    );
+}
+
+#[async_std::test]
+pub async fn test_dynamic_introspection() {
+    let schema = pets_schema();
+    let res = schema
+        .execute(r#"{ __type(name: "Pet") { kind possibleTypes { name } } }"#)
+        .await
+        .unwrap()
+        .data;
+    let mut possible_types = res["__type"]["possibleTypes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ty| ty["name"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    possible_types.sort();
+    assert_eq!(res["__type"]["kind"], "INTERFACE");
+    assert_eq!(possible_types, vec!["Cat", "Dog"]);
+
+    assert!(schema.sdl().contains("type Dog implements Pet {"));
+}
+
+#[async_std::test]
+pub async fn test_dynamic_schema_errors() {
+    let field = || {
+        Field::new("value", "Int!", |_| {
+            Box::pin(async move { Ok(FieldValue::NULL) })
+        })
+    };
+
+    assert_eq!(
+        SchemaBuilder::new("Query")
+            .register(Object::new("Query").field(field()))
+            .register(Object::new("Query"))
+            .build()
+            .err(),
+        Some(SchemaError(
+            "Type \"Query\" is defined more than once.".to_string()
+        ))
+    );
+
+    assert_eq!(
+        SchemaBuilder::new("Query")
+            .register(Object::new("Query").field(Field::new("obj", "MyObj", |_| {
+                Box::pin(async move { Ok(FieldValue::NULL) })
+            })))
+            .build()
+            .err(),
+        Some(SchemaError(
+            "Type \"MyObj\" of field \"Query.obj\" is not defined.".to_string()
+        ))
+    );
+
+    assert_eq!(
+        SchemaBuilder::new("Query")
+            .register(
+                Object::new("Query").field(field().argument(InputValue::new("input", "Query")))
+            )
+            .build()
+            .err(),
+        Some(SchemaError(
+            "Type \"Query\" of argument \"input\" of field \"Query.value\" is not an input type."
+                .to_string()
+        ))
+    );
+
+    assert_eq!(
+        SchemaBuilder::new("Query")
+            .register(Object::new("Query").field(field()).implement("Node"))
+            .register(Interface::new("Node").field(InterfaceField::new("id", "ID!")))
+            .build()
+            .err(),
+        Some(SchemaError(
+            "Object \"Query\" must have the field \"id\" of type \"ID!\" of the interface \"Node\"."
+                .to_string()
+        ))
+    );
+
+    assert_eq!(
+        SchemaBuilder::new("Root")
+            .register(Object::new("Query").field(field()))
+            .build()
+            .err(),
+        Some(SchemaError(
+            "Query type \"Root\" is not an object.".to_string()
+        ))
+    );
+}
+
+#[async_std::test]
+pub async fn test_dynamic_schema_strings_are_reused() {
+    let description = || {
+        let schema = SchemaBuilder::new("Query")
+            .register(
+                Object::new("Query").field(
+                    Field::new("value", "Int!", |_| {
+                        Box::pin(async move { Ok(FieldValue::NULL) })
+                    })
+                    .description("The value"),
+                ),
+            )
+            .build()
+            .unwrap()
+            .finish();
+        match &schema.registry().types["Query"] {
+            async_graphql::registry::Type::Object { fields, .. } => fields["value"].description,
+            _ => None,
+        }
+        .unwrap()
+    };
+    let a = description();
+    let b = description();
+    assert_eq!(a, "The value");
+    assert!(std::ptr::eq(a, b));
+}
@@ -0,0 +1,264 @@
+use async_graphql::extensions::{Extension, NextExecute, NextResolve, ResolveId, ResolveInfo};
+use async_graphql::*;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    async fn user(&self, ctx: &Context<'_>) -> String {
+        ctx.data_opt::<String>()
+            .cloned()
+            .unwrap_or_else(|| "guest".to_string())
+    }
+}
+
+struct MyExtension;
+
+#[async_trait::async_trait]
+impl Extension for MyExtension {
+    async fn prepare_request(&self, mut request: QueryBuilder) -> Result<QueryBuilder> {
+        if request.query_source() == "{ alias }" {
+            request.set_query_source("{ value }");
+        }
+        Ok(request.data("admin".to_string()))
+    }
+
+    async fn execute(&self, next: NextExecute<'_>) -> Result<QueryResponse> {
+        let mut res = next.run().await?;
+        res.data["extra"] = "extra".into();
+        Ok(res)
+    }
+
+    async fn resolve(
+        &self,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        let value = next.run(info).await?;
+        if info.return_type == "Int!" {
+            Ok((value.as_i64().unwrap() * 2).into())
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+struct CachedResponse;
+
+#[async_trait::async_trait]
+impl Extension for CachedResponse {
+    async fn execute(&self, _next: NextExecute<'_>) -> Result<QueryResponse> {
+        Ok(QueryResponse {
+            data: serde_json::json!({ "value": 0 }),
+            errors: Vec::new(),
+            extensions: None,
+            cache_control: Default::default(),
+            http_headers: Default::default(),
+        })
+    }
+}
+
+#[async_std::test]
+pub async fn test_extension_hooks() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension(|| MyExtension)
+        .finish();
+
+    assert_eq!(
+        schema.execute("{ value user }").await.unwrap().data,
+        serde_json::json!({
+            "value": 20,
+            "user": "admin",
+            "extra": "extra",
+        })
+    );
+    assert_eq!(
+        schema.execute("{ alias }").await.unwrap().data,
+        serde_json::json!({
+            "value": 20,
+            "extra": "extra",
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_extension_short_circuit() {
+    let resolved = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    struct Query {
+        resolved: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            self.resolved
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+            10
+        }
+    }
+
+    let schema = Schema::build(
+        Query {
+            resolved: resolved.clone(),
+        },
+        EmptyMutation,
+        EmptySubscription,
+    )
+    .extension(|| CachedResponse)
+    .extension(|| MyExtension)
+    .finish();
+
+    assert_eq!(
+        schema.execute("{ value }").await.unwrap().data,
+        serde_json::json!({ "value": 0 })
+    );
+    assert!(!resolved.load(std::sync::atomic::Ordering::Relaxed));
+}
+
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Extension for Recorder {
+    fn parse_start(&self, _query_source: &str) {
+        self.0.lock().push("parse_start".to_string());
+    }
+
+    fn parse_end(&self) {
+        self.0.lock().push("parse_end".to_string());
+    }
+
+    fn validation_start(&self) {
+        self.0.lock().push("validation_start".to_string());
+    }
+
+    fn validation_end(&self) {
+        self.0.lock().push("validation_end".to_string());
+    }
+
+    fn execution_start(&self) {
+        self.0.lock().push("execution_start".to_string());
+    }
+
+    fn execution_end(&self) {
+        self.0.lock().push("execution_end".to_string());
+    }
+
+    fn resolve_field_start(&self, info: &ResolveInfo<'_>) {
+        self.0
+            .lock()
+            .push(format!("resolve_start {}", info.path_node));
+    }
+
+    fn resolve_field_end(&self, _resolve_id: ResolveId) {
+        self.0.lock().push("resolve_end".to_string());
+    }
+
+    fn error(&self, err: &Error) {
+        let event = match err {
+            Error::Query { err, .. } => format!("error {}", err),
+            _ => "error".to_string(),
+        };
+        self.0.lock().push(event);
+    }
+
+    fn field_error(&self, err: &Error) {
+        let event = match err {
+            Error::Query {
+                path: Some(path),
+                err,
+                ..
+            } => format!("field_error {} {}", path, err),
+            _ => "field_error".to_string(),
+        };
+        self.0.lock().push(event);
+    }
+}
+
+#[async_std::test]
+pub async fn test_extension_error() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> FieldResult<i32> {
+            Err("failed".into())
+        }
+
+        async fn slow(&self) -> i32 {
+            async_std::task::sleep(Duration::from_secs(10)).await;
+            10
+        }
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension({
+            let events = events.clone();
+            move || Recorder(events.clone())
+        })
+        .finish();
+    let take_events = || std::mem::take(&mut *events.lock());
+
+    assert!(schema.execute("{ value").await.is_err());
+    assert_eq!(take_events(), vec!["parse_start", "parse_end", "error"]);
+
+    assert!(schema.execute("{ unknown }").await.is_err());
+    assert_eq!(
+        take_events(),
+        vec![
+            "parse_start",
+            "parse_end",
+            "validation_start",
+            "validation_end",
+            "error"
+        ]
+    );
+
+    assert_eq!(schema.execute("{ value }").await.unwrap().errors.len(), 1);
+    assert_eq!(
+        take_events(),
+        vec![
+            "parse_start",
+            "parse_end",
+            "validation_start",
+            "validation_end",
+            "execution_start",
+            "resolve_start value",
+            "resolve_end",
+            "execution_end",
+            r#"field_error ["value"] Failed to resolve field: failed"#,
+        ]
+    );
+
+    assert_eq!(
+        QueryBuilder::new("{ slow }")
+            .timeout(Duration::from_millis(50))
+            .execute(&schema)
+            .await
+            .unwrap()
+            .errors
+            .len(),
+        1
+    );
+    assert_eq!(
+        take_events(),
+        vec![
+            "parse_start",
+            "parse_end",
+            "validation_start",
+            "validation_end",
+            "execution_start",
+            "resolve_start slow",
+            "resolve_end",
+            "execution_end",
+            r#"field_error ["slow"] Query timed out"#,
+        ]
+    );
+}
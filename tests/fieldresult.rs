@@ -21,39 +21,135 @@ pub async fn test_fieldresult() {
 
     let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
 
+    let resp = schema.execute("{ error }").await.unwrap();
+    assert_eq!(resp.data, serde_json::Value::Null);
     assert_eq!(
-        schema.execute("{ error }").await.unwrap_err(),
-        Error::Query {
+        resp.errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["error"])),
             err: QueryError::FieldError {
                 err: "TestError".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 
+    let resp = schema.execute("{ optError }").await.unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "optError": null }));
     assert_eq!(
-        schema.execute("{ optError }").await.unwrap_err(),
-        Error::Query {
+        resp.errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["optError"])),
             err: QueryError::FieldError {
                 err: "TestError".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 
+    let resp = schema.execute("{ vecError }").await.unwrap();
+    assert_eq!(resp.data, serde_json::Value::Null);
     assert_eq!(
-        schema.execute("{ vecError }").await.unwrap_err(),
-        Error::Query {
+        resp.errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["vecError", 1])),
             err: QueryError::FieldError {
                 err: "TestError".to_string(),
                 extended_error: None,
             },
+        }]
+    );
+}
+
+#[async_std::test]
+pub async fn test_partial_result() {
+    struct MyObj;
+
+    #[Object]
+    impl MyObj {
+        async fn value(&self) -> i32 {
+            10
+        }
+
+        async fn error(&self) -> FieldResult<i32> {
+            Err("TestError".into())
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            1
+        }
+
+        async fn opt_error(&self) -> FieldResult<Option<i32>> {
+            Err("TestError".into())
+        }
+
+        async fn obj(&self) -> Option<MyObj> {
+            Some(MyObj)
         }
+
+        async fn items(&self) -> Vec<Option<FieldResult<i32>>> {
+            vec![Some(Ok(1)), Some(Err("TestError".into())), Some(Ok(3))]
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+
+    let resp = schema
+        .execute("{ value optError obj { value error } }")
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.data,
+        serde_json::json!({
+            "value": 1,
+            "optError": null,
+            "obj": null,
+        })
+    );
+    assert_eq!(
+        resp.errors,
+        vec![
+            Error::Query {
+                pos: Pos { line: 1, column: 9 },
+                path: Some(serde_json::json!(["optError"])),
+                err: QueryError::FieldError {
+                    err: "TestError".to_string(),
+                    extended_error: None,
+                },
+            },
+            Error::Query {
+                pos: Pos {
+                    line: 1,
+                    column: 30
+                },
+                path: Some(serde_json::json!(["obj", "error"])),
+                err: QueryError::FieldError {
+                    err: "TestError".to_string(),
+                    extended_error: None,
+                },
+            }
+        ]
+    );
+
+    let resp = schema.execute("{ items }").await.unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "items": [1, null, 3] }));
+    assert_eq!(
+        resp.errors,
+        vec![Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["items", 1])),
+            err: QueryError::FieldError {
+                err: "TestError".to_string(),
+                extended_error: None,
+            },
+        }]
     );
 }
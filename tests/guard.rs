@@ -99,15 +99,16 @@ pub async fn test_guard() {
             .data(Role::Guest)
             .execute(&schema)
             .await
-            .unwrap_err(),
-        Error::Query {
+            .unwrap()
+            .errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 9 },
             path: Some(serde_json::json!(["obj", "value"])),
             err: QueryError::FieldError {
                 err: "Forbidden".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 
     let query = "{ value }";
@@ -129,15 +130,16 @@ pub async fn test_guard() {
             .data(Role::Guest)
             .execute(&schema)
             .await
-            .unwrap_err(),
-        Error::Query {
+            .unwrap()
+            .errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["value"])),
             err: QueryError::FieldError {
                 err: "Forbidden".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 
     assert_eq!(
@@ -226,8 +228,9 @@ pub async fn test_guard() {
             .data(Role::Guest)
             .execute(&schema)
             .await
-            .unwrap_err(),
-        Error::Query {
+            .unwrap()
+            .errors,
+        vec![Error::Query {
             pos: Pos {
                 line: 2,
                 column: 13
@@ -237,7 +240,7 @@ pub async fn test_guard() {
                 err: "Forbidden".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 }
 
@@ -270,15 +273,16 @@ pub async fn test_multiple_guards() {
             .data(Username("test".to_string()))
             .execute(&schema)
             .await
-            .unwrap_err(),
-        Error::Query {
+            .unwrap()
+            .errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["value"])),
             err: QueryError::FieldError {
                 err: "Forbidden".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 
     let query = "{ value }";
@@ -288,15 +292,16 @@ pub async fn test_multiple_guards() {
             .data(Username("test1".to_string()))
             .execute(&schema)
             .await
-            .unwrap_err(),
-        Error::Query {
+            .unwrap()
+            .errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["value"])),
             err: QueryError::FieldError {
                 err: "Forbidden".to_string(),
                 extended_error: None,
             },
-        }
+        }]
     );
 
     let query = "{ value }";
@@ -306,14 +311,295 @@ pub async fn test_multiple_guards() {
             .data(Username("test1".to_string()))
             .execute(&schema)
             .await
-            .unwrap_err(),
-        Error::Query {
+            .unwrap()
+            .errors,
+        vec![Error::Query {
             pos: Pos { line: 1, column: 3 },
             path: Some(serde_json::json!(["value"])),
             err: QueryError::FieldError {
                 err: "Forbidden".to_string(),
                 extended_error: None,
             },
+        }]
+    );
+}
+
+struct OwnerGuard {
+    id: ID,
+}
+
+#[async_trait::async_trait]
+impl Guard for OwnerGuard {
+    async fn check(&self, ctx: &Context<'_>) -> FieldResult<()> {
+        if ctx.data_opt::<Username>().map(|name| name.0.as_str()) == Some(self.id.as_str()) {
+            Ok(())
+        } else {
+            Err("Not the owner".into())
+        }
+    }
+}
+
+struct GuestGuard;
+
+#[async_trait::async_trait]
+impl Guard for GuestGuard {
+    async fn check(&self, ctx: &Context<'_>) -> FieldResult<()> {
+        if ctx.data_opt::<Role>() == Some(&Role::Guest) {
+            Ok(())
+        } else {
+            Err("Forbidden".into())
         }
+    }
+}
+
+#[async_std::test]
+pub async fn test_guard_combinators() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[field(guard(or(RoleGuard(role = "Role::Admin"), OwnerGuard(id = "id.clone()"))))]
+        async fn email(&self, id: ID) -> String {
+            format!("{}@example.com", id.as_str())
+        }
+
+        #[field(guard(not(GuestGuard)))]
+        async fn secret(&self) -> i32 {
+            42
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let execute = |query: &str, role: Role, username: &str| {
+        QueryBuilder::new(query)
+            .data(role)
+            .data(Username(username.to_string()))
+            .execute(&schema)
+    };
+    let error = |err: &str| {
+        vec![Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["email"])),
+            err: QueryError::FieldError {
+                err: err.to_string(),
+                extended_error: None,
+            },
+        }]
+    };
+
+    assert_eq!(
+        execute(r#"{ email(id: "alice") }"#, Role::Admin, "bob")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "email": "alice@example.com" })
+    );
+    assert_eq!(
+        execute(r#"{ email(id: "alice") }"#, Role::Guest, "alice")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "email": "alice@example.com" })
+    );
+    assert_eq!(
+        execute(r#"{ email(id: "alice") }"#, Role::Guest, "bob")
+            .await
+            .unwrap()
+            .errors,
+        error("Not the owner")
+    );
+
+    assert_eq!(
+        execute("{ secret }", Role::Admin, "bob")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "secret": 42 })
+    );
+    assert_eq!(
+        execute("{ secret }", Role::Guest, "bob")
+            .await
+            .unwrap()
+            .errors,
+        vec![Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["secret"])),
+            err: QueryError::FieldError {
+                err: "Forbidden".to_string(),
+                extended_error: None,
+            },
+        }]
+    );
+}
+
+#[async_std::test]
+pub async fn test_type_guard() {
+    #[SimpleObject(guard(RoleGuard(role = "Role::Admin")))]
+    struct MyObj {
+        value: i32,
+    }
+
+    #[Interface(
+        guard(UserGuard(username = r#""test""#)),
+        field(name = "value", type = "i32")
+    )]
+    struct MyInterface(MyObj);
+
+    struct Query;
+
+    #[Object(guard(RoleGuard(role = "Role::Admin")))]
+    impl Query {
+        async fn value(&self) -> i32 {
+            1
+        }
+
+        #[field(guard(UserGuard(username = r#""test""#)))]
+        async fn user_value(&self) -> i32 {
+            2
+        }
+
+        #[field(name = "obj")]
+        async fn obj(&self) -> MyObj {
+            MyObj { value: 3 }
+        }
+
+        #[field(name = "iface")]
+        async fn iface(&self) -> MyInterface {
+            MyObj { value: 4 }.into()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let execute = |query: &str, role: Role, username: &str| {
+        QueryBuilder::new(query)
+            .data(role)
+            .data(Username(username.to_string()))
+            .execute(&schema)
+    };
+    let forbidden = |path: serde_json::Value, column: usize| {
+        vec![Error::Query {
+            pos: Pos { line: 1, column },
+            path: Some(path),
+            err: QueryError::FieldError {
+                err: "Forbidden".to_string(),
+                extended_error: None,
+            },
+        }]
+    };
+
+    assert_eq!(
+        execute(
+            "{ value userValue obj { value } iface { value } }",
+            Role::Admin,
+            "test"
+        )
+        .await
+        .unwrap()
+        .data,
+        serde_json::json!({
+            "value": 1,
+            "userValue": 2,
+            "obj": { "value": 3 },
+            "iface": { "value": 4 },
+        })
+    );
+
+    assert_eq!(
+        execute("{ value }", Role::Guest, "test")
+            .await
+            .unwrap()
+            .errors,
+        forbidden(serde_json::json!(["value"]), 3)
+    );
+
+    // The guard of the type is checked before the guard of the field
+    assert_eq!(
+        execute("{ userValue }", Role::Admin, "test1")
+            .await
+            .unwrap()
+            .errors,
+        forbidden(serde_json::json!(["userValue"]), 3)
+    );
+    assert_eq!(
+        execute("{ userValue }", Role::Guest, "test")
+            .await
+            .unwrap()
+            .errors,
+        forbidden(serde_json::json!(["userValue"]), 3)
+    );
+    assert_eq!(
+        execute("{ iface { value } }", Role::Admin, "test1")
+            .await
+            .unwrap()
+            .errors,
+        forbidden(serde_json::json!(["iface", "value"]), 11)
+    );
+}
+
+#[async_std::test]
+pub async fn test_guard_before_arguments() {
+    struct Code(String);
+
+    #[Scalar]
+    impl ScalarType for Code {
+        fn type_name() -> &'static str {
+            "Code"
+        }
+
+        fn parse(value: &Value) -> Option<Self> {
+            match value {
+                Value::String(s) if s.len() == 4 => Some(Code(s.clone())),
+                _ => None,
+            }
+        }
+
+        fn is_valid(_value: &Value) -> bool {
+            true
+        }
+
+        fn to_json(&self) -> Result<serde_json::Value> {
+            Ok(self.0.clone().into())
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[field(guard(RoleGuard(role = "Role::Admin")))]
+        async fn code(&self, value: Code) -> String {
+            value.0
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let execute = |role: Role| {
+        QueryBuilder::new(r#"{ code(value: "abc") }"#)
+            .data(role)
+            .execute(&schema)
+    };
+
+    // The guard doesn't refer to the argument, it is checked before the argument is parsed.
+    assert_eq!(
+        execute(Role::Guest).await.unwrap().errors,
+        vec![Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["code"])),
+            err: QueryError::FieldError {
+                err: "Forbidden".to_string(),
+                extended_error: None,
+            },
+        }]
+    );
+    assert_eq!(
+        execute(Role::Admin).await.unwrap().errors,
+        vec![Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: None,
+            err: QueryError::ExpectedType {
+                expect: "Code!".to_string(),
+                actual: Value::String("abc".to_string()),
+            },
+        }]
     );
 }
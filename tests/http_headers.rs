@@ -0,0 +1,82 @@
+use async_graphql::http::{HeaderMap, HeaderValue};
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_http_headers() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn user_agent(&self, ctx: &Context<'_>) -> Option<String> {
+            ctx.http_headers()
+                .get("user-agent")
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        }
+
+        async fn cost(&self, ctx: &Context<'_>) -> FieldResult<i32> {
+            ctx.insert_http_header("x-request-cost", "1")?;
+            ctx.insert_http_header("x-request-cost", "10")?;
+            Ok(10)
+        }
+
+        async fn invalid(&self, ctx: &Context<'_>) -> FieldResult<i32> {
+            ctx.insert_http_header("x-invalid", "a\nb")?;
+            Ok(0)
+        }
+    }
+
+    struct Mutation;
+
+    #[Object]
+    impl Mutation {
+        async fn login(&self, ctx: &Context<'_>) -> FieldResult<bool> {
+            ctx.append_http_header("set-cookie", "session=1")?;
+            ctx.append_http_header("set-cookie", "theme=dark")?;
+            Ok(true)
+        }
+    }
+
+    let schema = Schema::new(Query, Mutation, EmptySubscription);
+
+    let mut headers = HeaderMap::new();
+    headers.insert("user-agent", HeaderValue::from_static("test"));
+    let resp = QueryBuilder::new("{ userAgent cost }")
+        .http_headers(headers)
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.data,
+        serde_json::json!({ "userAgent": "test", "cost": 10 })
+    );
+    assert_eq!(resp.http_headers.len(), 1);
+    assert_eq!(resp.http_headers["x-request-cost"], "10");
+
+    let resp = schema.execute("{ userAgent }").await.unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "userAgent": null }));
+    assert!(resp.http_headers.is_empty());
+
+    let resp = schema.execute("mutation { login }").await.unwrap();
+    assert_eq!(
+        resp.http_headers
+            .get_all("set-cookie")
+            .iter()
+            .collect::<Vec<_>>(),
+        vec!["session=1", "theme=dark"]
+    );
+
+    let resp = schema.execute("{ invalid }").await.unwrap();
+    assert_eq!(resp.errors.len(), 1);
+    assert!(resp.http_headers.is_empty());
+
+    let resp = BatchRequest::Batch(vec![
+        QueryBuilder::new("mutation { login }"),
+        QueryBuilder::new("{ cost }"),
+    ])
+    .execute(&schema)
+    .await;
+    let headers = resp.http_headers();
+    assert_eq!(headers.get_all("set-cookie").iter().count(), 2);
+    assert_eq!(headers["x-request-cost"], "10");
+}
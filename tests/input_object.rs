@@ -105,3 +105,129 @@ pub async fn test_inputobject_derive_and_item_attributes() {
         MyInputObject { real: 100 }
     );
 }
+
+#[async_std::test]
+pub async fn test_input_object_invalid_field() {
+    #[InputObject]
+    struct Size {
+        width: u8,
+        heights: Vec<Option<u8>>,
+    }
+
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn area(&self, size: Size) -> i32 {
+            size.width as i32 * size.heights.len() as i32
+        }
+    }
+
+    let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+    let resp = schema
+        .execute("{ area(size: { width: 10, heights: [1, null, 256] }) }")
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.errors[0].to_string(),
+        r#"Query error: Invalid value for type "Size!": field "heights": 256 is out of range, the value must be between 0 and 255."#
+    );
+}
+
+#[async_std::test]
+pub async fn test_input_object_validator() {
+    use async_graphql::validators::{Email, IntRange};
+
+    fn check_range(range: &DateRange) -> std::result::Result<(), String> {
+        if range.end_date > range.start_date {
+            Ok(())
+        } else {
+            Err("endDate must be after startDate".to_string())
+        }
+    }
+
+    #[InputObject(validator = "check_range")]
+    struct DateRange {
+        #[field(validator(Email))]
+        owner: String,
+        #[field(validator(IntRange(min = "0", max = "100")))]
+        start_date: i32,
+        end_date: i32,
+    }
+
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn days(&self, range: DateRange) -> String {
+            format!("{} {}", range.owner, range.end_date - range.start_date)
+        }
+    }
+
+    let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema
+            .execute(r#"{ days(range: { owner: "a@b.com", startDate: 10, endDate: 15 }) }"#)
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "days": "a@b.com 5" })
+    );
+
+    assert!(schema
+        .execute(r#"{ days(range: { owner: "a", startDate: 10, endDate: 15 }) }"#)
+        .await
+        .is_err());
+    assert!(schema
+        .execute(r#"{ days(range: { owner: "a@b.com", startDate: 200, endDate: 300 }) }"#)
+        .await
+        .is_err());
+
+    let resp = schema
+        .execute(r#"{ days(range: { owner: "a@b.com", startDate: 10, endDate: 5 }) }"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.errors[0].to_string(),
+        r#"Query error: Invalid value for type "DateRange!": endDate must be after startDate."#
+    );
+
+    let resp = QueryBuilder::new("query($range: DateRange!) { days(range: $range) }")
+        .variables(
+            Variables::parse_from_json(serde_json::json!({
+                "range": { "owner": "a@b.com", "startDate": 10, "endDate": 10 },
+            }))
+            .unwrap(),
+        )
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.errors[0].to_string(),
+        r#"Query error: Invalid value for type "DateRange!": endDate must be after startDate."#
+    );
+
+    let resp = QueryBuilder::new(
+        "query($owner: String!) { days(range: { owner: $owner, startDate: 10, endDate: 15 }) }",
+    )
+    .variables(Variables::parse_from_json(serde_json::json!({ "owner": "notanemail" })).unwrap())
+    .execute(&schema)
+    .await
+    .unwrap();
+    assert_eq!(
+        resp.errors[0].to_string(),
+        r#"Query error: Invalid value for type "DateRange!": field "owner": invalid email format."#
+    );
+
+    let resp = QueryBuilder::new("query($range: DateRange!) { days(range: $range) }")
+        .variables(
+            Variables::parse_from_json(serde_json::json!({
+                "range": { "owner": "a@b.com", "startDate": 200, "endDate": 300 },
+            }))
+            .unwrap(),
+        )
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(resp.errors.len(), 1);
+}
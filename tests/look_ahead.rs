@@ -0,0 +1,171 @@
+use async_graphql::*;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+#[async_std::test]
+pub async fn test_look_ahead() {
+    #[SimpleObject]
+    #[derive(Clone)]
+    struct Detail {
+        a: i32,
+        b: i32,
+    }
+
+    #[SimpleObject]
+    struct MyObj {
+        value: i32,
+        name: String,
+        detail: Detail,
+    }
+
+    struct Query {
+        selections: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[Object]
+    impl Query {
+        async fn obj(&self, ctx: &Context<'_>, n: i32) -> FieldResult<MyObj> {
+            let look_ahead = ctx.look_ahead();
+            let mut selections = self.selections.lock();
+            selections.push(format!(
+                "{} n={:?}",
+                look_ahead.name().unwrap(),
+                look_ahead.argument::<i32>("n")?
+            ));
+            for field in look_ahead.fields() {
+                selections.push(format!(
+                    "{} {}",
+                    field.alias().unwrap_or("-"),
+                    field.name().unwrap()
+                ));
+            }
+            let detail = look_ahead.field("detail");
+            let detail_fields = detail
+                .fields()
+                .iter()
+                .map(|field| field.name().unwrap().to_string())
+                .collect::<Vec<_>>();
+            selections.push(format!("detail {}", detail_fields.join(" ")));
+            selections.push(format!("unknown {}", look_ahead.field("unknown").exists()));
+            Ok(MyObj {
+                value: n,
+                name: "obj".to_string(),
+                detail: Detail { a: 1, b: 2 },
+            })
+        }
+    }
+
+    let selections = Arc::new(Mutex::new(Vec::new()));
+    let schema = Schema::new(
+        Query {
+            selections: selections.clone(),
+        },
+        EmptyMutation,
+        EmptySubscription,
+    );
+
+    let query = r#"
+        query($n: Int!, $skip: Boolean!) {
+            obj(n: $n) {
+                value
+                v: value
+                ...Fields
+                ... on MyObj {
+                    detail { a }
+                }
+                detail { b }
+                name @skip(if: $skip)
+            }
+        }
+
+        fragment Fields on MyObj {
+            value
+            detail @include(if: false) { a b }
+        }
+    "#;
+    QueryBuilder::new(query)
+        .variables(
+            Variables::parse_from_json(serde_json::json!({ "n": 10, "skip": true })).unwrap(),
+        )
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(
+        std::mem::take(&mut *selections.lock()),
+        vec![
+            "obj n=Some(10)",
+            "- value",
+            "v value",
+            "- detail",
+            "detail a b",
+            "unknown false",
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_look_ahead_argument_default() {
+    struct Item;
+
+    #[Object]
+    impl Item {
+        async fn children(&self, #[arg(default = "10")] first: i32) -> Vec<Item> {
+            (0..first).map(|_| Item).collect()
+        }
+    }
+
+    struct Query {
+        arguments: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[Object]
+    impl Query {
+        async fn items(
+            &self,
+            ctx: &Context<'_>,
+            #[arg(default = "3")] first: i32,
+        ) -> FieldResult<Vec<Item>> {
+            let look_ahead = ctx.look_ahead();
+            self.arguments.lock().push(format!(
+                "{:?} {:?} {:?} {:?}",
+                look_ahead.argument::<i32>("first")?,
+                look_ahead.argument::<i32>("unknown")?,
+                look_ahead.field("children").argument::<i32>("first")?,
+                look_ahead.field("unknown").argument::<i32>("first")?,
+            ));
+            Ok((0..first).map(|_| Item).collect())
+        }
+    }
+
+    let arguments = Arc::new(Mutex::new(Vec::new()));
+    let schema = Schema::new(
+        Query {
+            arguments: arguments.clone(),
+        },
+        EmptyMutation,
+        EmptySubscription,
+    );
+    schema
+        .execute("{ items(first: 1) { children { __typename } } }")
+        .await
+        .unwrap();
+    schema
+        .execute("{ items { children(first: 2) { __typename } } }")
+        .await
+        .unwrap();
+    let res = schema
+        .execute(
+            "query($a: Int, $b: Int = 4) { items(first: $a) { children(first: $b) { __typename } } }",
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.data["items"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        std::mem::take(&mut *arguments.lock()),
+        vec![
+            "Some(1) None Some(10) None",
+            "Some(3) None Some(2) None",
+            "Some(3) None Some(4) None"
+        ]
+    );
+}
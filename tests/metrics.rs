@@ -0,0 +1,69 @@
+use async_graphql::extensions::{Metrics, MetricsRegistry};
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_metrics() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+
+        async fn fail(&self) -> FieldResult<i32> {
+            Err("failed".into())
+        }
+    }
+
+    let metrics = MetricsRegistry::with_buckets(vec![1.0, f64::NAN, 0.5, f64::INFINITY]);
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension({
+            let metrics = metrics.clone();
+            move || Metrics::new(metrics.clone())
+        })
+        .finish();
+
+    let operation = |query: &str| QueryBuilder::new(query).operator_name("A").execute(&schema);
+    operation("query A { value }").await.unwrap();
+    operation("query A { a: value b: value }").await.unwrap();
+    schema.execute("{ value fail }").await.unwrap();
+    assert!(schema.execute("{ unknown }").await.is_err());
+
+    // The label is the operation of the document, not the name sent by the client.
+    QueryBuilder::new("{ value }")
+        .operator_name("B")
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert!(QueryBuilder::new("query C { unknown }")
+        .operator_name("C")
+        .execute(&schema)
+        .await
+        .is_err());
+
+    let text = metrics.render();
+    let lines = text.lines().collect::<Vec<_>>();
+    for line in &[
+        "# TYPE graphql_requests_total counter",
+        r#"graphql_requests_total{operation=""} 4"#,
+        r#"graphql_requests_total{operation="A"} 2"#,
+        r#"graphql_request_errors_total{operation=""} 3"#,
+        r#"graphql_request_errors_total{operation="A"} 0"#,
+        "# TYPE graphql_request_duration_seconds histogram",
+        r#"graphql_request_duration_seconds_bucket{operation="A",le="0.5"} 2"#,
+        r#"graphql_request_duration_seconds_bucket{operation="A",le="1"} 2"#,
+        r#"graphql_request_duration_seconds_bucket{operation="A",le="+Inf"} 2"#,
+        r#"graphql_request_duration_seconds_count{operation="A"} 2"#,
+        r#"graphql_field_resolves_total{parent_type="Query",field="value"} 5"#,
+        r#"graphql_field_resolves_total{parent_type="Query",field="fail"} 1"#,
+        r#"graphql_field_errors_total{parent_type="Query",field="value"} 0"#,
+        r#"graphql_field_errors_total{parent_type="Query",field="fail"} 1"#,
+        r#"graphql_field_duration_seconds_count{parent_type="Query",field="value"} 5"#,
+    ] {
+        assert!(lines.contains(line), "missing line: {}", line);
+    }
+    assert!(!text.contains(r#"operation="B""#));
+    assert!(!text.contains(r#"operation="C""#));
+    assert!(!text.contains("NaN"));
+}
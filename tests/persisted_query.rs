@@ -0,0 +1,70 @@
+use async_graphql::persisted_query::LruPersistedQueryStore;
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_persisted_query() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            100
+        }
+    }
+
+    // sha256("{ value }")
+    let hash = "854174ebed716fe24fd6659c30290aecd9bc1d17dc4f47939a1848a1b8ed3c6b";
+    let query = "{ value }";
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        QueryBuilder::new("")
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        QueryError::PersistedQueryNotSupported.into_error(Pos::default())
+    );
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .persisted_query_store(LruPersistedQueryStore::new(10))
+        .finish();
+
+    assert_eq!(
+        QueryBuilder::new("")
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        QueryError::PersistedQueryNotFound.into_error(Pos::default())
+    );
+
+    assert_eq!(
+        QueryBuilder::new("{ value value }")
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        QueryError::InvalidPersistedQueryHash.into_error(Pos::default())
+    );
+
+    assert_eq!(
+        QueryBuilder::new(query)
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "value": 100 })
+    );
+
+    assert_eq!(
+        QueryBuilder::new("")
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "value": 100 })
+    );
+}
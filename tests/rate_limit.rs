@@ -0,0 +1,98 @@
+use async_graphql::http::GQLResponse;
+use async_graphql::rate_limit::*;
+use async_graphql::*;
+use std::time::Duration;
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    #[field(complexity = 5)]
+    async fn expensive(&self) -> i32 {
+        10
+    }
+}
+
+#[async_std::test]
+pub async fn test_complexity_budget() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .complexity_budget(ComplexityBudget::new(
+            10,
+            Duration::from_secs(100),
+            MemoryTokenBucketStore::new(100),
+        ))
+        .finish();
+    let query =
+        |source: &str, key: &str| QueryBuilder::new(source).data(ClientKey(key.to_string()));
+
+    assert!(query("{ expensive }", "a").execute(&schema).await.is_ok());
+    assert!(query("{ expensive }", "a").execute(&schema).await.is_ok());
+
+    // The budget of "a" is exhausted, 6 tokens are refilled in 60 seconds.
+    let err = query("{ value expensive }", "a")
+        .execute(&schema)
+        .await
+        .unwrap_err();
+    match &err {
+        Error::Query {
+            err: QueryError::RateLimited { retry_after },
+            ..
+        } => assert!(
+            *retry_after > Duration::from_secs(59) && *retry_after <= Duration::from_secs(60)
+        ),
+        _ => panic!("expected a rate limit error"),
+    }
+    assert_eq!(
+        serde_json::to_value(GQLResponse(Err(err))).unwrap(),
+        serde_json::json!({
+            "errors": [{
+                "message": "Rate limit exceeded, retry after 60 seconds.",
+                "extensions": {
+                    "code": "RATE_LIMITED",
+                    "retryAfter": 60,
+                },
+            }]
+        })
+    );
+
+    // The clients have separate budgets, the requests without a client key are not limited.
+    assert!(query("{ value expensive }", "b")
+        .execute(&schema)
+        .await
+        .is_ok());
+    assert!(schema.execute("{ value expensive }").await.is_ok());
+
+    // A query more complex than the capacity is always rejected.
+    assert_eq!(
+        query("{ a: expensive b: expensive c: value }", "c")
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        Error::Query {
+            pos: Pos::default(),
+            path: None,
+            err: QueryError::TooComplex,
+        }
+    );
+}
+
+#[async_std::test]
+pub async fn test_token_bucket_refill() {
+    let store = MemoryTokenBucketStore::new(100);
+    let period = Duration::from_millis(200);
+
+    assert!(store.take("a", 10, 10, period).await.is_ok());
+    assert!(store.take("a", 1, 10, period).await.is_err());
+    async_std::task::sleep(Duration::from_millis(100)).await;
+    assert!(store.take("a", 4, 10, period).await.is_ok());
+
+    // The bucket of an evicted client is full when it is seen again.
+    let store = MemoryTokenBucketStore::new(1);
+    assert!(store.take("a", 10, 10, period).await.is_ok());
+    assert!(store.take("b", 10, 10, period).await.is_ok());
+    assert!(store.take("a", 10, 10, period).await.is_ok());
+}
@@ -0,0 +1,220 @@
+use async_graphql::http::GQLRequest;
+use async_graphql::persisted_query::LruPersistedQueryStore;
+use async_graphql::safelist::Safelist;
+use async_graphql::*;
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        100
+    }
+}
+
+const MANIFEST: &str = r#"{
+    "GetValue": "query GetValue { value }",
+    "e0f7a5c5e6a6a1a1bd1f2b1f6b4f8b5a4e0a1b3c4d5e6f708192a3b4c5d6e7f8": "{ a: value }"
+}"#;
+
+fn not_safelisted() -> Error {
+    Error::Query {
+        pos: Pos::default(),
+        path: None,
+        err: QueryError::NotSafelisted,
+    }
+}
+
+#[async_std::test]
+pub async fn test_safelist() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .safelist(Safelist::from_json(MANIFEST).unwrap())
+        .finish();
+
+    // by id
+    let res = QueryBuilder::new("")
+        .document_id("GetValue")
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": 100 }));
+
+    // the id takes precedence over the query source
+    let res = QueryBuilder::new("{ b: value }")
+        .document_id("GetValue")
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": 100 }));
+
+    // by the hash of an automatic persisted query
+    let res = QueryBuilder::new("")
+        .persisted_query_hash("e0f7a5c5e6a6a1a1bd1f2b1f6b4f8b5a4e0a1b3c4d5e6f708192a3b4c5d6e7f8")
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::json!({ "a": 100 }));
+
+    // by the query source
+    let res = schema.execute("query GetValue { value }").await.unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": 100 }));
+
+    // from an HTTP request
+    let request: GQLRequest =
+        serde_json::from_value(serde_json::json!({ "id": "GetValue" })).unwrap();
+    let res = request
+        .into_query_builder()
+        .await
+        .unwrap()
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": 100 }));
+
+    assert_eq!(
+        schema.execute("{ value }").await.unwrap_err(),
+        not_safelisted()
+    );
+    assert_eq!(
+        QueryBuilder::new("")
+            .document_id("Unknown")
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        not_safelisted()
+    );
+    assert_eq!(
+        schema
+            .execute("{ __schema { queryType { name } } }")
+            .await
+            .unwrap_err(),
+        not_safelisted()
+    );
+}
+
+#[async_std::test]
+pub async fn test_safelist_persisted_query() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .safelist(Safelist::from_json(MANIFEST).unwrap())
+        .persisted_query_store(LruPersistedQueryStore::new(10))
+        .finish();
+
+    // sha256("{ value }")
+    let hash = "854174ebed716fe24fd6659c30290aecd9bc1d17dc4f47939a1848a1b8ed3c6b";
+
+    // a document that is not safelisted is not registered
+    assert_eq!(
+        QueryBuilder::new("{ value }")
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        not_safelisted()
+    );
+    assert_eq!(
+        QueryBuilder::new("")
+            .persisted_query_hash(hash)
+            .execute(&schema)
+            .await
+            .unwrap_err(),
+        QueryError::PersistedQueryNotFound.into_error(Pos::default())
+    );
+}
+
+#[async_std::test]
+pub async fn test_safelist_introspection() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .safelist(
+            Safelist::new()
+                .document("GetValue", "query GetValue { value }")
+                .allow_introspection(),
+        )
+        .finish();
+
+    let res = schema
+        .execute(
+            r#"{
+                __type(name: "Query") { name }
+                ...Schema
+            }
+
+            fragment Schema on Query {
+                __schema { queryType { name } }
+            }"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        res.data,
+        serde_json::json!({
+            "__type": { "name": "Query" },
+            "__schema": { "queryType": { "name": "Query" } },
+        })
+    );
+
+    assert_eq!(
+        schema
+            .execute("{ __type(name: \"Query\") { name } value }")
+            .await
+            .unwrap_err(),
+        not_safelisted()
+    );
+    assert_eq!(
+        schema
+            .execute("{ ...A } fragment A on Query { ...A }")
+            .await
+            .unwrap_err(),
+        not_safelisted()
+    );
+}
+
+#[async_std::test]
+pub async fn test_safelist_subscription() {
+    struct Subscription;
+
+    #[Subscription]
+    impl Subscription {
+        async fn values(&self) -> impl futures::Stream<Item = i32> {
+            futures::stream::iter(0..3)
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, Subscription)
+        .safelist(
+            Safelist::new()
+                .document("Values", "subscription Values { values }")
+                .allow_introspection(),
+        )
+        .finish();
+
+    assert!(schema
+        .create_subscription_stream(
+            "subscription Values { values }",
+            None,
+            Default::default(),
+            None
+        )
+        .await
+        .is_ok());
+    assert_eq!(
+        schema
+            .create_subscription_stream("subscription { values }", None, Default::default(), None)
+            .await
+            .err()
+            .unwrap(),
+        not_safelisted()
+    );
+    assert_eq!(
+        schema
+            .create_subscription_stream(
+                "subscription { __typename }",
+                None,
+                Default::default(),
+                None
+            )
+            .await
+            .err()
+            .unwrap(),
+        not_safelisted()
+    );
+}
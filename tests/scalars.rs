@@ -51,3 +51,158 @@ test_scalars!(test_f64_scalar, f32, 10.5, 10.5);
 
 test_scalars!(test_i64_scalar, i64, 10, "10");
 test_scalars!(test_u64_scalar, u64, 10, "10");
+
+#[async_std::test]
+pub async fn test_numeric_range() {
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn int8(&self, value: i8) -> i8 {
+            value
+        }
+
+        async fn uint8(&self, value: u8) -> u8 {
+            value
+        }
+
+        async fn int32(&self, value: i32) -> i32 {
+            value
+        }
+
+        async fn uint32(&self, value: u32) -> u32 {
+            value
+        }
+
+        async fn int64(&self, value: i64) -> i64 {
+            value
+        }
+
+        async fn uint64(&self, value: u64) -> u64 {
+            value
+        }
+
+        async fn uint32_default(&self, #[arg(default = "4294967295")] value: u32) -> u32 {
+            value
+        }
+
+        async fn float32(&self, value: f32) -> f32 {
+            value
+        }
+
+        async fn float64(&self, value: f64) -> f64 {
+            value
+        }
+    }
+
+    let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+
+    let reason = |query: QueryBuilder| {
+        let schema = &schema;
+        async move {
+            match query.execute(schema).await.unwrap().errors.remove(0) {
+                Error::Query {
+                    err: QueryError::InvalidValue { reason, .. },
+                    ..
+                } => reason,
+                err => panic!("unexpected error: {:?}", err),
+            }
+        }
+    };
+
+    let query = QueryBuilder::new(
+        r#"query($a: Int64!, $b: Int64!, $c: Int64!, $d: Int!, $e: Int!, $f: Float!) {
+            int64(value: $a)
+            uint64(value: $b)
+            negative: int64(value: $c)
+            uint8(value: $d)
+            uint32(value: $e)
+            float64(value: $f)
+        }"#,
+    )
+    .variables(
+        Variables::parse_from_json(serde_json::json!({
+            "a": 3000000000i64,
+            "b": 18446744073709551615u64,
+            "c": -9223372036854775808i64,
+            "d": 255,
+            "e": 4294967295u32,
+            "f": 9007199254740993i64,
+        }))
+        .unwrap(),
+    );
+    assert_eq!(
+        query.execute(&schema).await.unwrap().data,
+        serde_json::json!({
+            "int64": "3000000000",
+            "uint64": "18446744073709551615",
+            "negative": "-9223372036854775808",
+            "uint8": 255,
+            "uint32": 4294967295u32,
+            "float64": 9007199254740992.0,
+        })
+    );
+
+    assert_eq!(
+        schema
+            .execute("{ int8(value: -128) int64(value: 3000000000) float32(value: 16777217) float64(value: 1e300) uint32Default }")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({
+            "int8": -128,
+            "int64": "3000000000",
+            "float32": 16777216.0,
+            "float64": 1e300,
+            "uint32Default": 4294967295u32,
+        })
+    );
+
+    let query = QueryBuilder::new("query($v: Int!) { uint8(value: $v) }")
+        .variables(Variables::parse_from_json(serde_json::json!({ "v": 300 })).unwrap());
+    assert_eq!(
+        reason(query).await,
+        "300 is out of range, the value must be between 0 and 255"
+    );
+
+    let query = QueryBuilder::new("query($v: Int!) { int32(value: $v) }")
+        .variables(Variables::parse_from_json(serde_json::json!({ "v": 3000000000i64 })).unwrap());
+    assert_eq!(
+        reason(query).await,
+        "3000000000 is out of range, the value must be between -2147483648 and 2147483647"
+    );
+
+    let query = QueryBuilder::new("query($v: Int64!) { int64(value: $v) }").variables(
+        Variables::parse_from_json(serde_json::json!({ "v": 18446744073709551615u64 })).unwrap(),
+    );
+    assert_eq!(
+        reason(query).await,
+        "18446744073709551615 is out of range, the value must be between -9223372036854775808 and 9223372036854775807"
+    );
+
+    let query = QueryBuilder::new("query($v: Int!) { uint8(value: $v) }").variables(
+        Variables::parse_from_json(serde_json::json!({ "v": 18446744073709551615u64 })).unwrap(),
+    );
+    assert_eq!(
+        reason(query).await,
+        "18446744073709551615 is out of range, the value must be between 0 and 255"
+    );
+
+    let query = QueryBuilder::new("{ int8(value: -129) }");
+    assert_eq!(
+        reason(query).await,
+        "-129 is out of range, the value must be between -128 and 127"
+    );
+
+    let query = QueryBuilder::new("{ uint64(value: -1) }");
+    assert_eq!(
+        reason(query).await,
+        "-1 is out of range, the value must be between 0 and 18446744073709551615"
+    );
+
+    let query = QueryBuilder::new("{ float32(value: 1e300) }");
+    assert_eq!(
+        reason(query).await,
+        "1e300 is out of range, the value must be between -3.4028235e38 and 3.4028235e38"
+    );
+}
@@ -0,0 +1,197 @@
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_sdl() {
+    #[SimpleObject]
+    struct Dog {
+        id: ID,
+        #[field(desc = "Name of the dog")]
+        name: String,
+    }
+
+    #[SimpleObject]
+    struct Cat {
+        id: ID,
+        #[field(desc = "Also known as \"nine\"")]
+        lives: i32,
+    }
+
+    #[Interface(field(name = "id", type = "ID"))]
+    struct Node(Dog, Cat);
+
+    #[Union]
+    struct Pet(Dog, Cat);
+
+    #[Enum(desc = "Size of a pet")]
+    enum Size {
+        Small,
+        #[item(deprecation = "Use \"SMALL\" instead")]
+        Tiny,
+        #[item(desc = "Very big")]
+        Large,
+    }
+
+    #[InputObject]
+    struct PetFilter {
+        #[field(default = "10")]
+        limit: i32,
+        size: Option<Size>,
+    }
+
+    struct Timestamp(i64);
+
+    #[Scalar]
+    impl ScalarType for Timestamp {
+        fn type_name() -> &'static str {
+            "Timestamp"
+        }
+
+        fn parse(value: &Value) -> Option<Self> {
+            match value {
+                Value::Int(n) => Some(Timestamp(n.as_i64()?)),
+                _ => None,
+            }
+        }
+
+        fn to_json(&self) -> Result<serde_json::Value> {
+            Ok(self.0.into())
+        }
+    }
+
+    struct Query;
+
+    #[Object(desc = "The query root")]
+    impl Query {
+        async fn pets(&self, filter: Option<PetFilter>) -> Vec<Pet> {
+            let _ = filter.map(|filter| (filter.limit, filter.size));
+            Vec::new()
+        }
+
+        async fn node(&self, #[arg(desc = "Id of the node")] id: ID) -> Option<Node> {
+            let _ = id;
+            None
+        }
+
+        #[field(deprecation = "No longer supported")]
+        async fn now(&self) -> Timestamp {
+            Timestamp(0)
+        }
+
+        async fn count(&self, #[arg(default = "1")] a: i32, b: Option<String>) -> i32 {
+            let _ = b;
+            a
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema.sdl(),
+        r#"schema {
+  query: Query
+}
+
+type Cat implements Node {
+  id: ID!
+  """
+  Also known as "nine"
+  """
+  lives: Int!
+}
+
+type Dog implements Node {
+  id: ID!
+  """Name of the dog"""
+  name: String!
+}
+
+interface Node {
+  id: ID!
+}
+
+union Pet = Cat | Dog
+
+input PetFilter {
+  limit: Int! = 10
+  size: Size
+}
+
+"""The query root"""
+type Query {
+  count(a: Int! = 1, b: String): Int!
+  node(
+    """Id of the node"""
+    id: ID!
+  ): Node
+  now: Timestamp! @deprecated(reason: "No longer supported")
+  pets(filter: PetFilter): [Pet!]!
+}
+
+"""Size of a pet"""
+enum Size {
+  """Very big"""
+  LARGE
+  SMALL
+  TINY @deprecated(reason: "Use \"SMALL\" instead")
+}
+
+scalar Timestamp
+"#
+    );
+}
+
+#[async_std::test]
+pub async fn test_sdl_mutation_and_custom_directive() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    struct Mutation;
+
+    #[Object]
+    impl Mutation {
+        async fn set_value(&self, value: i32) -> bool {
+            let _ = value;
+            true
+        }
+    }
+
+    struct Lowercase;
+
+    #[async_trait::async_trait]
+    impl directive::Directive for Lowercase {
+        fn name(&self) -> &'static str {
+            "lowercase"
+        }
+
+        fn args(&self) -> Vec<directive::DirectiveArgument> {
+            vec![directive::DirectiveArgument::new::<Option<bool>>("enabled")]
+        }
+    }
+
+    let schema = Schema::build(Query, Mutation, EmptySubscription)
+        .directive(Lowercase)
+        .finish();
+    assert_eq!(
+        schema.sdl(),
+        r#"schema {
+  query: Query
+  mutation: Mutation
+}
+
+directive @lowercase(enabled: Boolean) on FIELD
+
+type Mutation {
+  setValue(value: Int!): Boolean!
+}
+
+type Query {
+  value: Int!
+}
+"#
+    );
+}
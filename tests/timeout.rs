@@ -0,0 +1,127 @@
+use async_graphql::*;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    async fn slow(&self, ctx: &Context<'_>) -> i32 {
+        let _flag = DropFlag(ctx.data::<Arc<AtomicBool>>().clone());
+        async_std::task::sleep(Duration::from_millis(300)).await;
+        10
+    }
+
+    async fn nullable_slow(&self, ctx: &Context<'_>) -> Option<i32> {
+        let _flag = DropFlag(ctx.data::<Arc<AtomicBool>>().clone());
+        async_std::task::sleep(Duration::from_millis(300)).await;
+        Some(10)
+    }
+}
+
+fn timeout_error(line: usize, column: usize, field: &str) -> Error {
+    Error::Query {
+        pos: Pos { line, column },
+        path: Some(serde_json::json!([field])),
+        err: QueryError::Timeout,
+    }
+}
+
+#[async_std::test]
+pub async fn test_query_timeout() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(dropped.clone())
+        .finish();
+
+    // The values resolved in time are kept, the outstanding fields fail.
+    let res = QueryBuilder::new("{ value nullableSlow }")
+        .timeout(Duration::from_millis(50))
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.data,
+        serde_json::json!({ "value": 10, "nullableSlow": null })
+    );
+    assert_eq!(res.errors, vec![timeout_error(1, 9, "nullableSlow")]);
+    assert!(dropped.load(Ordering::SeqCst));
+
+    // The error of a non-null field is propagated to its parent.
+    let res = QueryBuilder::new("{ value slow }")
+        .timeout(Duration::from_millis(50))
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::Value::Null);
+    assert_eq!(res.errors, vec![timeout_error(1, 9, "slow")]);
+
+    let res = QueryBuilder::new("{ value }")
+        .timeout(Duration::from_millis(50))
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": 10 }));
+}
+
+#[async_std::test]
+pub async fn test_schema_timeout() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(Arc::new(AtomicBool::new(false)))
+        .timeout(Duration::from_millis(50))
+        .finish();
+
+    let res = schema.execute("{ slow }").await.unwrap();
+    assert_eq!(res.data, serde_json::Value::Null);
+    assert_eq!(res.errors, vec![timeout_error(1, 3, "slow")]);
+
+    // The timeout of the query overrides the default timeout of the schema.
+    let res = QueryBuilder::new("{ value slow }")
+        .timeout(Duration::from_secs(5))
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(res.data, serde_json::json!({ "value": 10, "slow": 10 }));
+}
+
+#[async_std::test]
+pub async fn test_stream_timeout() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(Arc::new(AtomicBool::new(false)))
+        .timeout(Duration::from_millis(50))
+        .finish();
+
+    let responses = QueryBuilder::new("{ slow }")
+        .execute_stream(&schema)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(responses.len(), 1);
+    match &responses[0] {
+        IncrementalResponse::Initial {
+            response: Err(err),
+            has_next: false,
+        } => assert_eq!(
+            err,
+            &Error::Query {
+                pos: Pos::default(),
+                path: None,
+                err: QueryError::Timeout,
+            }
+        ),
+        _ => panic!("expected a timeout error"),
+    }
+}
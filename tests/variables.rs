@@ -70,3 +70,82 @@ pub async fn test_variable_default_value() {
         })
     );
 }
+
+#[async_std::test]
+pub async fn test_variables_nested() {
+    #[InputObject]
+    struct Range {
+        #[field(default = "0")]
+        min: i32,
+        max: Option<i32>,
+    }
+
+    #[InputObject]
+    struct Filter {
+        name: Option<String>,
+        range: Range,
+        tags: Vec<Vec<String>>,
+    }
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        pub async fn search(&self, filter: Filter) -> String {
+            format!(
+                "{:?} {}..{:?} {:?}",
+                filter.name, filter.range.min, filter.range.max, filter.tags
+            )
+        }
+
+        pub async fn matrix(&self, value: Vec<Vec<Option<i32>>>) -> Vec<Vec<Option<i32>>> {
+            value
+        }
+    }
+
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let query = QueryBuilder::new(
+        r#"
+            query($name: String, $min: Int, $max: Int = 20, $tag: String!, $a: Int, $b: Int, $skip: Boolean!) {
+                search(filter: { name: $name, range: { min: $min, max: $max }, tags: [[$tag, "b"], [$tag]] })
+                matrix(value: [[$a, 1], [$b]])
+                skipped: matrix(value: [[1]]) @skip(if: $skip)
+            }
+        "#,
+    )
+    .variables(
+        Variables::parse_from_json(serde_json::json!({
+            "name": "abc",
+            "min": 10,
+            "tag": "a",
+            "a": 5,
+            "skip": true,
+        }))
+        .unwrap(),
+    );
+    let resp = query.execute(&schema).await.unwrap();
+    assert_eq!(
+        resp.data,
+        serde_json::json!({
+            "search": r#"Some("abc") 10..Some(20) [["a", "b"], ["a"]]"#,
+            "matrix": [[5, 1], [null]],
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+            query($min: Int, $max: Int) {
+                search(filter: { range: { min: $min, max: $max }, tags: [] })
+            }
+        "#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.data,
+        serde_json::json!({
+            "search": "None 0..None []",
+        })
+    );
+}
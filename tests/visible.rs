@@ -0,0 +1,173 @@
+use async_graphql::*;
+
+struct Internal;
+
+fn is_internal(data: &Data) -> bool {
+    data.get::<Internal>().is_some()
+}
+
+#[async_std::test]
+pub async fn test_visible() {
+    #[SimpleObject(visible = "is_internal")]
+    struct Secret {
+        value: i32,
+    }
+
+    #[SimpleObject]
+    struct MyObj {
+        a: i32,
+        #[field(visible = "is_internal")]
+        b: i32,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn obj(&self) -> MyObj {
+            MyObj { a: 1, b: 2 }
+        }
+
+        async fn secret(&self) -> Secret {
+            Secret { value: 3 }
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let public = |query: &str| QueryBuilder::new(query).execute(&schema);
+    let internal = |query: &str| QueryBuilder::new(query).data(Internal).execute(&schema);
+
+    assert_eq!(
+        internal("{ obj { a b } secret { value } }")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({
+            "obj": { "a": 1, "b": 2 },
+            "secret": { "value": 3 },
+        })
+    );
+    assert_eq!(
+        public("{ obj { a } }").await.unwrap().data,
+        serde_json::json!({ "obj": { "a": 1 } })
+    );
+
+    let rule_error = |err: Error| match err {
+        Error::Rule { mut errors } if errors.len() == 1 => {
+            let err = errors.remove(0);
+            (err.locations, err.message)
+        }
+        _ => panic!("expected a rule error"),
+    };
+    assert_eq!(
+        rule_error(public("{ obj { a b } }").await.unwrap_err()),
+        (
+            vec![Pos {
+                line: 1,
+                column: 11
+            }],
+            r#"Unknown field "b" on type "MyObj"."#.to_string()
+        )
+    );
+    assert_eq!(
+        rule_error(public("{ secret { value } }").await.unwrap_err()),
+        (
+            vec![Pos { line: 1, column: 3 }],
+            r#"Unknown field "secret" on type "Query"."#.to_string()
+        )
+    );
+    assert!(public("{ ... on Secret { value } }").await.is_err());
+
+    let query = r#"{
+        obj: __type(name: "MyObj") { fields { name } }
+        secret: __type(name: "Secret") { name }
+        query: __type(name: "Query") { fields { name } }
+    }"#;
+    assert_eq!(
+        internal(query).await.unwrap().data,
+        serde_json::json!({
+            "obj": { "fields": [{ "name": "a" }, { "name": "b" }] },
+            "secret": { "name": "Secret" },
+            "query": { "fields": [{ "name": "obj" }, { "name": "secret" }] },
+        })
+    );
+    assert_eq!(
+        public(query).await.unwrap().data,
+        serde_json::json!({
+            "obj": { "fields": [{ "name": "a" }] },
+            "secret": null,
+            "query": { "fields": [{ "name": "obj" }] },
+        })
+    );
+
+    let types = public("{ __schema { types { name } } }")
+        .await
+        .unwrap()
+        .data;
+    assert!(!types["__schema"]["types"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({ "name": "Secret" })));
+}
+
+#[async_std::test]
+pub async fn test_visible_interface() {
+    #[SimpleObject]
+    struct MyObj {
+        a: i32,
+        #[field(visible = "is_internal")]
+        b: i32,
+        c: i32,
+    }
+
+    #[Interface(
+        field(name = "a", type = "i32"),
+        field(name = "b", type = "i32"),
+        field(name = "c", type = "i32", visible = "is_internal")
+    )]
+    struct Node(MyObj);
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn node(&self) -> Node {
+            MyObj { a: 1, b: 2, c: 3 }.into()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let public = |query: &str| QueryBuilder::new(query).execute(&schema);
+    let internal = |query: &str| QueryBuilder::new(query).data(Internal).execute(&schema);
+
+    assert_eq!(
+        internal("{ node { a b c } }").await.unwrap().data,
+        serde_json::json!({ "node": { "a": 1, "b": 2, "c": 3 } })
+    );
+    assert_eq!(
+        public("{ node { a } }").await.unwrap().data,
+        serde_json::json!({ "node": { "a": 1 } })
+    );
+    assert!(public("{ node { b } }").await.is_err());
+    assert!(public("{ node { c } }").await.is_err());
+    assert!(public("{ node { ... on MyObj { b } } }").await.is_err());
+    assert_eq!(
+        public("{ node { ... on MyObj { c } } }")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({ "node": { "c": 3 } })
+    );
+
+    let query = r#"{ __type(name: "Node") { fields { name } } }"#;
+    assert_eq!(
+        internal(query).await.unwrap().data,
+        serde_json::json!({
+            "__type": { "fields": [{ "name": "a" }, { "name": "b" }, { "name": "c" }] }
+        })
+    );
+    assert_eq!(
+        public(query).await.unwrap().data,
+        serde_json::json!({ "__type": { "fields": [{ "name": "a" }] } })
+    );
+}